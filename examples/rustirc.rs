@@ -4,11 +4,13 @@ extern crate env_logger;
 extern crate irc;
 
 use std::net::TcpStream;
-use std::io::{self, Write, BufRead, BufReader};
+use std::io::{self, BufRead, BufReader};
 use std::thread;
 use std::env;
 
-use irc::protocol::message::{IrcMessage, Message, Body};
+use irc::protocol::codec::{CodecError, MessageReader, MessageWriter};
+use irc::protocol::encoding::{Encoding, LegacyEncoding};
+use irc::protocol::message::{Message, Body};
 use irc::protocol::command::{Command, UserCommand, NickCommand, JoinCommand, PongCommand, PrivmsgCommand, QuitCommand};
 use irc::protocol::reply::ReplyCode;
 
@@ -21,8 +23,11 @@ fn main() {
     println!("Logging to {:?} as {:?}", server_addr, username);
 
     info!("Connecting to {:?}", server_addr);
-    let mut stream = TcpStream::connect(server_addr).unwrap();
-    let bufreader = BufReader::new(stream.try_clone().unwrap());
+    let stream = TcpStream::connect(server_addr).unwrap();
+    // Some networks still send Latin-1
+    let mut reader = MessageReader::with_encoding(stream.try_clone().unwrap(),
+                                                  Encoding::Utf8WithFallback(LegacyEncoding::Latin1));
+    let mut writer = MessageWriter::new(stream.try_clone().unwrap());
 
     let nickname = &username[..];
 
@@ -33,20 +38,27 @@ fn main() {
         let nickmsg = Message::new(None, Body::command(nickcmd));
 
         info!("Authorizing: sending {:?}", usermsg);
-        write!(stream, "{}\r\n", usermsg).unwrap();
+        writer.write_message(&usermsg).unwrap();
 
         info!("Authorizing: sending {:?}", nickmsg);
-        write!(stream, "{}\r\n", nickmsg).unwrap();
+        writer.write_message(&nickmsg).unwrap();
     }
 
-    let mut lines = bufreader.lines();
-
     {
         // Waiting for MOTD_START
-        while let Some(line) = lines.next() {
-            let line = line.unwrap();
-
-            let parsed = Message::from_str(line.trim()).unwrap();
+        loop {
+            let parsed = match reader.read_message() {
+                Ok(Some(parsed)) => parsed,
+                Ok(None) => return,
+                Err(CodecError::Io(err)) => {
+                    error!("Connection failed: {}", err);
+                    return;
+                },
+                Err(err) => {
+                    warn!("Failed to read message: {}", err);
+                    continue;
+                }
+            };
             trace!("{:?}", parsed);
 
             if let &Body::Reply(ref rpl) = parsed.body() {
@@ -65,10 +77,19 @@ fn main() {
         }
 
         // Printing MOTD
-        while let Some(line) = lines.next() {
-            let line = line.unwrap();
-
-            let parsed = Message::from_str(line.trim()).unwrap();
+        loop {
+            let parsed = match reader.read_message() {
+                Ok(Some(parsed)) => parsed,
+                Ok(None) => return,
+                Err(CodecError::Io(err)) => {
+                    error!("Connection failed: {}", err);
+                    return;
+                },
+                Err(err) => {
+                    warn!("Failed to read message: {}", err);
+                    continue;
+                }
+            };
             trace!("{:?}", parsed);
 
             if let &Body::Reply(ref rpl) = parsed.body() {
@@ -94,10 +115,10 @@ fn main() {
         let joinmsg = Message::new(None, Body::command(joincmd));
 
         info!("Join channel: Sending {:?}", joinmsg);
-        write!(stream, "{}\r\n", joinmsg).unwrap();
+        writer.write_message(&joinmsg).unwrap();
     }
 
-    let mut writeable_stream = stream.try_clone().unwrap();
+    let mut stdin_writer = MessageWriter::new(stream.try_clone().unwrap());
     let cloned_username = username.clone();
     thread::spawn(move|| {
         let bufstdin = BufReader::new(io::stdin());
@@ -116,14 +137,14 @@ fn main() {
                             let quitcmd = QuitCommand::new(sp.next());
                             let msg = Message::new(None, Body::command(quitcmd));
                             trace!("Quit: Sending {:?}", msg);
-                            write!(writeable_stream, "{}\r\n", msg).unwrap();
+                            stdin_writer.write_message(&msg).unwrap();
                         },
                         _ => {
                             let privmsg = PrivmsgCommand::new("#rust", msg);
                             println!("-*- < {} > {}", cloned_username, msg);
                             let msg = Message::new(None, Body::command(privmsg));
                             trace!("Privmsg: Sending {:?}", msg);
-                            write!(writeable_stream, "{}\r\n", msg).unwrap();
+                            stdin_writer.write_message(&msg).unwrap();
                         }
                     }
                 } else {
@@ -131,28 +152,33 @@ fn main() {
                     println!("-*- < {} > {}", cloned_username, msg);
                     let msg = Message::new(None, Body::command(privmsg));
                     trace!("Privmsg: Sending {:?}", msg);
-                    write!(writeable_stream, "{}\r\n", msg).unwrap();
+                    stdin_writer.write_message(&msg).unwrap();
                 }
             }
         }
     });
 
     // Prints conversation
-    for line in lines {
-        let line = line.unwrap();
-
-        let parsed = Message::from_str(line.trim()).unwrap();
+    loop {
+        let parsed = match reader.read_message() {
+            Ok(Some(parsed)) => parsed,
+            Ok(None) => break,
+            Err(CodecError::Io(err)) => {
+                error!("Connection failed: {}", err);
+                break;
+            },
+            Err(err) => {
+                // The line could not be parsed, the next one may be fine
+                warn!("Failed to read message: {}", err);
+                continue;
+            }
+        };
         trace!("{:?}", parsed);
 
         if let &Body::Command(ref cmd) = parsed.body() {
             match cmd {
                 &Command::Privmsg(ref privmsg) => {
-                    let prefix = parsed.prefix().unwrap();
-
-                    let name = match prefix.find('!') {
-                        None => prefix,
-                        Some(idx) => &prefix[..idx],
-                    };
+                    let name = parsed.nick().or(parsed.prefix()).unwrap_or("");
 
                     println!("-*- < {} > {}", name, privmsg.message());
                 },
@@ -160,7 +186,7 @@ fn main() {
                     debug!("Received ping {:?}", ping);
                     let pong = PongCommand::new(ping.servers().get());
                     debug!("Sending pong {:?}", pong);
-                    write!(stream, "{}\r\n", Message::new(None, Body::command(pong))).unwrap();
+                    writer.write_message(&Message::new(None, Body::command(pong))).unwrap();
                 },
                 _ => {}
             }
@@ -0,0 +1,281 @@
+//! Client side of the capability negotiation
+//!
+//! The negotiation starts with `CAP LS 302` before `NICK` and `USER`, the
+//! server then holds the registration until `CAP END`.
+
+use protocol::codec::MAX_MESSAGE_LENGTH;
+use protocol::command::Command;
+use protocol::command::cap::{CapCommand, CapSubCommand};
+use protocol::message::{Message, Body};
+use protocol::reply::ReplyCode;
+
+/// Requests the wanted capabilities which the server supports
+///
+/// Feed every message received during the registration to `handle_message`
+/// and send back the commands it returns.
+///
+/// ```
+/// use irc::client::cap::CapNegotiator;
+/// use irc::protocol::IrcMessage;
+/// use irc::protocol::command::CapCommand;
+///
+/// let mut negotiator = CapNegotiator::new(&["multi-prefix", "sasl"]);
+/// assert_eq!(&negotiator.start().to_string()[..], "CAP LS 302");
+///
+/// let ls = CapCommand::from_str("CAP * LS :multi-prefix server-time").unwrap();
+/// let req = negotiator.handle(&ls);
+/// assert_eq!(&req[0].to_string()[..], "CAP REQ multi-prefix");
+///
+/// let ack = CapCommand::from_str("CAP * ACK :multi-prefix").unwrap();
+/// assert_eq!(&negotiator.handle(&ack)[0].to_string()[..], "CAP END");
+/// assert!(negotiator.is_done());
+/// assert!(negotiator.is_enabled("multi-prefix"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CapNegotiator {
+    wanted: Vec<String>,
+    available: Vec<(String, Option<String>)>,
+    enabled: Vec<String>,
+    listed: bool,
+    pending: usize,
+    done: bool,
+}
+
+impl CapNegotiator {
+    pub fn new(wanted: &[&str]) -> CapNegotiator {
+        CapNegotiator {
+            wanted: wanted.iter().map(|c| (*c).to_owned()).collect(),
+            available: Vec::new(),
+            enabled: Vec::new(),
+            listed: false,
+            pending: 0,
+            done: false,
+        }
+    }
+
+    /// Add a wanted capability, before the server lists them
+    pub fn want(&mut self, name: &str) {
+        if !self.wanted.iter().any(|n| n == name) {
+            self.wanted.push(name.to_owned());
+        }
+    }
+
+    /// The first command to send, before `NICK` and `USER`
+    pub fn start(&self) -> CapCommand<'static> {
+        CapCommand::new(CapSubCommand::Ls, Some("302"))
+    }
+
+    /// Whether the negotiation is over
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    pub fn is_available(&self, name: &str) -> bool {
+        self.available.iter().any(|&(ref n, _)| n == name)
+    }
+
+    /// Value advertised by the server, such as the mechanisms of `sasl`
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.available.iter()
+            .find(|&&(ref n, _)| n == name)
+            .and_then(|&(_, ref v)| v.as_ref().map(|v| &v[..]))
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.iter().any(|n| n == name)
+    }
+
+    /// Capabilities enabled by the server
+    pub fn enabled(&self) -> &[String] {
+        &self.enabled
+    }
+
+    /// Handle a message received during the registration
+    ///
+    /// A welcome or an unknown `CAP` command ends the negotiation, the
+    /// server doesn't hold the registration then.
+    pub fn handle_message(&mut self, msg: &Message) -> Vec<CapCommand<'static>> {
+        match *msg.body() {
+            Body::Command(Command::Cap(ref cap)) => self.handle(cap),
+            Body::Reply(ref reply) => {
+                match reply.code() {
+                    ReplyCode::RPL_WELCOME => self.done = true,
+                    ReplyCode::ERR_UNKNOWNCOMMAND if reply.parameters().next() == Some("CAP") => self.done = true,
+                    _ => {},
+                }
+                Vec::new()
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Handle a `CAP` command sent by the server
+    pub fn handle(&mut self, cmd: &CapCommand) -> Vec<CapCommand<'static>> {
+        match cmd.subcommand() {
+            CapSubCommand::Ls | CapSubCommand::New => {
+                for cap in cmd.capabilities() {
+                    if !self.is_available(cap.name()) {
+                        self.available.push((cap.name().to_owned(), cap.value().map(str::to_owned)));
+                    }
+                }
+
+                if cmd.subcommand() == CapSubCommand::Ls && (cmd.has_more() || self.listed) {
+                    return Vec::new();
+                }
+                self.listed = true;
+
+                let requests = self.requests();
+                if requests.is_empty() {
+                    self.finish()
+                } else {
+                    self.pending += requests.len();
+                    requests
+                }
+            },
+            CapSubCommand::Ack | CapSubCommand::Nak => {
+                if cmd.subcommand() == CapSubCommand::Ack {
+                    for cap in cmd.capabilities() {
+                        if cap.is_removed() {
+                            self.enabled.retain(|n| n != cap.name());
+                        } else if !self.is_enabled(cap.name()) {
+                            self.enabled.push(cap.name().to_owned());
+                        }
+                    }
+                }
+
+                self.pending = self.pending.saturating_sub(1);
+                if self.pending == 0 {
+                    self.finish()
+                } else {
+                    Vec::new()
+                }
+            },
+            CapSubCommand::Del => {
+                for cap in cmd.capabilities() {
+                    self.available.retain(|&(ref n, _)| n != cap.name());
+                    self.enabled.retain(|n| n != cap.name());
+                }
+                Vec::new()
+            },
+            CapSubCommand::List | CapSubCommand::Req | CapSubCommand::End => Vec::new(),
+        }
+    }
+
+    /// `CAP REQ` for the wanted capabilities available and not enabled yet,
+    /// split to fit in messages
+    fn requests(&self) -> Vec<CapCommand<'static>> {
+        let limit = MAX_MESSAGE_LENGTH - "CAP REQ :\r\n".len();
+        let mut requests = Vec::new();
+        let mut line = String::new();
+
+        for cap in &self.wanted {
+            if !self.is_available(cap) || self.is_enabled(cap) {
+                continue;
+            }
+
+            if !line.is_empty() && line.len() + 1 + cap.len() > limit {
+                requests.push(CapCommand::new(CapSubCommand::Req, Some(&line)).into_owned());
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(cap);
+        }
+
+        if !line.is_empty() {
+            requests.push(CapCommand::new(CapSubCommand::Req, Some(&line)).into_owned());
+        }
+        requests
+    }
+
+    fn finish(&mut self) -> Vec<CapCommand<'static>> {
+        if self.done {
+            return Vec::new();
+        }
+
+        self.done = true;
+        vec![CapCommand::new(CapSubCommand::End, None)]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::{IrcMessage, Message};
+    use protocol::command::cap::CapCommand;
+
+    fn handle(negotiator: &mut CapNegotiator, line: &str) -> Vec<String> {
+        negotiator.handle_message(&Message::from_str(line).unwrap()).iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_negotiation() {
+        let mut negotiator = CapNegotiator::new(&["multi-prefix", "sasl", "away-notify", "cap-notify"]);
+
+        assert!(handle(&mut negotiator, ":irc.example.com CAP * LS * :multi-prefix sasl=PLAIN,EXTERNAL").is_empty());
+        assert_eq!(handle(&mut negotiator, ":irc.example.com CAP * LS :away-notify server-time"),
+                   vec!["CAP REQ :multi-prefix sasl away-notify"]);
+        assert_eq!(negotiator.value("sasl"), Some("PLAIN,EXTERNAL"));
+
+        assert_eq!(handle(&mut negotiator, ":irc.example.com CAP * ACK :multi-prefix sasl away-notify"),
+                   vec!["CAP END"]);
+        assert!(negotiator.is_done());
+        assert_eq!(negotiator.enabled(), &["multi-prefix", "sasl", "away-notify"]);
+
+        assert_eq!(handle(&mut negotiator, ":irc.example.com CAP WiZ NEW :cap-notify"), vec!["CAP REQ cap-notify"]);
+        assert!(handle(&mut negotiator, ":irc.example.com CAP WiZ ACK :cap-notify").is_empty());
+        assert!(negotiator.is_enabled("cap-notify"));
+
+        assert!(handle(&mut negotiator, ":irc.example.com CAP WiZ DEL :sasl").is_empty());
+        assert!(!negotiator.is_enabled("sasl"));
+        assert!(!negotiator.is_available("sasl"));
+    }
+
+    #[test]
+    fn test_negotiation_nothing_wanted() {
+        let mut negotiator = CapNegotiator::new(&["sasl"]);
+        assert_eq!(handle(&mut negotiator, ":irc.example.com CAP * LS :multi-prefix"), vec!["CAP END"]);
+        assert!(negotiator.is_done());
+        assert!(negotiator.enabled().is_empty());
+    }
+
+    #[test]
+    fn test_negotiation_nak() {
+        let mut negotiator = CapNegotiator::new(&["sasl"]);
+        handle(&mut negotiator, ":irc.example.com CAP * LS :sasl");
+        assert_eq!(handle(&mut negotiator, ":irc.example.com CAP * NAK :sasl"), vec!["CAP END"]);
+        assert!(!negotiator.is_enabled("sasl"));
+    }
+
+    #[test]
+    fn test_negotiation_unsupported() {
+        let mut negotiator = CapNegotiator::new(&["sasl"]);
+        assert!(handle(&mut negotiator, ":irc.example.com 421 * CAP :Unknown command").is_empty());
+        assert!(negotiator.is_done());
+
+        let mut negotiator = CapNegotiator::new(&["sasl"]);
+        handle(&mut negotiator, ":irc.example.com 001 WiZ :Welcome");
+        assert!(negotiator.is_done());
+    }
+
+    #[test]
+    fn test_request_split() {
+        let caps: Vec<String> = (0..100).map(|i| format!("vendor.example/cap-{}", i)).collect();
+        let wanted: Vec<&str> = caps.iter().map(|c| &c[..]).collect();
+        let mut negotiator = CapNegotiator::new(&wanted);
+
+        let ls = CapCommand::reply("*", CapSubCommand::Ls, false, &caps.join(" ")).into_owned();
+        let requests = negotiator.handle(&ls);
+        assert!(requests.len() > 1);
+        for req in &requests {
+            assert!(req.to_string().len() + 2 <= MAX_MESSAGE_LENGTH);
+        }
+
+        for _ in 0..requests.len() - 1 {
+            assert!(negotiator.handle(&CapCommand::reply("*", CapSubCommand::Nak, false, "x")).is_empty());
+        }
+        assert_eq!(negotiator.handle(&CapCommand::reply("*", CapSubCommand::Nak, false, "x")).len(), 1);
+    }
+}
@@ -0,0 +1,380 @@
+//! TCP side of DCC file transfers and chats
+//!
+//! The offers themselves are in `protocol::dcc`. Whoever listens, the
+//! sender writes the file in blocks and the receiver acknowledges every
+//! block with the number of bytes received so far, as a 32 bits big-endian
+//! integer. The transfer is complete when the sender got the acknowledgement
+//! of the whole file.
+//!
+//! ```no_run
+//! use std::fs::File;
+//! use std::net::TcpStream;
+//!
+//! use irc::client::dcc::DccReceive;
+//!
+//! let mut stream = TcpStream::connect("192.168.1.1:5000").unwrap();
+//! let file = File::create("file.txt").unwrap();
+//! DccReceive::new(file, Some(1024)).run(&mut stream, |pos| println!("{} bytes", pos)).unwrap();
+//! ```
+
+use std::cmp;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+
+use protocol::dcc::DccMessage;
+
+const BLOCK_SIZE: usize = 8192;
+
+/// Listen on any free port of `address`, to offer a transfer or answer a
+/// passive offer. The port to announce is in `local_addr()`
+pub fn listen(address: IpAddr) -> io::Result<TcpListener> {
+    TcpListener::bind(SocketAddr::new(address, 0))
+}
+
+/// Connect to the address of an offer
+pub fn connect(offer: &DccMessage) -> io::Result<TcpStream> {
+    let address = match *offer {
+        DccMessage::Chat { address, port, .. } |
+        DccMessage::Send { address, port, .. } if port != 0 => SocketAddr::new(address, port),
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "DCC offer has no address to connect to")),
+    };
+
+    TcpStream::connect(address)
+}
+
+/// Acknowledgements read by the sender, which may arrive in pieces
+struct Acks {
+    buf: [u8; 4],
+    filled: usize,
+    last: Option<u32>,
+}
+
+impl Acks {
+    fn new() -> Acks {
+        Acks {
+            buf: [0; 4],
+            filled: 0,
+            last: None,
+        }
+    }
+
+    /// Read once from the stream, returns `false` at the end of the stream
+    fn read_from<S: Read>(&mut self, stream: &mut S) -> io::Result<bool> {
+        let n = match stream.read(&mut self.buf[self.filled..]) {
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => return Ok(true),
+            Err(e) => return Err(e),
+        };
+
+        if n == 0 {
+            return Ok(false);
+        }
+
+        self.filled += n;
+        if self.filled == self.buf.len() {
+            self.last = Some(((self.buf[0] as u32) << 24) | ((self.buf[1] as u32) << 16) |
+                             ((self.buf[2] as u32) << 8) | self.buf[3] as u32);
+            self.filled = 0;
+        }
+        Ok(true)
+    }
+
+    /// Read the acknowledgements already received without blocking
+    fn drain(&mut self, stream: &mut TcpStream) -> io::Result<()> {
+        try!(stream.set_nonblocking(true));
+        let result = loop {
+            match self.read_from(stream) {
+                Ok(true) => {},
+                Ok(false) => break Ok(()),
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        };
+        try!(stream.set_nonblocking(false));
+        result
+    }
+}
+
+fn encode_ack(position: u64) -> [u8; 4] {
+    let ack = position as u32;
+    [(ack >> 24) as u8, (ack >> 16) as u8, (ack >> 8) as u8, ack as u8]
+}
+
+/// Sending side of a `DCC SEND`
+pub struct DccSend<R> {
+    file: R,
+    size: u64,
+    position: u64,
+}
+
+impl<R: Read + Seek> DccSend<R> {
+    /// Send the first `size` bytes of `file`, the size announced in the offer
+    pub fn new(file: R, size: u64) -> DccSend<R> {
+        DccSend {
+            file: file,
+            size: size,
+            position: 0,
+        }
+    }
+
+    /// Start from `position`, after accepting a `DCC RESUME`
+    pub fn position(mut self, position: u64) -> DccSend<R> {
+        self.position = position;
+        self
+    }
+
+    /// Send the file and wait for the last acknowledgement
+    ///
+    /// `progress` is called with the position in the file after every
+    /// block. Returns the number of bytes sent.
+    pub fn run<F: FnMut(u64)>(mut self, stream: &mut TcpStream, mut progress: F) -> io::Result<u64> {
+        try!(self.file.seek(SeekFrom::Start(self.position)));
+
+        let mut buf = vec![0; BLOCK_SIZE];
+        let mut acks = Acks::new();
+        let mut pos = self.position;
+
+        while pos < self.size {
+            let len = cmp::min(buf.len() as u64, self.size - pos) as usize;
+            let n = match self.file.read(&mut buf[..len]) {
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "File is shorter than the offer")),
+                Ok(n) => n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            try!(stream.write_all(&buf[..n]));
+            pos += n as u64;
+            progress(pos);
+
+            // Don't let the acknowledgements fill the buffers of the connection
+            try!(acks.drain(stream));
+        }
+        try!(stream.flush());
+
+        // Positions are acknowledged modulo 2^32
+        let last = Some(self.size as u32);
+        while acks.last != last {
+            if !try!(acks.read_from(stream)) {
+                // Some clients close without acknowledging
+                break;
+            }
+        }
+
+        Ok(pos - self.position)
+    }
+}
+
+/// Receiving side of a `DCC SEND`
+pub struct DccReceive<W> {
+    file: W,
+    size: Option<u64>,
+    position: u64,
+}
+
+impl<W: Write> DccReceive<W> {
+    /// Receive into `file`, `size` is the size announced in the offer
+    pub fn new(file: W, size: Option<u64>) -> DccReceive<W> {
+        DccReceive {
+            file: file,
+            size: size,
+            position: 0,
+        }
+    }
+
+    /// Continue a partial file of `position` bytes, after the sender
+    /// answered `DCC ACCEPT`. The file should be opened for appending
+    pub fn position(mut self, position: u64) -> DccReceive<W> {
+        self.position = position;
+        self
+    }
+
+    /// Receive until the announced size or the end of the stream
+    ///
+    /// `progress` is called with the position in the file after every
+    /// block. Returns the number of bytes received.
+    pub fn run<S, F>(mut self, stream: &mut S, mut progress: F) -> io::Result<u64>
+        where S: Read + Write,
+              F: FnMut(u64)
+    {
+        let mut buf = vec![0; BLOCK_SIZE];
+        let mut pos = self.position;
+
+        loop {
+            let len = match self.size {
+                Some(size) if pos >= size => break,
+                Some(size) => cmp::min(buf.len() as u64, size - pos) as usize,
+                None => buf.len(),
+            };
+
+            let n = match stream.read(&mut buf[..len]) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+
+            try!(self.file.write_all(&buf[..n]));
+            pos += n as u64;
+            try!(stream.write_all(&encode_ack(pos)));
+            progress(pos);
+        }
+        try!(self.file.flush());
+
+        match self.size {
+            Some(size) if pos < size => {
+                Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Connection closed before the end of the file"))
+            },
+            _ => Ok(pos - self.position),
+        }
+    }
+}
+
+/// A `DCC CHAT` connection, lines of text without any IRC framing
+pub struct DccChat {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl DccChat {
+    pub fn new(stream: TcpStream) -> io::Result<DccChat> {
+        let writer = try!(stream.try_clone());
+        Ok(DccChat {
+            reader: BufReader::new(stream),
+            writer: writer,
+        })
+    }
+
+    /// Read a line without its line ending, `None` at the end of the chat
+    pub fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if try!(self.reader.read_line(&mut line)) == 0 {
+            return Ok(None);
+        }
+
+        while line.ends_with('\n') || line.ends_with('\r') {
+            line.pop();
+        }
+        Ok(Some(line))
+    }
+
+    pub fn send_line(&mut self, line: &str) -> io::Result<()> {
+        try!(self.writer.write_all(line.as_bytes()));
+        try!(self.writer.write_all(b"\n"));
+        self.writer.flush()
+    }
+
+    pub fn get_ref(&self) -> &TcpStream {
+        &self.writer
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::io::Cursor;
+    use std::net::{IpAddr, Ipv4Addr, Shutdown};
+    use std::thread;
+
+    use protocol::dcc::DccMessage;
+
+    fn localhost() -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))
+    }
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_dcc_send_receive() {
+        let content = data(100000);
+        let listener = listen(localhost()).unwrap();
+        let offer = DccMessage::send("file", localhost(), listener.local_addr().unwrap().port(),
+                                     Some(content.len() as u64));
+
+        let to_send = content.clone();
+        let sender = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let size = to_send.len() as u64;
+            DccSend::new(Cursor::new(to_send), size).run(&mut stream, |_| {}).unwrap()
+        });
+
+        let mut stream = connect(&offer).unwrap();
+        let mut received = Vec::new();
+        let mut positions = Vec::new();
+        let n = DccReceive::new(&mut received, Some(content.len() as u64))
+            .run(&mut stream, |pos| positions.push(pos)).unwrap();
+
+        assert_eq!(n, content.len() as u64);
+        assert_eq!(sender.join().unwrap(), content.len() as u64);
+        assert_eq!(received, content);
+        assert_eq!(positions.last(), Some(&(content.len() as u64)));
+    }
+
+    #[test]
+    fn test_dcc_resume() {
+        let content = data(20000);
+        let listener = listen(localhost()).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let to_send = content.clone();
+        let sender = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let size = to_send.len() as u64;
+            DccSend::new(Cursor::new(to_send), size).position(5000).run(&mut stream, |_| {}).unwrap()
+        });
+
+        let mut stream = TcpStream::connect((localhost(), port)).unwrap();
+        let mut received = content[..5000].to_vec();
+        let n = DccReceive::new(&mut received, Some(content.len() as u64))
+            .position(5000)
+            .run(&mut stream, |_| {}).unwrap();
+
+        assert_eq!(n, 15000);
+        assert_eq!(sender.join().unwrap(), 15000);
+        assert_eq!(received, content);
+    }
+
+    #[test]
+    fn test_dcc_receive_truncated() {
+        let listener = listen(localhost()).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let sender = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(&data(100)).unwrap();
+            // Closing with unread acknowledgements would reset the connection
+            stream.shutdown(Shutdown::Write).unwrap();
+            stream.read_to_end(&mut Vec::new()).unwrap();
+        });
+
+        let mut stream = TcpStream::connect((localhost(), port)).unwrap();
+        let err = DccReceive::new(Vec::new(), Some(1000)).run(&mut stream, |_| {}).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        drop(stream);
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn test_dcc_chat() {
+        let listener = listen(localhost()).unwrap();
+        let offer = DccMessage::chat(localhost(), listener.local_addr().unwrap().port());
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut chat = DccChat::new(stream).unwrap();
+            let line = chat.read_line().unwrap().unwrap();
+            chat.send_line(&line.to_uppercase()).unwrap();
+        });
+
+        let mut chat = DccChat::new(connect(&offer).unwrap()).unwrap();
+        chat.send_line("hello\r").unwrap();
+        assert_eq!(chat.read_line().unwrap(), Some("HELLO".to_owned()));
+        assert_eq!(chat.read_line().unwrap(), None);
+        server.join().unwrap();
+
+        assert!(connect(&DccMessage::chat(localhost(), 0)).is_err());
+    }
+}
@@ -0,0 +1,6 @@
+//! Helpers for the client side of a connection
+
+pub mod cap;
+pub mod dcc;
+pub mod registration;
+pub mod sasl;
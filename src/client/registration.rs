@@ -0,0 +1,214 @@
+//! Registration of a client connection
+//!
+//! Runs the capability negotiation and the SASL authentication before the
+//! server completes the registration with `RPL_WELCOME`.
+
+use protocol::command::{Command, CapCommand, NickCommand, PassCommand, UserCommand};
+use protocol::command::cap::CapSubCommand;
+use protocol::message::{Message, Body};
+use protocol::reply::ReplyCode;
+use client::cap::CapNegotiator;
+use client::sasl::{SaslAuthenticator, SaslMechanism};
+
+/// Sends the registration commands and answers the server until welcomed
+///
+/// ```
+/// use irc::client::registration::Registration;
+/// use irc::client::sasl::Plain;
+/// use irc::protocol::IrcMessage;
+/// use irc::protocol::message::Message;
+///
+/// let mut registration = Registration::new("jilles", "jilles", "Jilles")
+///     .sasl(Plain::new("jilles", "sesame"));
+///
+/// let start: Vec<String> = registration.start().iter().map(|c| c.to_string()).collect();
+/// assert_eq!(start, vec!["CAP LS 302", "NICK jilles", "USER jilles 0 * :Jilles"]);
+///
+/// let ls = Message::from_str(":irc.example.com CAP * LS :sasl=PLAIN").unwrap();
+/// assert_eq!(&registration.handle_message(&ls)[0].to_string()[..], "CAP REQ sasl");
+/// ```
+#[derive(Debug)]
+pub struct Registration {
+    nickname: String,
+    user: String,
+    realname: String,
+    password: Option<String>,
+    cap: CapNegotiator,
+    sasl: Option<SaslAuthenticator>,
+    end_pending: bool,
+    registered: bool,
+}
+
+impl Registration {
+    pub fn new(nickname: &str, user: &str, realname: &str) -> Registration {
+        Registration {
+            nickname: nickname.to_owned(),
+            user: user.to_owned(),
+            realname: realname.to_owned(),
+            password: None,
+            cap: CapNegotiator::new(&[]),
+            sasl: None,
+            end_pending: false,
+            registered: false,
+        }
+    }
+
+    /// Send `PASS` with the connection password
+    pub fn password(mut self, password: &str) -> Registration {
+        self.password = Some(password.to_owned());
+        self
+    }
+
+    /// Request capabilities if the server supports them
+    pub fn capabilities(mut self, caps: &[&str]) -> Registration {
+        for cap in caps {
+            self.cap.want(cap);
+        }
+        self
+    }
+
+    /// Authenticate with a SASL mechanism if the server supports it
+    pub fn sasl<M: SaslMechanism + 'static>(mut self, mechanism: M) -> Registration {
+        self.cap.want("sasl");
+        self.sasl = Some(SaslAuthenticator::new(mechanism));
+        self
+    }
+
+    /// The commands to send once connected
+    pub fn start(&self) -> Vec<Command<'static>> {
+        let mut commands = vec![Command::Cap(self.cap.start())];
+
+        if let Some(ref password) = self.password {
+            commands.push(Command::Pass(PassCommand::new(password).into_owned()));
+        }
+        commands.push(Command::Nick(NickCommand::new(&self.nickname).into_owned()));
+        commands.push(Command::User(UserCommand::new(&self.user, 0, &self.realname).into_owned()));
+        commands
+    }
+
+    /// Whether the server sent `RPL_WELCOME`
+    pub fn is_registered(&self) -> bool {
+        self.registered
+    }
+
+    pub fn capability_negotiator(&self) -> &CapNegotiator {
+        &self.cap
+    }
+
+    pub fn sasl_authenticator(&self) -> Option<&SaslAuthenticator> {
+        self.sasl.as_ref()
+    }
+
+    /// Handle a message received during the registration, returns the
+    /// commands to send back
+    pub fn handle_message(&mut self, msg: &Message) -> Vec<Command<'static>> {
+        let mut commands = Vec::new();
+
+        for cap in self.cap.handle_message(msg) {
+            if cap.subcommand() == CapSubCommand::End {
+                if let Some(start) = self.start_sasl() {
+                    self.end_pending = true;
+                    commands.push(Command::Authenticate(start));
+                    continue;
+                }
+            }
+            commands.push(Command::Cap(cap));
+        }
+
+        if self.end_pending {
+            let sasl = self.sasl.as_mut().expect("SASL was started");
+            commands.extend(sasl.handle_message(msg).into_iter().map(Command::Authenticate));
+
+            if sasl.is_finished() {
+                self.end_pending = false;
+                commands.push(Command::Cap(CapCommand::new(CapSubCommand::End, None)));
+            }
+        }
+
+        if let Body::Reply(ref reply) = *msg.body() {
+            if reply.code() == ReplyCode::RPL_WELCOME {
+                self.registered = true;
+            }
+        }
+        commands
+    }
+
+    /// `AUTHENTICATE <mechanism>` if `sasl` is enabled and the server
+    /// supports the mechanism
+    fn start_sasl(&mut self) -> Option<::protocol::command::AuthenticateCommand<'static>> {
+        if !self.cap.is_enabled("sasl") {
+            return None;
+        }
+
+        let sasl = match self.sasl {
+            Some(ref mut sasl) if !sasl.is_finished() => sasl,
+            _ => return None,
+        };
+
+        // Servers listing mechanisms in the capability value
+        if let Some(mechanisms) = self.cap.value("sasl") {
+            if !mechanisms.split(',').any(|m| m == sasl.mechanism_name()) {
+                return None;
+            }
+        }
+        Some(sasl.start())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::{IrcMessage, Message};
+    use client::sasl::{Plain, SaslStatus};
+
+    fn handle(registration: &mut Registration, line: &str) -> Vec<String> {
+        registration.handle_message(&Message::from_str(line).unwrap()).iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_registration_sasl() {
+        let mut registration = Registration::new("jilles", "jilles", "Jilles")
+            .password("hunter2")
+            .capabilities(&["multi-prefix"])
+            .sasl(Plain::new("jilles", "sesame"));
+
+        let start: Vec<String> = registration.start().iter().map(|c| c.to_string()).collect();
+        assert_eq!(start, vec!["CAP LS 302", "PASS hunter2", "NICK jilles", "USER jilles 0 * :Jilles"]);
+
+        assert_eq!(handle(&mut registration, ":irc.example.com CAP * LS :multi-prefix sasl=PLAIN,EXTERNAL"),
+                   vec!["CAP REQ :multi-prefix sasl"]);
+        assert_eq!(handle(&mut registration, ":irc.example.com CAP * ACK :multi-prefix sasl"),
+                   vec!["AUTHENTICATE PLAIN"]);
+        assert_eq!(handle(&mut registration, "AUTHENTICATE +"), vec!["AUTHENTICATE amlsbGVzAGppbGxlcwBzZXNhbWU="]);
+        assert!(handle(&mut registration, ":irc.example.com 900 jilles jilles!jilles@localhost jilles :Logged in")
+            .is_empty());
+        assert_eq!(handle(&mut registration, ":irc.example.com 903 jilles :SASL authentication successful"),
+                   vec!["CAP END"]);
+        assert_eq!(registration.sasl_authenticator().unwrap().status(), SaslStatus::Succeeded);
+
+        assert!(!registration.is_registered());
+        assert!(handle(&mut registration, ":irc.example.com 001 jilles :Welcome").is_empty());
+        assert!(registration.is_registered());
+    }
+
+    #[test]
+    fn test_registration_sasl_failed() {
+        let mut registration = Registration::new("jilles", "jilles", "Jilles").sasl(Plain::new("jilles", "bad"));
+        handle(&mut registration, ":irc.example.com CAP * LS :sasl");
+        handle(&mut registration, ":irc.example.com CAP * ACK :sasl");
+        handle(&mut registration, "AUTHENTICATE +");
+        assert_eq!(handle(&mut registration, ":irc.example.com 904 jilles :SASL authentication failed"),
+                   vec!["CAP END"]);
+        assert_eq!(registration.sasl_authenticator().unwrap().status(),
+                   SaslStatus::Failed(ReplyCode::ERR_SASLFAIL));
+    }
+
+    #[test]
+    fn test_registration_unsupported_mechanism() {
+        let mut registration = Registration::new("jilles", "jilles", "Jilles").sasl(Plain::new("jilles", "sesame"));
+        handle(&mut registration, ":irc.example.com CAP * LS :sasl=EXTERNAL");
+        assert_eq!(handle(&mut registration, ":irc.example.com CAP * ACK :sasl"), vec!["CAP END"]);
+        assert_eq!(registration.sasl_authenticator().unwrap().status(), SaslStatus::NotStarted);
+    }
+}
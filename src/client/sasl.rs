@@ -0,0 +1,481 @@
+//! SASL authentication with `AUTHENTICATE`
+//!
+//! The authentication runs after the `sasl` capability is acknowledged and
+//! before `CAP END`, see `Registration` to run it as part of the
+//! registration.
+
+use std::fmt;
+use std::error;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use getrandom;
+use hmac::{Hmac, Mac};
+use pbkdf2::pbkdf2_hmac;
+use sha2::{Digest, Sha256};
+
+use protocol::command::Command;
+use protocol::command::authenticate::{AuthenticateCommand, AuthenticateBuffer};
+use protocol::message::{Message, Body};
+use protocol::reply::ReplyCode;
+
+/// Highest SCRAM iteration count accepted from the server, a larger count
+/// would make the client spend a long time deriving the key
+pub const MAX_SCRAM_ITERATIONS: u32 = 100000;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SaslError {
+    /// The server sent a challenge the mechanism doesn't understand
+    InvalidChallenge(&'static str),
+
+    /// The server failed to prove it knows the password
+    InvalidServerSignature,
+
+    /// The server reported an error in the exchange
+    ServerError(String),
+
+    /// No random nonce could be generated
+    NoRandom,
+}
+
+impl fmt::Display for SaslError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SaslError::InvalidChallenge(desc) => write!(f, "Invalid SASL challenge: {}", desc),
+            SaslError::InvalidServerSignature => write!(f, "Invalid SASL server signature"),
+            SaslError::ServerError(ref err) => write!(f, "SASL server error: {}", err),
+            SaslError::NoRandom => write!(f, "Failed to generate a SASL nonce"),
+        }
+    }
+}
+
+impl error::Error for SaslError {
+    fn description(&self) -> &str {
+        match *self {
+            SaslError::InvalidChallenge(desc) => desc,
+            SaslError::InvalidServerSignature => "Invalid server signature",
+            SaslError::ServerError(..) => "Server error",
+            SaslError::NoRandom => "Failed to generate a nonce",
+        }
+    }
+}
+
+/// A SASL mechanism, from the client side
+pub trait SaslMechanism {
+    /// Name sent in the first `AUTHENTICATE`, such as `PLAIN`
+    fn name(&self) -> &str;
+
+    /// Answer a challenge of the server, the first one being empty
+    fn respond(&mut self, challenge: &[u8]) -> Result<Vec<u8>, SaslError>;
+}
+
+/// `PLAIN`, the password in clear
+#[derive(Debug, Clone)]
+pub struct Plain {
+    authzid: String,
+    authcid: String,
+    password: String,
+}
+
+impl Plain {
+    pub fn new(account: &str, password: &str) -> Plain {
+        Plain {
+            authzid: account.to_owned(),
+            authcid: account.to_owned(),
+            password: password.to_owned(),
+        }
+    }
+}
+
+impl SaslMechanism for Plain {
+    fn name(&self) -> &str {
+        "PLAIN"
+    }
+
+    fn respond(&mut self, _challenge: &[u8]) -> Result<Vec<u8>, SaslError> {
+        Ok(format!("{}\0{}\0{}", self.authzid, self.authcid, self.password).into_bytes())
+    }
+}
+
+/// `EXTERNAL`, the credentials are the TLS client certificate
+#[derive(Debug, Clone, Default)]
+pub struct External {
+    authzid: Option<String>,
+}
+
+impl External {
+    pub fn new() -> External {
+        External {
+            authzid: None,
+        }
+    }
+
+    /// Log into another account than the one of the certificate
+    pub fn with_authzid(authzid: &str) -> External {
+        External {
+            authzid: Some(authzid.to_owned()),
+        }
+    }
+}
+
+impl SaslMechanism for External {
+    fn name(&self) -> &str {
+        "EXTERNAL"
+    }
+
+    fn respond(&mut self, _challenge: &[u8]) -> Result<Vec<u8>, SaslError> {
+        Ok(self.authzid.as_ref().map(|a| a.clone().into_bytes()).unwrap_or_default())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ScramState {
+    Initial,
+    ClientFirst { client_first_bare: String },
+    ClientFinal { server_signature: Vec<u8> },
+    Done,
+}
+
+/// `SCRAM-SHA-256`, RFC 7677, the password is never sent and the server
+/// proves it knows it
+#[derive(Debug, Clone)]
+pub struct ScramSha256 {
+    username: String,
+    password: String,
+    nonce: String,
+    state: ScramState,
+}
+
+impl ScramSha256 {
+    pub fn new(username: &str, password: &str) -> Result<ScramSha256, SaslError> {
+        let mut random = [0u8; 18];
+        try!(getrandom::getrandom(&mut random).map_err(|_| SaslError::NoRandom));
+        Ok(ScramSha256::with_nonce(username, password, &BASE64.encode(random)))
+    }
+
+    /// Use a known client nonce, which must be printable and contain no `,`
+    pub fn with_nonce(username: &str, password: &str, nonce: &str) -> ScramSha256 {
+        ScramSha256 {
+            username: username.to_owned(),
+            password: password.to_owned(),
+            nonce: nonce.to_owned(),
+            state: ScramState::Initial,
+        }
+    }
+
+    fn client_final(&self, client_first_bare: &str, server_first: &str)
+            -> Result<(String, Vec<u8>), SaslError> {
+        let mut nonce = None;
+        let mut salt = None;
+        let mut iterations = None;
+
+        for attr in server_first.split(',') {
+            if let Some(value) = attr.strip_prefix("r=") {
+                nonce = Some(value);
+            } else if let Some(value) = attr.strip_prefix("s=") {
+                salt = BASE64.decode(value).ok();
+            } else if let Some(value) = attr.strip_prefix("i=") {
+                iterations = value.parse::<u32>().ok();
+            } else if let Some(value) = attr.strip_prefix("e=") {
+                return Err(SaslError::ServerError(value.to_owned()));
+            }
+        }
+
+        let nonce = match nonce {
+            Some(n) if n.starts_with(&self.nonce[..]) && n.len() > self.nonce.len() => n,
+            _ => return Err(SaslError::InvalidChallenge("Missing or invalid server nonce")),
+        };
+        let salt = try!(salt.ok_or(SaslError::InvalidChallenge("Missing or invalid salt")));
+        let iterations = match iterations {
+            Some(i) if i > MAX_SCRAM_ITERATIONS => {
+                return Err(SaslError::InvalidChallenge("Iteration count is too large"));
+            },
+            Some(i) if i > 0 => i,
+            _ => return Err(SaslError::InvalidChallenge("Missing or invalid iteration count")),
+        };
+
+        // "n,," base64 encoded, no channel binding nor authzid
+        let without_proof = format!("c=biws,r={}", nonce);
+        let auth_message = format!("{},{},{}", client_first_bare, server_first, without_proof);
+
+        let mut salted_password = [0u8; 32];
+        pbkdf2_hmac::<Sha256>(self.password.as_bytes(), &salt, iterations, &mut salted_password);
+
+        let client_key = hmac_sha256(&salted_password, b"Client Key");
+        let stored_key = Sha256::digest(&client_key);
+        let client_signature = hmac_sha256(&stored_key, auth_message.as_bytes());
+        let proof: Vec<u8> = client_key.iter().zip(client_signature.iter()).map(|(k, s)| k ^ s).collect();
+
+        let server_key = hmac_sha256(&salted_password, b"Server Key");
+        let server_signature = hmac_sha256(&server_key, auth_message.as_bytes());
+
+        Ok((format!("{},p={}", without_proof, BASE64.encode(&proof)), server_signature))
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Escape `=` and `,` in a SCRAM user name
+fn scram_name(name: &str) -> String {
+    name.replace('=', "=3D").replace(',', "=2C")
+}
+
+impl SaslMechanism for ScramSha256 {
+    fn name(&self) -> &str {
+        "SCRAM-SHA-256"
+    }
+
+    fn respond(&mut self, challenge: &[u8]) -> Result<Vec<u8>, SaslError> {
+        let challenge = try!(::std::str::from_utf8(challenge)
+            .map_err(|_| SaslError::InvalidChallenge("Challenge is not UTF-8")));
+
+        match ::std::mem::replace(&mut self.state, ScramState::Done) {
+            ScramState::Initial => {
+                let client_first_bare = format!("n={},r={}", scram_name(&self.username), self.nonce);
+                let response = format!("n,,{}", client_first_bare);
+                self.state = ScramState::ClientFirst { client_first_bare: client_first_bare };
+                Ok(response.into_bytes())
+            },
+            ScramState::ClientFirst { client_first_bare } => {
+                let (response, server_signature) = try!(self.client_final(&client_first_bare, challenge));
+                self.state = ScramState::ClientFinal { server_signature: server_signature };
+                Ok(response.into_bytes())
+            },
+            ScramState::ClientFinal { server_signature } => {
+                if let Some(error) = challenge.strip_prefix("e=") {
+                    return Err(SaslError::ServerError(error.to_owned()));
+                }
+
+                let verifier = challenge.strip_prefix("v=")
+                    .and_then(|v| BASE64.decode(v.split(',').next().unwrap_or("")).ok());
+
+                if verifier.as_ref() != Some(&server_signature) {
+                    return Err(SaslError::InvalidServerSignature);
+                }
+                Ok(Vec::new())
+            },
+            ScramState::Done => Err(SaslError::InvalidChallenge("Unexpected challenge after the exchange")),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SaslStatus {
+    /// `start` was not called yet
+    NotStarted,
+
+    /// Waiting for the server
+    InProgress,
+
+    /// The server accepted the credentials
+    Succeeded,
+
+    /// The server answered `ERR_SASLALREADY`, we were already logged in
+    AlreadyAuthenticated,
+
+    /// The server rejected or aborted the authentication with this reply
+    Failed(ReplyCode),
+}
+
+/// Runs the `AUTHENTICATE` exchange of a mechanism
+pub struct SaslAuthenticator {
+    mechanism: Box<dyn SaslMechanism>,
+    buffer: AuthenticateBuffer,
+    status: SaslStatus,
+    error: Option<SaslError>,
+    account: Option<String>,
+}
+
+impl SaslAuthenticator {
+    pub fn new<M: SaslMechanism + 'static>(mechanism: M) -> SaslAuthenticator {
+        SaslAuthenticator {
+            mechanism: Box::new(mechanism),
+            buffer: AuthenticateBuffer::new(),
+            status: SaslStatus::NotStarted,
+            error: None,
+            account: None,
+        }
+    }
+
+    pub fn mechanism_name(&self) -> &str {
+        self.mechanism.name()
+    }
+
+    /// The first command to send, once `sasl` is enabled
+    pub fn start(&mut self) -> AuthenticateCommand<'static> {
+        self.status = SaslStatus::InProgress;
+        AuthenticateCommand::new(self.mechanism.name()).into_owned()
+    }
+
+    pub fn status(&self) -> SaslStatus {
+        self.status
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match self.status {
+            SaslStatus::Succeeded | SaslStatus::AlreadyAuthenticated | SaslStatus::Failed(..) => true,
+            SaslStatus::NotStarted | SaslStatus::InProgress => false,
+        }
+    }
+
+    /// Error of the mechanism which made us abort
+    pub fn error(&self) -> Option<&SaslError> {
+        self.error.as_ref()
+    }
+
+    /// Account we are logged into, from `RPL_LOGGEDIN`
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_ref().map(|a| &a[..])
+    }
+
+    /// Handle a message received during the authentication
+    pub fn handle_message(&mut self, msg: &Message) -> Vec<AuthenticateCommand<'static>> {
+        if self.status != SaslStatus::InProgress {
+            return Vec::new();
+        }
+
+        match *msg.body() {
+            Body::Command(Command::Authenticate(ref cmd)) => {
+                let response = match self.buffer.push(cmd) {
+                    None => return Vec::new(),
+                    Some(Err(err)) => Err(SaslError::InvalidChallenge(err.desc())),
+                    Some(Ok(challenge)) => self.mechanism.respond(&challenge),
+                };
+
+                match response {
+                    Ok(response) => AuthenticateCommand::from_payload(&response),
+                    Err(err) => {
+                        self.error = Some(err);
+                        vec![AuthenticateCommand::abort()]
+                    },
+                }
+            },
+            Body::Reply(ref reply) => {
+                match reply.code() {
+                    ReplyCode::RPL_LOGGEDIN => {
+                        let mut params = reply.parameters();
+                        params.next();
+                        self.account = params.next().map(str::to_owned);
+                    },
+                    ReplyCode::RPL_SASLSUCCESS => self.status = SaslStatus::Succeeded,
+                    code @ ReplyCode::ERR_NICKLOCKED |
+                    code @ ReplyCode::ERR_SASLFAIL |
+                    code @ ReplyCode::ERR_SASLTOOLONG |
+                    code @ ReplyCode::ERR_SASLABORTED => self.status = SaslStatus::Failed(code),
+                    ReplyCode::ERR_SASLALREADY => self.status = SaslStatus::AlreadyAuthenticated,
+                    _ => {},
+                }
+                Vec::new()
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Debug for SaslAuthenticator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SaslAuthenticator")
+            .field("mechanism", &self.mechanism.name())
+            .field("status", &self.status)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::{IrcMessage, Message};
+    use protocol::reply::ReplyCode;
+
+    #[test]
+    fn test_plain() {
+        let mut plain = Plain::new("jilles", "sesame");
+        assert_eq!(plain.respond(b"").unwrap(), b"jilles\0jilles\0sesame");
+
+        assert_eq!(External::new().respond(b"").unwrap(), b"");
+        assert_eq!(External::with_authzid("jilles").respond(b"").unwrap(), b"jilles");
+    }
+
+    #[test]
+    fn test_scram_sha_256() {
+        // Example exchange of RFC 7677
+        let mut scram = ScramSha256::with_nonce("user", "pencil", "rOprNGfwEbeRWgbNEkqO");
+
+        assert_eq!(scram.respond(b"").unwrap(), &b"n,,n=user,r=rOprNGfwEbeRWgbNEkqO"[..]);
+
+        let server_first = b"r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+                             s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096";
+        assert_eq!(scram.respond(server_first).unwrap(),
+                   &b"c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+                      p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ="[..]);
+
+        assert_eq!(scram.respond(b"v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=").unwrap(), b"");
+    }
+
+    #[test]
+    fn test_scram_sha_256_errors() {
+        let mut scram = ScramSha256::with_nonce("user", "pencil", "abc");
+        scram.respond(b"").unwrap();
+        assert_eq!(scram.respond(b"r=xyz,s=QSXCR+Q6sek8bf92,i=4096").unwrap_err(),
+                   SaslError::InvalidChallenge("Missing or invalid server nonce"));
+
+        let mut scram = ScramSha256::with_nonce("user", "pencil", "abc");
+        scram.respond(b"").unwrap();
+        scram.respond(b"r=abcdef,s=QSXCR+Q6sek8bf92,i=1").unwrap();
+        assert_eq!(scram.respond(b"v=AAAA").unwrap_err(), SaslError::InvalidServerSignature);
+
+        let mut scram = ScramSha256::with_nonce("us,er=", "pencil", "abc");
+        assert_eq!(scram.respond(b"").unwrap(), &b"n,,n=us=2Cer=3D,r=abc"[..]);
+        assert_eq!(scram.respond(b"e=unknown-user").unwrap_err(), SaslError::ServerError("unknown-user".to_owned()));
+
+        let mut scram = ScramSha256::with_nonce("user", "pencil", "abc");
+        scram.respond(b"").unwrap();
+        assert_eq!(scram.respond(b"r=abcdef,s=QSXCR+Q6sek8bf92,i=4294967295").unwrap_err(),
+                   SaslError::InvalidChallenge("Iteration count is too large"));
+    }
+
+    fn handle(auth: &mut SaslAuthenticator, line: &str) -> Vec<String> {
+        auth.handle_message(&Message::from_str(line).unwrap()).iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_authenticator() {
+        let mut auth = SaslAuthenticator::new(Plain::new("jilles", "sesame"));
+        assert_eq!(&auth.start().to_string()[..], "AUTHENTICATE PLAIN");
+
+        assert_eq!(handle(&mut auth, ":irc.example.com AUTHENTICATE +"),
+                   vec!["AUTHENTICATE amlsbGVzAGppbGxlcwBzZXNhbWU="]);
+        handle(&mut auth, ":irc.example.com 900 jilles jilles!jilles@example.com jilles :You are now logged in");
+        assert!(!auth.is_finished());
+        handle(&mut auth, ":irc.example.com 903 jilles :SASL authentication successful");
+        assert_eq!(auth.status(), SaslStatus::Succeeded);
+        assert_eq!(auth.account(), Some("jilles"));
+    }
+
+    #[test]
+    fn test_authenticator_abort() {
+        let mut auth = SaslAuthenticator::new(ScramSha256::with_nonce("user", "pencil", "abc"));
+        auth.start();
+        handle(&mut auth, ":irc.example.com AUTHENTICATE +");
+        assert_eq!(handle(&mut auth, ":irc.example.com AUTHENTICATE cj14eXo="), vec!["AUTHENTICATE *"]);
+        assert!(auth.error().is_some());
+
+        handle(&mut auth, ":irc.example.com 906 user :SASL authentication aborted");
+        assert_eq!(auth.status(), SaslStatus::Failed(ReplyCode::ERR_SASLABORTED));
+    }
+
+    #[test]
+    fn test_authenticator_already() {
+        let mut auth = SaslAuthenticator::new(Plain::new("jilles", "sesame"));
+        auth.start();
+        handle(&mut auth, ":irc.example.com 907 jilles :You have already authenticated using SASL");
+        assert_eq!(auth.status(), SaslStatus::AlreadyAuthenticated);
+        assert!(auth.is_finished());
+    }
+}
@@ -1,5 +1,10 @@
-extern crate regex;
+extern crate base64;
+extern crate sha2;
+extern crate hmac;
+extern crate pbkdf2;
+extern crate getrandom;
 #[macro_use]
 extern crate log;
 
+pub mod client;
 pub mod protocol;
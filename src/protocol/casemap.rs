@@ -0,0 +1,205 @@
+//! Case insensitive comparison of nicknames and channel names
+//!
+//! Servers advertise the rules in the `CASEMAPPING` token of
+//! `RPL_ISUPPORT`. With `rfc1459`, `[]\~` are the upper case of `{}|^`, so
+//! `[WiZ]` and `{wiz}` are the same nickname.
+
+use std::fmt;
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CaseMapping {
+    /// Only `A` to `Z` are folded
+    Ascii,
+
+    /// `A` to `Z` and `[]\~` are folded to `a` to `z` and `{}|^`
+    Rfc1459,
+
+    /// `A` to `Z` and `[]\` are folded to `a` to `z` and `{}|`
+    StrictRfc1459,
+}
+
+impl CaseMapping {
+    /// Get the mapping of a `CASEMAPPING` value
+    pub fn from_name(name: &str) -> Option<CaseMapping> {
+        match name {
+            "ascii" => Some(CaseMapping::Ascii),
+            "rfc1459" => Some(CaseMapping::Rfc1459),
+            "strict-rfc1459" => Some(CaseMapping::StrictRfc1459),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            CaseMapping::Ascii => "ascii",
+            CaseMapping::Rfc1459 => "rfc1459",
+            CaseMapping::StrictRfc1459 => "strict-rfc1459",
+        }
+    }
+
+    /// Lower case of a character
+    pub fn fold_char(&self, c: char) -> char {
+        match (*self, c) {
+            (_, 'A'..='Z') => c.to_ascii_lowercase(),
+            (CaseMapping::Ascii, _) => c,
+            (_, '[') => '{',
+            (_, ']') => '}',
+            (_, '\\') => '|',
+            (CaseMapping::Rfc1459, '~') => '^',
+            _ => c,
+        }
+    }
+
+    /// Lower case of a name, borrowed if it is already lower case
+    pub fn fold<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if s.chars().all(|c| self.fold_char(c) == c) {
+            Cow::Borrowed(s)
+        } else {
+            Cow::Owned(s.chars().map(|c| self.fold_char(c)).collect())
+        }
+    }
+
+    /// Compare two names case insensitively
+    pub fn eq_names(&self, a: &str, b: &str) -> bool {
+        a.len() == b.len() && a.chars().zip(b.chars()).all(|(x, y)| self.fold_char(x) == self.fold_char(y))
+    }
+
+    /// Hash a name so that equal names have the same hash
+    pub fn hash_name<H: Hasher>(&self, s: &str, state: &mut H) {
+        for c in s.chars() {
+            self.fold_char(c).hash(state);
+        }
+        0xffu8.hash(state);
+    }
+}
+
+impl Default for CaseMapping {
+    fn default() -> CaseMapping {
+        CaseMapping::Rfc1459
+    }
+}
+
+impl fmt::Display for CaseMapping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A nickname or channel name usable as a case insensitive map key
+///
+/// Keys compare with the mapping they were created with, the keys of a
+/// map should share the mapping of the server.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use irc::protocol::casemap::{CaseMapping, IrcKey};
+///
+/// let mut channels = HashMap::new();
+/// channels.insert(IrcKey::new("#Rust[dev]", CaseMapping::Rfc1459), 42);
+/// assert_eq!(channels.get(&IrcKey::new("#rust{DEV}", CaseMapping::Rfc1459)), Some(&42));
+/// ```
+#[derive(Debug, Clone)]
+pub struct IrcKey {
+    name: String,
+    mapping: CaseMapping,
+}
+
+impl IrcKey {
+    pub fn new(name: &str, mapping: CaseMapping) -> IrcKey {
+        IrcKey {
+            name: name.to_owned(),
+            mapping: mapping,
+        }
+    }
+
+    /// The name as given, with its case
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    pub fn mapping(&self) -> CaseMapping {
+        self.mapping
+    }
+
+    /// The lower case name
+    pub fn folded(&self) -> Cow<'_, str> {
+        self.mapping.fold(&self.name)
+    }
+}
+
+impl PartialEq for IrcKey {
+    fn eq(&self, other: &IrcKey) -> bool {
+        self.mapping.eq_names(&self.name, &other.name)
+    }
+}
+
+impl Eq for IrcKey {}
+
+impl Hash for IrcKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.mapping.hash_name(&self.name, state)
+    }
+}
+
+impl fmt::Display for IrcKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_casemapping_fold() {
+        assert_eq!(CaseMapping::Ascii.fold("[WiZ]~"), "[wiz]~");
+        assert_eq!(CaseMapping::Rfc1459.fold("[WiZ]\\~"), "{wiz}|^");
+        assert_eq!(CaseMapping::StrictRfc1459.fold("[WiZ]\\~"), "{wiz}|~");
+
+        match CaseMapping::Rfc1459.fold("{wiz}") {
+            Cow::Borrowed(..) => {},
+            Cow::Owned(..) => panic!("Lower case names should be borrowed"),
+        }
+    }
+
+    #[test]
+    fn test_casemapping_eq() {
+        assert!(CaseMapping::Rfc1459.eq_names("[WiZ]^", "{wiz}~"));
+        assert!(!CaseMapping::StrictRfc1459.eq_names("WiZ^", "wiz~"));
+        assert!(CaseMapping::StrictRfc1459.eq_names("[WiZ]", "{wiz}"));
+        assert!(!CaseMapping::Ascii.eq_names("[WiZ]", "{wiz}"));
+        assert!(CaseMapping::Ascii.eq_names("WIZ", "wiz"));
+        assert!(!CaseMapping::Ascii.eq_names("wiz", "wizz"));
+        assert!(!CaseMapping::Ascii.eq_names("É", "é"));
+
+        // The derived comparison of mappings is not shadowed
+        assert!(CaseMapping::Rfc1459.eq(&CaseMapping::default()));
+    }
+
+    #[test]
+    fn test_casemapping_names() {
+        for mapping in &[CaseMapping::Ascii, CaseMapping::Rfc1459, CaseMapping::StrictRfc1459] {
+            assert_eq!(CaseMapping::from_name(mapping.name()), Some(*mapping));
+        }
+        assert_eq!(CaseMapping::from_name("rfc7613"), None);
+    }
+
+    #[test]
+    fn test_irc_key() {
+        let mut nicks = HashMap::new();
+        nicks.insert(IrcKey::new("WiZ[away]", CaseMapping::Rfc1459), 1);
+        nicks.insert(IrcKey::new("wiz{AWAY}", CaseMapping::Rfc1459), 2);
+        assert_eq!(nicks.len(), 1);
+
+        let key = nicks.keys().next().unwrap();
+        assert_eq!(key.as_str(), "WiZ[away]");
+        assert_eq!(key.folded(), "wiz{away}");
+        assert_eq!(nicks[&IrcKey::new("WIZ{away}", CaseMapping::Rfc1459)], 2);
+        assert!(!nicks.contains_key(&IrcKey::new("WiZ", CaseMapping::Rfc1459)));
+    }
+}
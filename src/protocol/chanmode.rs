@@ -0,0 +1,445 @@
+//! Channel modes, as defined in RFC 2811 and advertised by servers in the
+//! `CHANMODES` and `PREFIX` parameters of `RPL_ISUPPORT`
+//!
+//! ```plain
+//! MODE #rust +ob-k nick *!*@example.com key
+//! ```
+//!
+//! Which letters consume an argument depends on the server, so parsing a
+//! mode string requires a `ChannelModes` table.
+
+use std::fmt;
+
+use protocol::codec::MAX_MESSAGE_LENGTH;
+use protocol::command::{CMD_MODE, ModeCommand};
+use protocol::split::DEFAULT_SOURCE_RESERVE;
+use protocol::message::{MessageParamIter, ParseMessageError, ParseMessageErrorKind};
+
+/// Number of modes with an argument per `MODE` command when the server
+/// doesn't advertise `MODES`
+pub const DEFAULT_MODES: usize = 3;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ModeSign {
+    Add,
+    Remove,
+}
+
+impl ModeSign {
+    pub fn as_char(&self) -> char {
+        match *self {
+            ModeSign::Add => '+',
+            ModeSign::Remove => '-',
+        }
+    }
+}
+
+impl fmt::Display for ModeSign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
+/// How a channel mode letter takes its argument
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ChannelModeKind {
+    /// Type A, a list such as bans. Takes an argument when set or unset,
+    /// without an argument it queries the list
+    List,
+
+    /// Type B, always takes an argument, such as the key
+    Always,
+
+    /// Type C, takes an argument only when set, such as the limit
+    SetOnly,
+
+    /// Type D, never takes an argument
+    Never,
+
+    /// Membership mode such as `o` or `v`, always takes a nickname
+    Prefix,
+}
+
+/// Table of the channel modes supported by a server
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ChannelModes {
+    list: String,
+    always: String,
+    set_only: String,
+    never: String,
+    prefix_modes: String,
+    prefix_chars: String,
+}
+
+impl ChannelModes {
+    /// Modes defined by RFC 2811, `CHANMODES=beI,Ok,l,aimnqpsrt` and
+    /// `PREFIX=(ov)@+`
+    ///
+    /// The channel creator `O` has no prefix of its own, it is a mode which
+    /// always takes a nickname.
+    pub fn rfc2811() -> ChannelModes {
+        ChannelModes {
+            list: "beI".to_owned(),
+            always: "Ok".to_owned(),
+            set_only: "l".to_owned(),
+            never: "aimnqpsrt".to_owned(),
+            prefix_modes: "ov".to_owned(),
+            prefix_chars: "@+".to_owned(),
+        }
+    }
+
+    /// Build the table from `CHANMODES` and `PREFIX` values, such as
+    /// `beI,k,l,imnpst` and `(ov)@+`
+    ///
+    /// The RFC 2811 prefixes are used if `prefix` is `None`.
+    pub fn from_isupport(chanmodes: &str, prefix: Option<&str>) -> Result<ChannelModes, ParseMessageError> {
+        let mut modes = ChannelModes::rfc2811();
+        try!(modes.set_chanmodes(chanmodes));
+        if let Some(prefix) = prefix {
+            try!(modes.set_prefix(prefix));
+        }
+        Ok(modes)
+    }
+
+    /// Replace the mode types with a `CHANMODES` value
+    ///
+    /// Types beyond the fourth are ignored, as the specification requires.
+    pub fn set_chanmodes(&mut self, chanmodes: &str) -> Result<(), ParseMessageError> {
+        let mut groups = chanmodes.split(',');
+        match (groups.next(), groups.next(), groups.next(), groups.next()) {
+            (Some(a), Some(b), Some(c), Some(d)) => {
+                self.list = a.to_owned();
+                self.always = b.to_owned();
+                self.set_only = c.to_owned();
+                self.never = d.to_owned();
+                Ok(())
+            },
+            _ => Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                            "CHANMODES requires 4 comma separated groups")),
+        }
+    }
+
+    /// Replace the membership modes with a `PREFIX` value
+    ///
+    /// An empty value means the server has no membership modes.
+    pub fn set_prefix(&mut self, prefix: &str) -> Result<(), ParseMessageError> {
+        if prefix.is_empty() {
+            self.prefix_modes.clear();
+            self.prefix_chars.clear();
+            return Ok(());
+        }
+
+        let close = prefix.find(')');
+        match close {
+            Some(idx) if prefix.starts_with('(') => {
+                let modes = &prefix[1..idx];
+                let chars = &prefix[idx+1..];
+                if modes.chars().count() != chars.chars().count() {
+                    return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                                      "PREFIX requires as many prefixes as modes"));
+                }
+
+                self.prefix_modes = modes.to_owned();
+                self.prefix_chars = chars.to_owned();
+                Ok(())
+            },
+            _ => Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                            "PREFIX should be formatted as (modes)prefixes")),
+        }
+    }
+
+    /// Get the type of a mode letter, `None` if the server doesn't know it
+    pub fn kind(&self, letter: char) -> Option<ChannelModeKind> {
+        if self.prefix_modes.contains(letter) {
+            Some(ChannelModeKind::Prefix)
+        } else if self.list.contains(letter) {
+            Some(ChannelModeKind::List)
+        } else if self.always.contains(letter) {
+            Some(ChannelModeKind::Always)
+        } else if self.set_only.contains(letter) {
+            Some(ChannelModeKind::SetOnly)
+        } else if self.never.contains(letter) {
+            Some(ChannelModeKind::Never)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether a change consumes an argument
+    ///
+    /// Unknown letters are assumed to take no argument.
+    pub fn takes_argument(&self, sign: ModeSign, letter: char) -> bool {
+        match self.kind(letter) {
+            Some(ChannelModeKind::List) | Some(ChannelModeKind::Always) | Some(ChannelModeKind::Prefix) => true,
+            Some(ChannelModeKind::SetOnly) => sign == ModeSign::Add,
+            Some(ChannelModeKind::Never) | None => false,
+        }
+    }
+
+    /// Get the nickname prefix of a membership mode, `@` for `o`
+    pub fn prefix_of(&self, letter: char) -> Option<char> {
+        self.prefix_modes.chars().position(|c| c == letter)
+            .and_then(|idx| self.prefix_chars.chars().nth(idx))
+    }
+
+    /// Get the membership mode of a nickname prefix, `o` for `@`
+    pub fn mode_of_prefix(&self, prefix: char) -> Option<char> {
+        self.prefix_chars.chars().position(|c| c == prefix)
+            .and_then(|idx| self.prefix_modes.chars().nth(idx))
+    }
+}
+
+impl Default for ChannelModes {
+    fn default() -> ChannelModes {
+        ChannelModes::rfc2811()
+    }
+}
+
+/// A single mode change, such as `+o nick`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ModeChange<'a> {
+    sign: ModeSign,
+    letter: char,
+    argument: Option<&'a str>,
+}
+
+impl<'a> ModeChange<'a> {
+    pub fn new(sign: ModeSign, letter: char, argument: Option<&'a str>) -> ModeChange<'a> {
+        ModeChange {
+            sign: sign,
+            letter: letter,
+            argument: argument,
+        }
+    }
+
+    pub fn sign(&self) -> ModeSign {
+        self.sign
+    }
+
+    pub fn letter(&self) -> char {
+        self.letter
+    }
+
+    pub fn argument(&self) -> Option<&'a str> {
+        self.argument
+    }
+}
+
+impl<'a> fmt::Display for ModeChange<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{}{}", self.sign, self.letter));
+        match self.argument {
+            Some(arg) => write!(f, " {}", arg),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Iterator of the changes in a mode string and its arguments
+///
+/// A change which should take an argument but has none left gets `None`,
+/// which is how a list such as the bans is queried.
+pub struct ModeChangeIter<'a, 'm> {
+    modes: &'m ChannelModes,
+    letters: ::std::str::Chars<'a>,
+    args: MessageParamIter<'a>,
+    sign: ModeSign,
+}
+
+impl<'a, 'm> ModeChangeIter<'a, 'm> {
+    /// Parse the parameters of a `MODE` command following the target
+    pub fn new(modes: &'m ChannelModes, mut params: MessageParamIter<'a>) -> ModeChangeIter<'a, 'm> {
+        let letters = params.next().unwrap_or("");
+        ModeChangeIter {
+            modes: modes,
+            letters: letters.chars(),
+            args: params,
+            sign: ModeSign::Add,
+        }
+    }
+}
+
+impl<'a, 'm> Iterator for ModeChangeIter<'a, 'm> {
+    type Item = ModeChange<'a>;
+
+    fn next(&mut self) -> Option<ModeChange<'a>> {
+        loop {
+            let letter = match self.letters.next() {
+                None => return None,
+                Some(c) => c,
+            };
+
+            match letter {
+                '+' => self.sign = ModeSign::Add,
+                '-' => self.sign = ModeSign::Remove,
+                _ => {
+                    let argument = if self.modes.takes_argument(self.sign, letter) {
+                        self.args.next()
+                    } else {
+                        None
+                    };
+
+                    return Some(ModeChange::new(self.sign, letter, argument));
+                }
+            }
+        }
+    }
+}
+
+/// Pack mode changes into as few `MODE` commands as possible
+///
+/// Each command carries at most `max_args` changes with an argument, the
+/// value of `MODES`, and fits in a message once relayed with the source of
+/// the sender. `None` is no limit, as for a `MODES` token without a value,
+/// and only the length splits the changes.
+pub fn pack_mode_changes(target: &str, changes: &[ModeChange], max_args: Option<usize>) -> Vec<ModeCommand<'static>> {
+    // ":source MODE target " and "\r\n"
+    let overhead = 1 + DEFAULT_SOURCE_RESERVE + 1 + CMD_MODE.len() + 1 + target.len() + 1 + 2;
+    let max_len = MAX_MESSAGE_LENGTH.saturating_sub(overhead);
+    let max_args = max_args.map(|max| if max == 0 { 1 } else { max });
+
+    let mut commands = Vec::new();
+    let mut letters = String::new();
+    let mut args = String::new();
+    let mut sign = None;
+    let mut nargs = 0;
+
+    for change in changes {
+        let arg_len = change.argument.map(|a| a.len() + 1).unwrap_or(0);
+        let sign_len = if sign == Some(change.sign) { 0 } else { 1 };
+
+        let full = change.argument.is_some() && Some(nargs) == max_args;
+        let too_long = letters.len() + sign_len + change.letter.len_utf8() + args.len() + arg_len > max_len;
+        if !letters.is_empty() && (full || too_long) {
+            commands.push(mode_command(target, &letters, &args));
+            letters.clear();
+            args.clear();
+            sign = None;
+            nargs = 0;
+        }
+
+        if sign != Some(change.sign) {
+            letters.push(change.sign.as_char());
+            sign = Some(change.sign);
+        }
+        letters.push(change.letter);
+
+        if let Some(arg) = change.argument {
+            args.push(' ');
+            args.push_str(arg);
+            nargs += 1;
+        }
+    }
+
+    if !letters.is_empty() {
+        commands.push(mode_command(target, &letters, &args));
+    }
+
+    commands
+}
+
+fn mode_command(target: &str, letters: &str, args: &str) -> ModeCommand<'static> {
+    let modes = format!("{}{}", letters, args);
+    ModeCommand::new(target, &modes).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::IrcMessage;
+    use protocol::command::ModeCommand;
+
+    #[test]
+    fn test_parse_changes() {
+        let modes = ChannelModes::rfc2811();
+        let cmd = ModeCommand::from_str("MODE #rust +ob-k+l-l nick *!*@example.com key 10").unwrap();
+
+        let changes: Vec<ModeChange> = ModeChangeIter::new(&modes, cmd.modes()).collect();
+        assert_eq!(changes, vec![
+            ModeChange::new(ModeSign::Add, 'o', Some("nick")),
+            ModeChange::new(ModeSign::Add, 'b', Some("*!*@example.com")),
+            ModeChange::new(ModeSign::Remove, 'k', Some("key")),
+            ModeChange::new(ModeSign::Add, 'l', Some("10")),
+            ModeChange::new(ModeSign::Remove, 'l', None),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_list_query() {
+        let modes = ChannelModes::rfc2811();
+        let cmd = ModeCommand::from_str("MODE #rust +b").unwrap();
+
+        let changes: Vec<ModeChange> = cmd.channel_changes(&modes).collect();
+        assert_eq!(changes, vec![ModeChange::new(ModeSign::Add, 'b', None)]);
+    }
+
+    #[test]
+    fn test_isupport_modes() {
+        let modes = ChannelModes::from_isupport("beIq,k,fl,CMnt", Some("(qaohv)~&@%+")).unwrap();
+        assert_eq!(modes.kind('q'), Some(ChannelModeKind::Prefix));
+        assert_eq!(modes.kind('f'), Some(ChannelModeKind::SetOnly));
+        assert_eq!(modes.kind('C'), Some(ChannelModeKind::Never));
+        assert_eq!(modes.kind('Z'), None);
+        assert_eq!(modes.prefix_of('h'), Some('%'));
+        assert_eq!(modes.mode_of_prefix('~'), Some('q'));
+
+        assert!(ChannelModes::from_isupport("b,k", None).is_err());
+        assert!(ChannelModes::from_isupport("b,k,l,imnpst", Some("(ov)@")).is_err());
+    }
+
+    #[test]
+    fn test_pack_changes() {
+        let changes = [
+            ModeChange::new(ModeSign::Add, 'o', Some("a")),
+            ModeChange::new(ModeSign::Add, 'o', Some("b")),
+            ModeChange::new(ModeSign::Add, 'n', None),
+            ModeChange::new(ModeSign::Remove, 'v', Some("c")),
+            ModeChange::new(ModeSign::Remove, 'v', Some("d")),
+        ];
+
+        let lines: Vec<String> = pack_mode_changes("#rust", &changes, Some(3)).iter().map(|c| c.to_string()).collect();
+        assert_eq!(lines, vec!["MODE #rust +oon-v a b c", "MODE #rust -v d"]);
+
+        let lines: Vec<String> = pack_mode_changes("#rust", &changes, Some(10)).iter().map(|c| c.to_string()).collect();
+        assert_eq!(lines, vec!["MODE #rust +oon-vv a b c d"]);
+
+        let lines: Vec<String> = pack_mode_changes("#rust", &changes, None).iter().map(|c| c.to_string()).collect();
+        assert_eq!(lines, vec!["MODE #rust +oon-vv a b c d"]);
+
+        let lines: Vec<String> = pack_mode_changes("#rust", &changes, Some(0)).iter().map(|c| c.to_string()).collect();
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn test_pack_changes_long() {
+        let changes = [
+            ModeChange::new(ModeSign::Add, 'o', Some("a")),
+            ModeChange::new(ModeSign::Add, 'v', Some("b")),
+        ];
+
+        // Each change gets its own command when nothing fits
+        let target = format!("#{}", "a".repeat(600));
+        let commands = pack_mode_changes(&target, &changes, Some(3));
+        assert_eq!(commands.len(), 2);
+
+        // Room is left for the source added by the server
+        let nicks: Vec<String> = (0..100).map(|i| format!("nick{:02}", i)).collect();
+        let changes: Vec<ModeChange> = nicks.iter().map(|n| ModeChange::new(ModeSign::Add, 'v', Some(n))).collect();
+        let commands = pack_mode_changes("#rust", &changes, None);
+        assert!(commands.len() > 1);
+        for cmd in commands {
+            let line = format!(":{} {}\r\n", "s".repeat(DEFAULT_SOURCE_RESERVE), cmd);
+            assert!(line.len() <= MAX_MESSAGE_LENGTH, "{:?} is too long", line);
+        }
+    }
+
+    #[test]
+    fn test_rfc2811_modes() {
+        let modes = ChannelModes::rfc2811();
+        assert_eq!(modes.mode_of_prefix('@'), Some('o'));
+        assert_eq!(modes.prefix_of('o'), Some('@'));
+        assert_eq!(modes.prefix_of('O'), None);
+        assert_eq!(modes.kind('O'), Some(ChannelModeKind::Always));
+    }
+}
@@ -0,0 +1,276 @@
+//! Channel names, as defined in RFC 2811
+//!
+//! ```plain
+//! channel    =  ( "#" / "+" / ( "!" channelid ) / "&" ) chanstring [ ":" chanstring ]
+//! channelid  =  5( %x41-5A / digit )
+//! ```
+//!
+//! The part after `:` is a server mask, the channel is only known to the
+//! servers matching it.
+
+use std::fmt;
+
+use protocol::message::{ParseMessageError, ParseMessageErrorKind};
+use protocol::name::{NameLimits, is_valid_channel};
+
+/// Channel prefixes defined by RFC 2811
+pub const RFC2811_CHANTYPES: &'static str = "#&+!";
+
+/// Length of the identifier of a safe channel
+pub const SAFE_CHANNEL_ID_LEN: usize = 5;
+
+/// Characters of a generated safe channel identifier, 'A' is 0
+const SAFE_CHANNEL_ID_BASE: &'static [u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ12345";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ChannelPrefix {
+    /// `#`, known to all the servers of the network
+    Network,
+
+    /// `&`, known to a single server
+    Local,
+
+    /// `+`, without channel modes
+    Modeless,
+
+    /// `!`, with a unique identifier
+    Safe,
+
+    /// Another prefix advertised in `CHANTYPES`
+    Other(char),
+}
+
+impl ChannelPrefix {
+    pub fn from_char(c: char) -> ChannelPrefix {
+        match c {
+            '#' => ChannelPrefix::Network,
+            '&' => ChannelPrefix::Local,
+            '+' => ChannelPrefix::Modeless,
+            '!' => ChannelPrefix::Safe,
+            c => ChannelPrefix::Other(c),
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match *self {
+            ChannelPrefix::Network => '#',
+            ChannelPrefix::Local => '&',
+            ChannelPrefix::Modeless => '+',
+            ChannelPrefix::Safe => '!',
+            ChannelPrefix::Other(c) => c,
+        }
+    }
+}
+
+impl fmt::Display for ChannelPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
+/// Whether a target starts with one of `chantypes`
+pub fn is_channel_name(target: &str, chantypes: &str) -> bool {
+    match target.chars().next() {
+        Some(c) => chantypes.contains(c),
+        None => false,
+    }
+}
+
+/// A valid channel name
+///
+/// ```
+/// use irc::protocol::channel::{ChannelName, ChannelPrefix};
+///
+/// let channel = ChannelName::parse("!12345rust:*.fi").unwrap();
+/// assert_eq!(channel.prefix(), ChannelPrefix::Safe);
+/// assert_eq!(channel.safe_id(), Some("12345"));
+/// assert_eq!(channel.short_name(), "rust");
+/// assert_eq!(channel.mask(), Some("*.fi"));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ChannelName<'a> {
+    name: &'a str,
+}
+
+impl<'a> ChannelName<'a> {
+    /// Parse a channel name with the RFC 2811 prefixes and length
+    pub fn parse(s: &'a str) -> Result<ChannelName<'a>, ParseMessageError> {
+        ChannelName::with_limits(s, &NameLimits::rfc2812())
+    }
+
+    /// Parse a channel name with the `CHANTYPES` and `CHANNELLEN` of a server
+    pub fn with_limits(s: &'a str, limits: &NameLimits) -> Result<ChannelName<'a>, ParseMessageError> {
+        if !is_channel_name(s, limits.chantypes) {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                              "Channel name should start with a channel prefix"));
+        }
+        if s.len() > limits.channellen {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam, "Channel name is too long"));
+        }
+        if !is_valid_channel(s, limits.chantypes, limits.channellen) {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam, "Invalid channel name"));
+        }
+
+        Ok(ChannelName {
+            name: s,
+        })
+    }
+
+    /// Parse a comma separated list, such as the channels of `JOIN`
+    pub fn parse_list(list: &'a str) -> Result<Vec<ChannelName<'a>>, ParseMessageError> {
+        list.split(',').map(ChannelName::parse).collect()
+    }
+
+    /// The full name, with the prefix and the mask
+    pub fn as_str(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn prefix(&self) -> ChannelPrefix {
+        ChannelPrefix::from_char(self.name.chars().next().expect("Channel names are not empty"))
+    }
+
+    pub fn is_safe(&self) -> bool {
+        self.prefix() == ChannelPrefix::Safe
+    }
+
+    /// The identifier of a safe channel, `None` when creating one with `!!`
+    pub fn safe_id(&self) -> Option<&'a str> {
+        let rest = &self.name[1..];
+        if self.is_safe() && rest.len() >= SAFE_CHANNEL_ID_LEN
+            && rest.as_bytes()[..SAFE_CHANNEL_ID_LEN].iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit()) {
+            Some(&rest[..SAFE_CHANNEL_ID_LEN])
+        } else {
+            None
+        }
+    }
+
+    /// The name without the prefix, the safe channel identifier and the
+    /// mask
+    pub fn short_name(&self) -> &'a str {
+        let start = match self.safe_id() {
+            Some(id) => 1 + id.len(),
+            None if self.name.starts_with("!!") => 2,
+            None => 1,
+        };
+        let name = &self.name[start..];
+        match name.find(':') {
+            Some(idx) => &name[..idx],
+            None => name,
+        }
+    }
+
+    /// The server mask after `:`
+    pub fn mask(&self) -> Option<&'a str> {
+        self.name[1..].find(':').map(|idx| &self.name[idx + 2..])
+    }
+}
+
+impl<'a> fmt::Display for ChannelName<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Generate the identifier of a safe channel from the time in seconds
+/// since the epoch
+///
+/// The time is written in base 31 with the digits
+/// `ABCDEFGHIJKLMNOPQRSTUVWXYZ12345`, keeping the last 5 digits.
+///
+/// ```
+/// use irc::protocol::channel::safe_channel_id;
+///
+/// assert_eq!(&safe_channel_id(0)[..], "AAAAA");
+/// assert_eq!(format!("!{}rust", safe_channel_id(1792234861)).len(), 10);
+/// ```
+pub fn safe_channel_id(secs: u64) -> String {
+    let base = SAFE_CHANNEL_ID_BASE.len() as u64;
+    let mut id = [b'A'; SAFE_CHANNEL_ID_LEN];
+    let mut value = secs;
+
+    for digit in id.iter_mut().rev() {
+        *digit = SAFE_CHANNEL_ID_BASE[(value % base) as usize];
+        value /= base;
+    }
+    String::from_utf8_lossy(&id).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::isupport::Isupport;
+    use protocol::name::NameLimits;
+
+    #[test]
+    fn test_channel_name() {
+        let channel = ChannelName::parse("#rust").unwrap();
+        assert_eq!(channel.prefix(), ChannelPrefix::Network);
+        assert_eq!(channel.short_name(), "rust");
+        assert_eq!(channel.mask(), None);
+        assert_eq!(channel.safe_id(), None);
+
+        assert_eq!(ChannelName::parse("&local").unwrap().prefix(), ChannelPrefix::Local);
+        assert_eq!(ChannelName::parse("+modeless").unwrap().prefix(), ChannelPrefix::Modeless);
+
+        let channel = ChannelName::parse("#rust:*.fi").unwrap();
+        assert_eq!(channel.short_name(), "rust");
+        assert_eq!(channel.mask(), Some("*.fi"));
+        assert_eq!(&channel.to_string()[..], "#rust:*.fi");
+    }
+
+    #[test]
+    fn test_channel_name_invalid() {
+        for name in &["rust", "#", "#ru st", "#ru,st", "#ru\x07st", "#rust:", "#:x", "!ABCDrust", "!abcderust",
+                     "!!", "!12345", "!12345:*.fi"] {
+            assert!(ChannelName::parse(name).is_err(), "{:?} should be invalid", name);
+        }
+        assert!(ChannelName::parse(&format!("#{}", "a".repeat(50))).is_err());
+    }
+
+    #[test]
+    fn test_channel_name_limits() {
+        let mut isupport = Isupport::new();
+        isupport.apply_token("CHANTYPES=#~");
+        isupport.apply_token("CHANNELLEN=64");
+        let limits = NameLimits::from_isupport(&isupport);
+
+        let channel = ChannelName::with_limits("~tilde", &limits).unwrap();
+        assert_eq!(channel.prefix(), ChannelPrefix::Other('~'));
+        assert!(ChannelName::with_limits("&local", &limits).is_err());
+        assert!(ChannelName::with_limits(&format!("#{}", "a".repeat(60)), &limits).is_ok());
+    }
+
+    #[test]
+    fn test_channel_name_list() {
+        let channels = ChannelName::parse_list("#rust,&local").unwrap();
+        assert_eq!(channels.iter().map(|c| c.as_str()).collect::<Vec<&str>>(), vec!["#rust", "&local"]);
+        assert!(ChannelName::parse_list("#rust,,#ok").is_err());
+    }
+
+    #[test]
+    fn test_safe_channel() {
+        let channel = ChannelName::parse("!A1B2Crust").unwrap();
+        assert!(channel.is_safe());
+        assert_eq!(channel.safe_id(), Some("A1B2C"));
+        assert_eq!(channel.short_name(), "rust");
+
+        // Creating a safe channel
+        let channel = ChannelName::parse("!!rust").unwrap();
+        assert_eq!(channel.safe_id(), None);
+        assert_eq!(channel.short_name(), "rust");
+
+        // No name after the identifier
+        assert!(ChannelName::parse("!!").is_err());
+        assert!(ChannelName::parse("!12345").is_err());
+
+        assert_eq!(&safe_channel_id(0)[..], "AAAAA");
+        assert_eq!(&safe_channel_id(31)[..], "AAABA");
+        assert_eq!(&safe_channel_id(30)[..], "AAAA5");
+
+        let id = safe_channel_id(1792234861);
+        let name = format!("!{}rust", id);
+        assert_eq!(ChannelName::parse(&name).unwrap().safe_id(), Some(&id[..]));
+    }
+}
@@ -0,0 +1,522 @@
+use std::fmt::{self, Write as FmtWrite};
+use std::error;
+use std::io::{self, Read, Write};
+use std::str;
+
+use protocol::message::{Message, RawMessage, ParseMessageError};
+use protocol::encoding::{Encoding, EncodingError};
+
+/// Maximum length of a message in bytes, including the trailing `CR-LF`
+/// and excluding the tags section
+pub const MAX_MESSAGE_LENGTH: usize = 512;
+
+/// Maximum length of the tags section in bytes, including the leading `@`
+/// and the trailing space
+pub const MAX_TAGS_LENGTH: usize = 8191;
+
+const READ_CHUNK_SIZE: usize = 4096;
+
+#[derive(Debug)]
+pub enum CodecError {
+    /// Error from the underlying stream
+    Io(io::Error),
+
+    /// The line exceeded `MAX_MESSAGE_LENGTH` or `MAX_TAGS_LENGTH`,
+    /// carries the length of the offending part
+    LineTooLong(usize),
+
+    /// The line is not valid UTF-8
+    InvalidUtf8(str::Utf8Error),
+
+    /// The line could not be parsed as a message
+    Parse(ParseMessageError),
+
+    /// The message could not be encoded for writing
+    Encoding(EncodingError),
+
+    /// The message to write contains `CR`, `LF` or `NUL`, which would end
+    /// the line early or be rejected by the server
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &CodecError::Io(ref err) => write!(f, "I/O error: {}", err),
+            &CodecError::LineTooLong(len) => write!(f, "Line too long: {} bytes", len),
+            &CodecError::InvalidUtf8(ref err) => write!(f, "Invalid UTF-8: {}", err),
+            &CodecError::Parse(ref err) => write!(f, "Invalid message: {}", err),
+            &CodecError::Encoding(ref err) => write!(f, "Encoding error: {}", err),
+            &CodecError::InvalidCharacter(c) => write!(f, "Invalid character in message: {:?}", c),
+        }
+    }
+}
+
+impl error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            &CodecError::Io(ref err) => Some(err),
+            &CodecError::InvalidUtf8(ref err) => Some(err),
+            &CodecError::Parse(ref err) => Some(err),
+            &CodecError::Encoding(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CodecError {
+    fn from(err: io::Error) -> CodecError {
+        CodecError::Io(err)
+    }
+}
+
+impl From<EncodingError> for CodecError {
+    fn from(err: EncodingError) -> CodecError {
+        CodecError::Encoding(err)
+    }
+}
+
+impl From<ParseMessageError> for CodecError {
+    fn from(err: ParseMessageError) -> CodecError {
+        CodecError::Parse(err)
+    }
+}
+
+/// Check a line, without its terminator, against the length limits
+fn check_line_length(line: &[u8]) -> Result<(), CodecError> {
+    let body = if line.starts_with(b"@") {
+        let tags_len = match line.iter().position(|&b| b == b' ') {
+            None => line.len(),
+            Some(idx) => idx + 1,
+        };
+
+        if tags_len > MAX_TAGS_LENGTH {
+            return Err(CodecError::LineTooLong(tags_len));
+        }
+
+        &line[tags_len..]
+    } else {
+        line
+    };
+
+    if body.len() + 2 > MAX_MESSAGE_LENGTH {
+        return Err(CodecError::LineTooLong(body.len() + 2));
+    }
+
+    Ok(())
+}
+
+/// Reads messages from a stream
+///
+/// Lines are terminated by `CR-LF` or a bare `LF`, empty lines are skipped.
+/// Parsed messages borrow from an internal buffer which is reused for every
+/// line, call `into_owned` on them to keep them around.
+///
+/// Messages are decoded with `Encoding::Utf8` unless another encoding is
+/// provided with `with_encoding`.
+pub struct MessageReader<R> {
+    inner: R,
+    encoding: Encoding,
+    buf: Vec<u8>,
+    pos: usize,
+    filled: usize,
+    discarding: bool,
+    eof: bool,
+}
+
+impl<R: Read> MessageReader<R> {
+    pub fn new(inner: R) -> MessageReader<R> {
+        MessageReader::with_encoding(inner, Encoding::Utf8)
+    }
+
+    pub fn with_encoding(inner: R, encoding: Encoding) -> MessageReader<R> {
+        MessageReader {
+            inner: inner,
+            encoding: encoding,
+            buf: vec![0u8; MAX_TAGS_LENGTH + MAX_MESSAGE_LENGTH + READ_CHUNK_SIZE],
+            pos: 0,
+            filled: 0,
+            discarding: false,
+            eof: false,
+        }
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read the next non-empty line, without its terminator
+    ///
+    /// Returns `Ok(None)` at the end of the stream. A line that exceeds the
+    /// length limits is skipped and reported as `CodecError::LineTooLong`,
+    /// the next call continues with the following line.
+    pub fn read_line_bytes(&mut self) -> Result<Option<&[u8]>, CodecError> {
+        let (start, end) = match try!(self.next_line_range()) {
+            None => return Ok(None),
+            Some(r) => r,
+        };
+
+        Ok(Some(&self.buf[start..end]))
+    }
+
+    /// Read the next non-empty line as UTF-8, regardless of the encoding
+    pub fn read_line(&mut self) -> Result<Option<&str>, CodecError> {
+        match try!(self.read_line_bytes()) {
+            None => Ok(None),
+            Some(line) => match str::from_utf8(line) {
+                Ok(s) => Ok(Some(s)),
+                Err(err) => Err(CodecError::InvalidUtf8(err)),
+            },
+        }
+    }
+
+    /// Read the next line as a `RawMessage`
+    pub fn read_raw_message(&mut self) -> Result<Option<RawMessage<'_>>, CodecError> {
+        let encoding = self.encoding;
+        match try!(self.read_line_bytes()) {
+            None => Ok(None),
+            Some(line) => Ok(Some(try!(RawMessage::from_bytes(line, encoding)))),
+        }
+    }
+
+    /// Read the next line as a `Message`
+    pub fn read_message(&mut self) -> Result<Option<Message<'_>>, CodecError> {
+        let encoding = self.encoding;
+        match try!(self.read_line_bytes()) {
+            None => Ok(None),
+            Some(line) => Ok(Some(try!(Message::from_bytes(line, encoding)))),
+        }
+    }
+
+    fn next_line_range(&mut self) -> Result<Option<(usize, usize)>, CodecError> {
+        loop {
+            if let Some(idx) = self.buf[self.pos..self.filled].iter().position(|&b| b == b'\n') {
+                let start = self.pos;
+                let newline = start + idx;
+                self.pos = newline + 1;
+
+                if self.discarding {
+                    // Tail of an over-long line which was already reported
+                    self.discarding = false;
+                    continue;
+                }
+
+                let end = if newline > start && self.buf[newline - 1] == b'\r' {
+                    newline - 1
+                } else {
+                    newline
+                };
+
+                if start == end {
+                    continue;
+                }
+
+                try!(check_line_length(&self.buf[start..end]));
+                return Ok(Some((start, end)));
+            }
+
+            if self.eof {
+                if self.pos == self.filled || self.discarding {
+                    self.pos = self.filled;
+                    return Ok(None);
+                }
+
+                // Unterminated last line
+                let (start, mut end) = (self.pos, self.filled);
+                self.pos = self.filled;
+                if self.buf[end - 1] == b'\r' {
+                    end -= 1;
+                    if start == end {
+                        return Ok(None);
+                    }
+                }
+                try!(check_line_length(&self.buf[start..end]));
+                return Ok(Some((start, end)));
+            }
+
+            // Move the pending data to the front of the buffer
+            if self.pos > 0 {
+                self.buf.copy_within(self.pos..self.filled, 0);
+                self.filled -= self.pos;
+                self.pos = 0;
+            }
+
+            if self.filled == self.buf.len() {
+                // No terminator in sight, the line can't fit in any limit
+                let len = self.filled;
+                self.filled = 0;
+                if self.discarding {
+                    continue;
+                }
+
+                self.discarding = true;
+                return Err(CodecError::LineTooLong(len));
+            }
+
+            let n = match self.inner.read(&mut self.buf[self.filled..]) {
+                Ok(n) => n,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(From::from(err)),
+            };
+            if n == 0 {
+                self.eof = true;
+            }
+            self.filled += n;
+        }
+    }
+}
+
+/// Writes messages to a stream, each terminated by `CR-LF`
+///
+/// Messages are encoded with `Encoding::Utf8` unless another encoding is
+/// provided with `with_encoding`.
+pub struct MessageWriter<W> {
+    inner: W,
+    encoding: Encoding,
+    buf: String,
+}
+
+impl<W: Write> MessageWriter<W> {
+    pub fn new(inner: W) -> MessageWriter<W> {
+        MessageWriter::with_encoding(inner, Encoding::Utf8)
+    }
+
+    pub fn with_encoding(inner: W, encoding: Encoding) -> MessageWriter<W> {
+        MessageWriter {
+            inner: inner,
+            encoding: encoding,
+            buf: String::with_capacity(MAX_MESSAGE_LENGTH),
+        }
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Write a message
+    ///
+    /// Messages exceeding the length limits are rejected with
+    /// `CodecError::LineTooLong`, and messages which can't be represented in
+    /// the encoding with `CodecError::Encoding`. Parameters containing `CR`,
+    /// `LF` or `NUL` are rejected with `CodecError::InvalidCharacter`, so
+    /// they can't smuggle in another command. Nothing is written in any of
+    /// these cases.
+    pub fn write_message(&mut self, msg: &Message) -> Result<(), CodecError> {
+        self.write_display(msg)
+    }
+
+    /// Write a raw message
+    pub fn write_raw_message(&mut self, msg: &RawMessage) -> Result<(), CodecError> {
+        self.write_display(msg)
+    }
+
+    pub fn flush(&mut self) -> Result<(), CodecError> {
+        self.inner.flush().map_err(From::from)
+    }
+
+    fn write_display<T: fmt::Display>(&mut self, msg: &T) -> Result<(), CodecError> {
+        self.buf.clear();
+        write!(self.buf, "{}", msg).unwrap();
+
+        if let Some(c) = self.buf.chars().find(|&c| c == '\r' || c == '\n' || c == '\0') {
+            return Err(CodecError::InvalidCharacter(c));
+        }
+
+        let mut encoded = try!(self.encoding.encode(&self.buf)).into_owned();
+        try!(check_line_length(&encoded));
+
+        encoded.extend_from_slice(b"\r\n");
+        try!(self.inner.write_all(&encoded));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use protocol::message::{Message, Body};
+    use protocol::command::{Command, PrivmsgCommand};
+    use protocol::encoding::LegacyEncoding;
+
+    #[test]
+    fn test_reader_line_endings() {
+        let data = b"PING :a\r\nPING :b\n\r\n\nPING :c";
+        let mut reader = MessageReader::new(Cursor::new(&data[..]));
+
+        assert_eq!(reader.read_line().unwrap(), Some("PING :a"));
+        assert_eq!(reader.read_line().unwrap(), Some("PING :b"));
+        assert_eq!(reader.read_line().unwrap(), Some("PING :c"));
+        assert_eq!(reader.read_line().unwrap(), None);
+    }
+
+    #[test]
+    fn test_reader_message() {
+        let data = b":abc!abc@example.com PRIVMSG #rust :Hello\r\n";
+        let mut reader = MessageReader::new(Cursor::new(&data[..]));
+
+        let msg = reader.read_message().unwrap().unwrap().into_owned();
+        let expected = Message::new(Some("abc!abc@example.com"),
+                                    Body::Command(Command::Privmsg(PrivmsgCommand::new("#rust", "Hello"))));
+        assert_eq!(msg, expected);
+        assert!(reader.read_message().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_reader_too_long() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"PRIVMSG #rust :");
+        data.extend_from_slice("a".repeat(600).as_bytes());
+        data.extend_from_slice(b"\r\nPING :after\r\n");
+
+        let mut reader = MessageReader::new(Cursor::new(data));
+        match reader.read_line() {
+            Err(CodecError::LineTooLong(..)) => {},
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert_eq!(reader.read_line().unwrap(), Some("PING :after"));
+    }
+
+    #[test]
+    fn test_reader_too_long_without_terminator() {
+        let mut data = vec![b'a'; MAX_TAGS_LENGTH + MAX_MESSAGE_LENGTH + 10000];
+        data.extend_from_slice(b"\nPING :after\n");
+
+        let mut reader = MessageReader::new(Cursor::new(data));
+        match reader.read_line() {
+            Err(CodecError::LineTooLong(..)) => {},
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert_eq!(reader.read_line().unwrap(), Some("PING :after"));
+    }
+
+    #[test]
+    fn test_reader_tagged_limit() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"@label=");
+        data.extend_from_slice("a".repeat(4000).as_bytes());
+        data.extend_from_slice(b" PING :ok\r\n");
+
+        let mut reader = MessageReader::new(Cursor::new(data));
+        let raw = reader.read_raw_message().unwrap().unwrap();
+        assert_eq!(raw.command(), "PING");
+    }
+
+    #[test]
+    fn test_reader_invalid_utf8() {
+        let data = b"PRIVMSG #rust :caf\xe9\r\nPING :ok\r\n";
+        let mut reader = MessageReader::new(Cursor::new(&data[..]));
+
+        match reader.read_line() {
+            Err(CodecError::InvalidUtf8(..)) => {},
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert_eq!(reader.read_line().unwrap(), Some("PING :ok"));
+    }
+
+    #[test]
+    fn test_reader_encoding() {
+        let data = b"PRIVMSG #rust :caf\xe9\r\nPRIVMSG #rust :caf\xc3\xa9\r\n";
+
+        let mut reader = MessageReader::new(Cursor::new(&data[..]));
+        match reader.read_raw_message() {
+            Err(CodecError::Parse(..)) => {},
+            other => panic!("Unexpected result {:?}", other),
+        }
+
+        let mut reader = MessageReader::with_encoding(Cursor::new(&data[..]),
+                                                      Encoding::Utf8WithFallback(LegacyEncoding::Latin1));
+        for _ in 0..2 {
+            let raw = reader.read_raw_message().unwrap().unwrap();
+            assert_eq!(raw.parameters().nth(1), Some("café"));
+        }
+    }
+
+    #[test]
+    fn test_writer_encoding() {
+        let mut writer = MessageWriter::with_encoding(Vec::new(), Encoding::Legacy(LegacyEncoding::Latin1));
+
+        let msg = Message::new(None, Body::command(PrivmsgCommand::new("#rust", "café")));
+        writer.write_message(&msg).unwrap();
+
+        let msg = Message::new(None, Body::command(PrivmsgCommand::new("#rust", "\u{20AC}")));
+        match writer.write_message(&msg) {
+            Err(CodecError::Encoding(..)) => {},
+            other => panic!("Unexpected result {:?}", other),
+        }
+
+        assert_eq!(&writer.into_inner()[..], &b"PRIVMSG #rust :caf\xe9\r\n"[..]);
+    }
+
+    #[test]
+    fn test_writer() {
+        let mut writer = MessageWriter::new(Vec::new());
+
+        let msg = Message::new(None, Body::command(PrivmsgCommand::new("#rust", "Hello")));
+        writer.write_message(&msg).unwrap();
+
+        let long_text = "a".repeat(600);
+        let msg = Message::new(None, Body::command(PrivmsgCommand::new("#rust", &long_text)));
+        match writer.write_message(&msg) {
+            Err(CodecError::LineTooLong(..)) => {},
+            other => panic!("Unexpected result {:?}", other),
+        }
+
+        assert_eq!(&writer.into_inner()[..], &b"PRIVMSG #rust :Hello\r\n"[..]);
+    }
+
+    #[test]
+    fn test_writer_line_breaks() {
+        let mut writer = MessageWriter::new(Vec::new());
+
+        for text in &["hi\r\nQUIT :pwned", "hi\nQUIT", "hi\0"] {
+            let msg = Message::new(None, Body::command(PrivmsgCommand::new("#rust", text)));
+            match writer.write_message(&msg) {
+                Err(CodecError::InvalidCharacter(..)) => {},
+                other => panic!("Unexpected result {:?}", other),
+            }
+        }
+
+        let msg = Message::new(None, Body::command(PrivmsgCommand::new("#rust\r\nQUIT", "hi")));
+        match writer.write_message(&msg) {
+            Err(CodecError::InvalidCharacter('\r')) => {},
+            other => panic!("Unexpected result {:?}", other),
+        }
+
+        assert!(writer.into_inner().is_empty());
+    }
+}
@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_ADMIN;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct AdminCommand<'a> {
-    target: Option<&'a str>,
+    target: Option<Cow<'a, str>>,
 }
 
 impl<'a> AdminCommand<'a> {
     pub fn new(target: Option<&'a str>) -> AdminCommand<'a> {
         AdminCommand {
-            target: target,
+            target: target.map(Cow::Borrowed),
         }
     }
 
-    pub fn target(&self) -> Option<&'a str> {
-        self.target
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|t| &t[..])
+    }
+
+    pub fn into_owned(self) -> AdminCommand<'static> {
+        AdminCommand {
+            target: self.target.map(|t| Cow::Owned(t.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> AdminCommand<'b> {
+        AdminCommand {
+            target: self.target.as_ref().map(|t| Cow::Borrowed(&t[..])),
+        }
     }
 }
 
@@ -25,14 +38,16 @@ impl<'a> fmt::Display for AdminCommand<'a> {
         try!(write!(f, "{}", CMD_ADMIN));
         match self.target {
             None => Ok(()),
-            Some(t) => write!(f, " {}", t),
+            Some(ref t) => write!(f, " {}", LastParam(t)),
         }
     }
 }
 
 impl<'a> IrcMessage<'a> for AdminCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<AdminCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
-        Ok(AdminCommand::new(params.next()))
+        let mut params = raw.cow_parameters();
+        Ok(AdminCommand {
+            target: params.next(),
+        })
     }
 }
@@ -0,0 +1,212 @@
+//! SASL authentication, after the `sasl` capability is enabled
+//!
+//! ```plain
+//! AUTHENTICATE PLAIN
+//! :irc.example.com AUTHENTICATE +
+//! AUTHENTICATE amlsbGVzAGppbGxlcwBzZXNhbWU=
+//! :irc.example.com 903 jilles :SASL authentication successful
+//! ```
+//!
+//! Payloads are base64 encoded and sent in chunks of 400 bytes, a chunk
+//! shorter than 400 bytes ends the payload. `+` is an empty chunk and `*`
+//! aborts the authentication.
+
+use std::fmt;
+use std::borrow::Cow;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+use protocol::command::CMD_AUTHENTICATE;
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
+
+/// Maximum length of the base64 data in a single `AUTHENTICATE`
+pub const AUTHENTICATE_CHUNK_SIZE: usize = 400;
+
+/// Default limit of the base64 data buffered by `AuthenticateBuffer`
+pub const MAX_AUTHENTICATE_LENGTH: usize = 16384;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AuthenticateCommand<'a> {
+    data: Cow<'a, str>,
+}
+
+impl<'a> AuthenticateCommand<'a> {
+    /// Create a command with a mechanism name or a chunk of base64 data
+    pub fn new(data: &'a str) -> AuthenticateCommand<'a> {
+        AuthenticateCommand {
+            data: Cow::Borrowed(data),
+        }
+    }
+
+    /// Abort the authentication
+    pub fn abort() -> AuthenticateCommand<'static> {
+        AuthenticateCommand::new("*")
+    }
+
+    /// Encode a payload and split it in chunks
+    ///
+    /// A `+` is appended when the last chunk is exactly 400 bytes long, so
+    /// that the receiver knows the payload is complete.
+    pub fn from_payload(payload: &[u8]) -> Vec<AuthenticateCommand<'static>> {
+        let encoded = BASE64.encode(payload);
+
+        let mut chunks: Vec<AuthenticateCommand<'static>> = encoded.as_bytes()
+            .chunks(AUTHENTICATE_CHUNK_SIZE)
+            .map(|c| AuthenticateCommand { data: Cow::Owned(String::from_utf8_lossy(c).into_owned()) })
+            .collect();
+
+        if encoded.len() % AUTHENTICATE_CHUNK_SIZE == 0 {
+            chunks.push(AuthenticateCommand::new("+"));
+        }
+        chunks
+    }
+
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    pub fn is_abort(&self) -> bool {
+        &self.data[..] == "*"
+    }
+
+    /// Whether this is the last chunk of a payload
+    pub fn is_last_chunk(&self) -> bool {
+        self.data.len() < AUTHENTICATE_CHUNK_SIZE
+    }
+
+    pub fn into_owned(self) -> AuthenticateCommand<'static> {
+        AuthenticateCommand {
+            data: Cow::Owned(self.data.into_owned()),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> AuthenticateCommand<'b> {
+        AuthenticateCommand {
+            data: Cow::Borrowed(&self.data[..]),
+        }
+    }
+}
+
+impl<'a> fmt::Display for AuthenticateCommand<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", CMD_AUTHENTICATE, LastParam(&self.data))
+    }
+}
+
+impl<'a> IrcMessage<'a> for AuthenticateCommand<'a> {
+    fn from_raw(raw: &RawMessage<'a>) -> Result<AuthenticateCommand<'a>, ParseMessageError> {
+        let mut params = raw.cow_parameters();
+
+        let data = match params.next() {
+            None => {
+                return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
+                                                  "AUTHENTICATE requires data"));
+            },
+            Some(d) => d,
+        };
+
+        Ok(AuthenticateCommand {
+            data: data,
+        })
+    }
+}
+
+/// Reassembles a payload from `AUTHENTICATE` chunks
+///
+/// At most `MAX_AUTHENTICATE_LENGTH` bytes of base64 data are buffered
+/// unless another limit is given with `with_limit`.
+#[derive(Debug, Clone)]
+pub struct AuthenticateBuffer {
+    data: String,
+    limit: usize,
+}
+
+impl Default for AuthenticateBuffer {
+    fn default() -> AuthenticateBuffer {
+        AuthenticateBuffer::new()
+    }
+}
+
+impl AuthenticateBuffer {
+    pub fn new() -> AuthenticateBuffer {
+        AuthenticateBuffer::with_limit(MAX_AUTHENTICATE_LENGTH)
+    }
+
+    pub fn with_limit(limit: usize) -> AuthenticateBuffer {
+        AuthenticateBuffer {
+            data: String::new(),
+            limit: limit,
+        }
+    }
+
+    /// Add a chunk, returns the decoded payload after the last chunk
+    ///
+    /// A payload exceeding the limit is dropped with an error as soon as
+    /// the limit is reached, the authentication should then be aborted.
+    pub fn push(&mut self, cmd: &AuthenticateCommand) -> Option<Result<Vec<u8>, ParseMessageError>> {
+        if cmd.data() != "+" {
+            if self.data.len() + cmd.data().len() > self.limit {
+                self.data.clear();
+                return Some(Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                                       "AUTHENTICATE payload is too long")));
+            }
+            self.data.push_str(cmd.data());
+        }
+
+        if !cmd.is_last_chunk() {
+            return None;
+        }
+
+        let result = BASE64.decode(self.data.as_bytes()).map_err(|err| {
+            ParseMessageError::new_with_detail(ParseMessageErrorKind::InvalidParam,
+                                               "AUTHENTICATE data is not valid base64",
+                                               err.to_string())
+        });
+        self.data.clear();
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::IrcMessage;
+
+    #[test]
+    fn test_authenticate_chunks() {
+        let chunks = AuthenticateCommand::from_payload(b"jilles\0jilles\0sesame");
+        assert_eq!(chunks, vec![AuthenticateCommand::new("amlsbGVzAGppbGxlcwBzZXNhbWU=")]);
+
+        assert_eq!(AuthenticateCommand::from_payload(b""), vec![AuthenticateCommand::new("+")]);
+
+        // 600 bytes are exactly 800 bytes of base64, two full chunks
+        let payload: Vec<u8> = (0..600).map(|i| i as u8).collect();
+        let chunks = AuthenticateCommand::from_payload(&payload);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].data().len(), AUTHENTICATE_CHUNK_SIZE);
+        assert_eq!(chunks[2].data(), "+");
+
+        let mut buffer = AuthenticateBuffer::new();
+        assert!(buffer.push(&chunks[0]).is_none());
+        assert!(buffer.push(&chunks[1]).is_none());
+        assert_eq!(buffer.push(&chunks[2]).unwrap().unwrap(), payload);
+
+        assert_eq!(buffer.push(&AuthenticateCommand::new("+")).unwrap().unwrap(), b"");
+        assert!(buffer.push(&AuthenticateCommand::new("!!")).unwrap().is_err());
+
+        let mut buffer = AuthenticateBuffer::with_limit(AUTHENTICATE_CHUNK_SIZE);
+        assert!(buffer.push(&chunks[0]).is_none());
+        assert!(buffer.push(&chunks[1]).unwrap().is_err());
+    }
+
+    #[test]
+    fn test_authenticate_parse() {
+        let cmd = AuthenticateCommand::from_str("AUTHENTICATE PLAIN").unwrap();
+        assert_eq!(cmd.data(), "PLAIN");
+        assert!(!cmd.is_abort());
+        assert!(AuthenticateCommand::from_str("AUTHENTICATE *").unwrap().is_abort());
+        assert!(AuthenticateCommand::from_str("AUTHENTICATE").is_err());
+    }
+}
@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_AWAY;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct AwayCommand<'a> {
-    msg: Option<&'a str>,
+    msg: Option<Cow<'a, str>>,
 }
 
 impl<'a> AwayCommand<'a> {
     pub fn new(msg: Option<&'a str>) -> AwayCommand<'a> {
         AwayCommand {
-            msg: msg,
+            msg: msg.map(Cow::Borrowed),
         }
     }
 
-    pub fn message(&self) -> Option<&'a str> {
-        self.msg
+    pub fn message(&self) -> Option<&str> {
+        self.msg.as_ref().map(|m| &m[..])
+    }
+
+    pub fn into_owned(self) -> AwayCommand<'static> {
+        AwayCommand {
+            msg: self.msg.map(|m| Cow::Owned(m.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> AwayCommand<'b> {
+        AwayCommand {
+            msg: self.msg.as_ref().map(|m| Cow::Borrowed(&m[..])),
+        }
     }
 }
 
@@ -25,7 +38,7 @@ impl<'a> fmt::Display for AwayCommand<'a> {
         try!(write!(f, "{}", CMD_AWAY));
 
         match self.msg {
-            Some(m) => write!(f, " :{}", m),
+            Some(ref m) => write!(f, " :{}", m),
             None => Ok(()),
         }
     }
@@ -33,6 +46,8 @@ impl<'a> fmt::Display for AwayCommand<'a> {
 
 impl<'a> IrcMessage<'a> for AwayCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<AwayCommand<'a>, ParseMessageError> {
-        Ok(AwayCommand::new(raw.parameters().next()))
+        Ok(AwayCommand {
+            msg: raw.cow_parameters().next(),
+        })
     }
 }
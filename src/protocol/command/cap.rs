@@ -0,0 +1,360 @@
+//! IRCv3 capability negotiation
+//!
+//! ```plain
+//! CAP LS 302
+//! :irc.example.com CAP * LS * :multi-prefix sasl=PLAIN,EXTERNAL
+//! :irc.example.com CAP * LS :server-time
+//! CAP REQ :multi-prefix sasl
+//! :irc.example.com CAP * ACK :multi-prefix sasl
+//! CAP END
+//! ```
+//!
+//! Commands sent by the client have no target, the ones sent by the server
+//! have the nickname of the client or `*` before the subcommand.
+
+use std::fmt;
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use protocol::command::CMD_CAP;
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CapSubCommand {
+    /// List the capabilities supported by the server
+    Ls,
+
+    /// List the capabilities enabled for the client
+    List,
+
+    /// Request capabilities
+    Req,
+
+    /// The server enabled the requested capabilities
+    Ack,
+
+    /// The server rejected the requested capabilities
+    Nak,
+
+    /// New capabilities are available, with `cap-notify`
+    New,
+
+    /// Capabilities are no longer available, with `cap-notify`
+    Del,
+
+    /// End the negotiation and continue the registration
+    End,
+}
+
+impl CapSubCommand {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            CapSubCommand::Ls => "LS",
+            CapSubCommand::List => "LIST",
+            CapSubCommand::Req => "REQ",
+            CapSubCommand::Ack => "ACK",
+            CapSubCommand::Nak => "NAK",
+            CapSubCommand::New => "NEW",
+            CapSubCommand::Del => "DEL",
+            CapSubCommand::End => "END",
+        }
+    }
+}
+
+impl fmt::Display for CapSubCommand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for CapSubCommand {
+    type Err = ParseMessageError;
+
+    fn from_str(s: &str) -> Result<CapSubCommand, ParseMessageError> {
+        match s {
+            "LS" => Ok(CapSubCommand::Ls),
+            "LIST" => Ok(CapSubCommand::List),
+            "REQ" => Ok(CapSubCommand::Req),
+            "ACK" => Ok(CapSubCommand::Ack),
+            "NAK" => Ok(CapSubCommand::Nak),
+            "NEW" => Ok(CapSubCommand::New),
+            "DEL" => Ok(CapSubCommand::Del),
+            "END" => Ok(CapSubCommand::End),
+            _ => Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam, "Unknown CAP subcommand")),
+        }
+    }
+}
+
+/// A capability in a list, such as `sasl=PLAIN,EXTERNAL` or `-multi-prefix`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Capability<'a> {
+    name: &'a str,
+    value: Option<&'a str>,
+    removed: bool,
+}
+
+impl<'a> Capability<'a> {
+    pub fn new(name: &'a str, value: Option<&'a str>) -> Capability<'a> {
+        Capability {
+            name: name,
+            value: value,
+            removed: false,
+        }
+    }
+
+    pub fn parse(s: &'a str) -> Capability<'a> {
+        let (removed, s) = match s.strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, s),
+        };
+        let (name, value) = match s.find('=') {
+            None => (s, None),
+            Some(idx) => (&s[..idx], Some(&s[idx + 1..])),
+        };
+
+        Capability {
+            name: name,
+            value: value,
+            removed: removed,
+        }
+    }
+
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    pub fn value(&self) -> Option<&'a str> {
+        self.value
+    }
+
+    /// Values separated by `,`, such as the mechanisms of `sasl`
+    pub fn values(&self) -> ::std::str::Split<'a, char> {
+        self.value.unwrap_or("").split(',')
+    }
+
+    /// Whether the capability is disabled, in `REQ` and `ACK`
+    pub fn is_removed(&self) -> bool {
+        self.removed
+    }
+}
+
+impl<'a> fmt::Display for Capability<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.removed {
+            try!(write!(f, "-"));
+        }
+        try!(write!(f, "{}", self.name));
+
+        match self.value {
+            None => Ok(()),
+            Some(v) => write!(f, "={}", v),
+        }
+    }
+}
+
+/// Iterator of a space separated capability list
+pub struct CapabilityIter<'a> {
+    caps: ::std::str::Split<'a, char>,
+}
+
+impl<'a> Iterator for CapabilityIter<'a> {
+    type Item = Capability<'a>;
+
+    fn next(&mut self) -> Option<Capability<'a>> {
+        loop {
+            match self.caps.next() {
+                None => return None,
+                Some("") => {},
+                Some(cap) => return Some(Capability::parse(cap)),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CapCommand<'a> {
+    target: Option<Cow<'a, str>>,
+    subcommand: CapSubCommand,
+    more: bool,
+    param: Option<Cow<'a, str>>,
+}
+
+impl<'a> CapCommand<'a> {
+    /// Create a command sent by a client, `param` is the version for `LS`
+    /// and the capabilities for `REQ`
+    pub fn new(subcommand: CapSubCommand, param: Option<&'a str>) -> CapCommand<'a> {
+        CapCommand {
+            target: None,
+            subcommand: subcommand,
+            more: false,
+            param: param.map(Cow::Borrowed),
+        }
+    }
+
+    /// Create a command sent by a server, `more` is set on all but the last
+    /// line of a long `LS` or `LIST`
+    pub fn reply(target: &'a str, subcommand: CapSubCommand, more: bool, caps: &'a str) -> CapCommand<'a> {
+        CapCommand {
+            target: Some(Cow::Borrowed(target)),
+            subcommand: subcommand,
+            more: more,
+            param: Some(Cow::Borrowed(caps)),
+        }
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|t| &t[..])
+    }
+
+    pub fn subcommand(&self) -> CapSubCommand {
+        self.subcommand
+    }
+
+    /// Whether more lines of the `LS` or `LIST` reply follow
+    pub fn has_more(&self) -> bool {
+        self.more
+    }
+
+    /// Version of the negotiation requested by `CAP LS`, such as 302
+    pub fn version(&self) -> Option<u32> {
+        if self.target.is_some() || self.subcommand != CapSubCommand::Ls {
+            return None;
+        }
+
+        self.param.as_ref().and_then(|p| p.parse().ok())
+    }
+
+    /// The capability list, empty for a client `LS`
+    pub fn capabilities(&self) -> CapabilityIter<'_> {
+        let caps = if self.version().is_some() { "" } else { self.param.as_ref().map(|p| &p[..]).unwrap_or("") };
+
+        CapabilityIter {
+            caps: caps.split(' '),
+        }
+    }
+
+    pub fn into_owned(self) -> CapCommand<'static> {
+        CapCommand {
+            target: self.target.map(|t| Cow::Owned(t.into_owned())),
+            subcommand: self.subcommand,
+            more: self.more,
+            param: self.param.map(|p| Cow::Owned(p.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> CapCommand<'b> {
+        CapCommand {
+            target: self.target.as_ref().map(|t| Cow::Borrowed(&t[..])),
+            subcommand: self.subcommand,
+            more: self.more,
+            param: self.param.as_ref().map(|p| Cow::Borrowed(&p[..])),
+        }
+    }
+}
+
+impl<'a> fmt::Display for CapCommand<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{}", CMD_CAP));
+
+        if let Some(ref target) = self.target {
+            try!(write!(f, " {}", target));
+        }
+        try!(write!(f, " {}", self.subcommand));
+
+        if self.more {
+            try!(write!(f, " *"));
+        }
+
+        match self.param {
+            None => Ok(()),
+            Some(ref p) => write!(f, " {}", LastParam(p)),
+        }
+    }
+}
+
+impl<'a> IrcMessage<'a> for CapCommand<'a> {
+    fn from_raw(raw: &RawMessage<'a>) -> Result<CapCommand<'a>, ParseMessageError> {
+        let mut params: Vec<Cow<'a, str>> = raw.cow_parameters().collect();
+
+        let target = if params.len() >= 2 && params[1].parse::<CapSubCommand>().is_ok() {
+            Some(params.remove(0))
+        } else {
+            None
+        };
+        let sub_index = if target.is_some() { 1 } else { 0 };
+
+        let mut params = params.into_iter();
+        let subcommand = match params.next() {
+            None => {
+                return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
+                                                  "CAP requires a subcommand"));
+            },
+            Some(s) => try!(s.parse::<CapSubCommand>().map_err(|err| err.with_param_index(sub_index))),
+        };
+
+        let mut param = params.next();
+        let mut more = false;
+        if target.is_some() && param.as_ref().map(|p| p == "*").unwrap_or(false) {
+            if let Some(p) = params.next() {
+                more = true;
+                param = Some(p);
+            }
+        }
+
+        Ok(CapCommand {
+            target: target,
+            subcommand: subcommand,
+            more: more,
+            param: param,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::IrcMessage;
+
+    #[test]
+    fn test_cap_client() {
+        let cmd = CapCommand::from_str("CAP LS 302").unwrap();
+        assert_eq!(cmd.target(), None);
+        assert_eq!(cmd.subcommand(), CapSubCommand::Ls);
+        assert_eq!(cmd.version(), Some(302));
+        assert_eq!(cmd.capabilities().count(), 0);
+
+        let cmd = CapCommand::from_str("CAP REQ :multi-prefix -away-notify").unwrap();
+        let caps: Vec<Capability> = cmd.capabilities().collect();
+        assert_eq!(caps[0], Capability::new("multi-prefix", None));
+        assert!(caps[1].is_removed());
+        assert_eq!(caps[1].name(), "away-notify");
+
+        assert_eq!(CapCommand::from_str("CAP END").unwrap(), CapCommand::new(CapSubCommand::End, None));
+        assert!(CapCommand::from_str("CAP").is_err());
+        assert_eq!(CapCommand::from_str("CAP FOO").unwrap_err().param_index(), Some(0));
+        assert_eq!("ACK".parse::<CapSubCommand>().unwrap(), CapSubCommand::Ack);
+        assert!("ack".parse::<CapSubCommand>().is_err());
+    }
+
+    #[test]
+    fn test_cap_server() {
+        let cmd = CapCommand::from_str("CAP * LS * :multi-prefix sasl=PLAIN,EXTERNAL").unwrap();
+        assert_eq!(cmd.target(), Some("*"));
+        assert!(cmd.has_more());
+        assert_eq!(cmd.version(), None);
+
+        let caps: Vec<Capability> = cmd.capabilities().collect();
+        assert_eq!(caps.len(), 2);
+        assert_eq!(caps[1].name(), "sasl");
+        assert_eq!(caps[1].values().collect::<Vec<&str>>(), vec!["PLAIN", "EXTERNAL"]);
+
+        let cmd = CapCommand::from_str("CAP WiZ ACK :sasl").unwrap();
+        assert_eq!(cmd, CapCommand::reply("WiZ", CapSubCommand::Ack, false, "sasl"));
+        assert!(!cmd.has_more());
+
+        let cmd = CapCommand::from_str("CAP WiZ LS *").unwrap();
+        assert!(!cmd.has_more());
+        assert_eq!(cmd.capabilities().next().unwrap().name(), "*");
+    }
+}
@@ -1,34 +1,51 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_CONNECT;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ConnectCommand<'a> {
-    target_server: &'a str,
+    target_server: Cow<'a, str>,
     port: u16,
-    remote_server: Option<&'a str>,
+    remote_server: Option<Cow<'a, str>>,
 }
 
 impl<'a> ConnectCommand<'a> {
     pub fn new(target: &'a str, port: u16, remote: Option<&'a str>) -> ConnectCommand<'a> {
         ConnectCommand {
-            target_server: target,
+            target_server: Cow::Borrowed(target),
             port: port,
-            remote_server: remote,
+            remote_server: remote.map(Cow::Borrowed),
         }
     }
 
-    pub fn target_server(&self) -> &'a str {
-        self.target_server
+    pub fn target_server(&self) -> &str {
+        &self.target_server
     }
 
     pub fn port(&self) -> u16 {
         self.port
     }
 
-    pub fn remote_server(&self) -> Option<&'a str> {
-        self.remote_server
+    pub fn remote_server(&self) -> Option<&str> {
+        self.remote_server.as_ref().map(|r| &r[..])
+    }
+
+    pub fn into_owned(self) -> ConnectCommand<'static> {
+        ConnectCommand {
+            target_server: Cow::Owned(self.target_server.into_owned()),
+            port: self.port,
+            remote_server: self.remote_server.map(|r| Cow::Owned(r.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> ConnectCommand<'b> {
+        ConnectCommand {
+            target_server: Cow::Borrowed(&self.target_server[..]),
+            port: self.port,
+            remote_server: self.remote_server.as_ref().map(|r| Cow::Borrowed(&r[..])),
+        }
     }
 }
 
@@ -37,20 +54,20 @@ impl<'a> fmt::Display for ConnectCommand<'a> {
         try!(write!(f, "{} {} {}", CMD_CONNECT, self.target_server, self.port));
         match self.remote_server {
             None => Ok(()),
-            Some(t) => write!(f, " {}", t),
+            Some(ref t) => write!(f, " {}", LastParam(t)),
         }
     }
 }
 
 impl<'a> IrcMessage<'a> for ConnectCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<ConnectCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let (t, p) = match (params.next(), params.next()) {
             (Some(t), Some(p)) => {
                 let p = match p.parse::<u16>() {
                     Err(..) => {
                         return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
-                                                          "Port should be a valid u16 number"));
+                                                          "Port should be a valid u16 number").with_param_index(1));
                     },
                     Ok(p) => p,
                 };
@@ -63,6 +80,10 @@ impl<'a> IrcMessage<'a> for ConnectCommand<'a> {
             },
         };
 
-        Ok(ConnectCommand::new(t, p, params.next()))
+        Ok(ConnectCommand {
+            target_server: t,
+            port: p,
+            remote_server: params.next(),
+        })
     }
 }
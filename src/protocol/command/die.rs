@@ -6,6 +6,16 @@ use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct DieCommand;
 
+impl DieCommand {
+    pub fn into_owned(self) -> DieCommand {
+        self
+    }
+
+    pub fn as_borrowed(&self) -> DieCommand {
+        self.clone()
+    }
+}
+
 impl fmt::Display for DieCommand {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", CMD_DIE)
@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_ERROR;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ErrorCommand<'a> {
-    msg: &'a str,
+    msg: Cow<'a, str>,
 }
 
 impl<'a> ErrorCommand<'a> {
     pub fn new(msg: &'a str) -> ErrorCommand<'a> {
         ErrorCommand {
-            msg: msg,
+            msg: Cow::Borrowed(msg),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    pub fn into_owned(self) -> ErrorCommand<'static> {
+        ErrorCommand {
+            msg: Cow::Owned(self.msg.into_owned()),
         }
     }
 
-    pub fn message(&self) -> &'a str {
-        self.msg
+    pub fn as_borrowed<'b>(&'b self) -> ErrorCommand<'b> {
+        ErrorCommand {
+            msg: Cow::Borrowed(&self.msg[..]),
+        }
     }
 }
 
@@ -28,7 +41,7 @@ impl<'a> fmt::Display for ErrorCommand<'a> {
 
 impl<'a> IrcMessage<'a> for ErrorCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<ErrorCommand<'a>, ParseMessageError> {
-        let msg = match raw.parameters().next() {
+        let msg = match raw.cow_parameters().next() {
             None => {
                 return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
                                                   "ERROR command needs a message"));
@@ -36,6 +49,8 @@ impl<'a> IrcMessage<'a> for ErrorCommand<'a> {
             Some(m) => m,
         };
 
-        Ok(ErrorCommand::new(msg))
+        Ok(ErrorCommand {
+            msg: msg,
+        })
     }
 }
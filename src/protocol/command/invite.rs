@@ -1,40 +1,55 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_INVITE;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct InviteCommand<'a> {
-    nickname: &'a str,
-    channel: &'a str,
+    nickname: Cow<'a, str>,
+    channel: Cow<'a, str>,
 }
 
 impl<'a> InviteCommand<'a> {
     pub fn new(nickname: &'a str, channel: &'a str) -> InviteCommand<'a> {
         InviteCommand {
-            nickname: nickname,
-            channel: channel,
+            nickname: Cow::Borrowed(nickname),
+            channel: Cow::Borrowed(channel),
         }
     }
 
-    pub fn channel(&self) -> &'a str {
-        self.channel
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    pub fn nickname(&self) -> &str {
+        &self.nickname
+    }
+
+    pub fn into_owned(self) -> InviteCommand<'static> {
+        InviteCommand {
+            nickname: Cow::Owned(self.nickname.into_owned()),
+            channel: Cow::Owned(self.channel.into_owned()),
+        }
     }
 
-    pub fn nickname(&self) -> &'a str {
-        self.nickname
+    pub fn as_borrowed<'b>(&'b self) -> InviteCommand<'b> {
+        InviteCommand {
+            nickname: Cow::Borrowed(&self.nickname[..]),
+            channel: Cow::Borrowed(&self.channel[..]),
+        }
     }
 }
 
 impl<'a> fmt::Display for InviteCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {} {}", CMD_INVITE, self.nickname, self.channel)
+        write!(f, "{} {} {}", CMD_INVITE, self.nickname, LastParam(&self.channel))
     }
 }
 
 impl<'a> IrcMessage<'a> for InviteCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<InviteCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let nickname = match params.next() {
             None => {
                 return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
@@ -51,6 +66,9 @@ impl<'a> IrcMessage<'a> for InviteCommand<'a> {
             Some(t) => t,
         };
 
-        Ok(InviteCommand::new(nickname, channel))
+        Ok(InviteCommand {
+            nickname: nickname,
+            channel: channel,
+        })
     }
 }
@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_ISON;
 use protocol::message::{IrcMessage, MessageParamIter, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct IsonCommand<'a> {
-    nicknames: &'a str,
+    nicknames: Cow<'a, str>,
 }
 
 impl<'a> IsonCommand<'a> {
     pub fn new(nicknames: &'a str) -> IsonCommand<'a> {
         IsonCommand {
-            nicknames: nicknames,
+            nicknames: Cow::Borrowed(nicknames),
+        }
+    }
+
+    pub fn nicknames(&self) -> MessageParamIter<'_> {
+        MessageParamIter::wrap(&self.nicknames)
+    }
+
+    pub fn into_owned(self) -> IsonCommand<'static> {
+        IsonCommand {
+            nicknames: Cow::Owned(self.nicknames.into_owned()),
         }
     }
 
-    pub fn nicknames(&self) -> MessageParamIter<'a> {
-        MessageParamIter::wrap(self.nicknames)
+    pub fn as_borrowed<'b>(&'b self) -> IsonCommand<'b> {
+        IsonCommand {
+            nicknames: Cow::Borrowed(&self.nicknames[..]),
+        }
     }
 }
 
@@ -28,13 +41,15 @@ impl<'a> fmt::Display for IsonCommand<'a> {
 
 impl<'a> IrcMessage<'a> for IsonCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<IsonCommand<'a>, ParseMessageError> {
-        let nicknames = raw.parameters().get();
+        let nicknames = raw.cow_parameters().get();
 
         if nicknames.is_empty() {
             return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
                                               "ISON requires at least one nickname"));
         }
 
-        Ok(IsonCommand::new(nicknames))
+        Ok(IsonCommand {
+            nicknames: nicknames,
+        })
     }
 }
@@ -1,7 +1,9 @@
 use std::fmt;
+use std::borrow::Cow;
 
+use protocol::channel::ChannelName;
 use protocol::command::CMD_JOIN;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
 pub struct ChannelKeyIter<'a> {
     channels: &'a str,
@@ -53,22 +55,46 @@ impl<'a> Iterator for ChannelKeyIter<'a> {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct JoinCommand<'a> {
-    channels: &'a str,
-    keys: Option<&'a str>,
+    channels: Cow<'a, str>,
+    keys: Option<Cow<'a, str>>,
 }
 
 impl<'a> JoinCommand<'a> {
     pub fn new(c: &'a str, keys: Option<&'a str>) -> JoinCommand<'a> {
         JoinCommand {
-            channels: c,
-            keys: keys,
+            channels: Cow::Borrowed(c),
+            keys: keys.map(Cow::Borrowed),
         }
     }
 
-    pub fn channel_with_keys(&self) -> ChannelKeyIter<'a> {
+    pub fn channel_with_keys(&self) -> ChannelKeyIter<'_> {
         ChannelKeyIter {
-            channels: self.channels,
-            keys: self.keys,
+            channels: &self.channels,
+            keys: self.keys.as_ref().map(|k| &k[..]),
+        }
+    }
+
+    /// Whether this is `JOIN 0`, which leaves all the channels
+    pub fn is_part_all(&self) -> bool {
+        &self.channels[..] == "0"
+    }
+
+    /// Parse the channels as `ChannelName`s
+    pub fn channel_names(&self) -> Result<Vec<ChannelName<'_>>, ParseMessageError> {
+        ChannelName::parse_list(&self.channels).map_err(|err| err.with_param_index(0))
+    }
+
+    pub fn into_owned(self) -> JoinCommand<'static> {
+        JoinCommand {
+            channels: Cow::Owned(self.channels.into_owned()),
+            keys: self.keys.map(|k| Cow::Owned(k.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> JoinCommand<'b> {
+        JoinCommand {
+            channels: Cow::Borrowed(&self.channels[..]),
+            keys: self.keys.as_ref().map(|k| Cow::Borrowed(&k[..])),
         }
     }
 }
@@ -78,7 +104,7 @@ impl<'a> fmt::Display for JoinCommand<'a> {
         try!(write!(f, "{} {}", CMD_JOIN, self.channels));
 
         match self.keys {
-            Some(k) => write!(f, "{}", k),
+            Some(ref k) => write!(f, " {}", LastParam(k)),
             None => Ok(()),
         }
     }
@@ -86,7 +112,7 @@ impl<'a> fmt::Display for JoinCommand<'a> {
 
 impl<'a> IrcMessage<'a> for JoinCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<JoinCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
         let channels = match params.next() {
             None => {
@@ -98,7 +124,10 @@ impl<'a> IrcMessage<'a> for JoinCommand<'a> {
 
         let keys = params.next();
 
-        Ok(JoinCommand::new(channels, keys))
+        Ok(JoinCommand {
+            channels: channels,
+            keys: keys,
+        })
     }
 }
 
@@ -140,6 +169,17 @@ mod test {
         assert_eq!(&expected_chan[..], &actual[..]);
     }
 
+    #[test]
+    fn test_join_command_channel_names() {
+        let cmd = JoinCommand::from_str("JOIN #foo,&bar,!!baz").unwrap();
+        let names: Vec<&str> = cmd.channel_names().unwrap().iter().map(|c| c.short_name()).collect();
+        assert_eq!(names, vec!["foo", "bar", "baz"]);
+
+        let cmd = JoinCommand::from_str("JOIN 0").unwrap();
+        assert!(cmd.is_part_all());
+        assert_eq!(cmd.channel_names().unwrap_err().param_index(), Some(0));
+    }
+
     #[test]
     fn test_join_command_basic3() {
         let data = "JOIN #foo,#bar";
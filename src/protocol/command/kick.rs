@@ -1,34 +1,57 @@
 use std::fmt;
+use std::borrow::Cow;
 
+use protocol::channel::ChannelName;
 use protocol::command::{CMD_KICK, MultipleFieldIter};
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct KickCommand<'a> {
-    channels: &'a str,
-    users: &'a str,
-    comment: Option<&'a str>,
+    channels: Cow<'a, str>,
+    users: Cow<'a, str>,
+    comment: Option<Cow<'a, str>>,
 }
 
 impl<'a> KickCommand<'a> {
     pub fn new(channels: &'a str, users: &'a str, comment: Option<&'a str>) -> KickCommand<'a> {
         KickCommand {
-            channels: channels,
-            users: users,
-            comment: comment,
+            channels: Cow::Borrowed(channels),
+            users: Cow::Borrowed(users),
+            comment: comment.map(Cow::Borrowed),
         }
     }
 
-    pub fn channels(&self) -> MultipleFieldIter<'a> {
-        MultipleFieldIter::wrap(self.channels)
+    pub fn channels(&self) -> MultipleFieldIter<'_> {
+        MultipleFieldIter::wrap(&self.channels)
+    }
+
+    /// Parse the channels as `ChannelName`s
+    pub fn channel_names(&self) -> Result<Vec<ChannelName<'_>>, ParseMessageError> {
+        ChannelName::parse_list(&self.channels).map_err(|err| err.with_param_index(0))
+    }
+
+    pub fn users(&self) -> MultipleFieldIter<'_> {
+        MultipleFieldIter::wrap(&self.users)
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_ref().map(|c| &c[..])
     }
 
-    pub fn users(&self) -> MultipleFieldIter<'a> {
-        MultipleFieldIter::wrap(self.users)
+    pub fn into_owned(self) -> KickCommand<'static> {
+        KickCommand {
+            channels: Cow::Owned(self.channels.into_owned()),
+            users: Cow::Owned(self.users.into_owned()),
+            comment: self.comment.map(|c| Cow::Owned(c.into_owned())),
+        }
     }
 
-    pub fn comment(&self) -> Option<&'a str> {
-        self.comment
+    pub fn as_borrowed<'b>(&'b self) -> KickCommand<'b> {
+        KickCommand {
+            channels: Cow::Borrowed(&self.channels[..]),
+            users: Cow::Borrowed(&self.users[..]),
+            comment: self.comment.as_ref().map(|c| Cow::Borrowed(&c[..])),
+        }
     }
 }
 
@@ -37,14 +60,14 @@ impl<'a> fmt::Display for KickCommand<'a> {
         try!(write!(f, "{} {} {}", CMD_KICK, self.channels, self.users));
         match self.comment {
             None => Ok(()),
-            Some(t) => write!(f, " :{}", t),
+            Some(ref t) => write!(f, " :{}", t),
         }
     }
 }
 
 impl<'a> IrcMessage<'a> for KickCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<KickCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let (chans, users) = match (params.next(), params.next()) {
             (Some(c), Some(u)) => (c, u),
             _ => {
@@ -53,6 +76,10 @@ impl<'a> IrcMessage<'a> for KickCommand<'a> {
             },
         };
 
-        Ok(KickCommand::new(chans, users, params.next()))
+        Ok(KickCommand {
+            channels: chans,
+            users: users,
+            comment: params.next(),
+        })
     }
 }
@@ -1,28 +1,43 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_KILL;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct KillCommand<'a> {
-    nickname: &'a str,
-    msg: Option<&'a str>,
+    nickname: Cow<'a, str>,
+    msg: Option<Cow<'a, str>>,
 }
 
 impl<'a> KillCommand<'a> {
-    fn new(nickname: &'a str, msg: Option<&'a str>) -> KillCommand<'a> {
+    pub fn new(nickname: &'a str, msg: Option<&'a str>) -> KillCommand<'a> {
         KillCommand {
-            nickname: nickname,
-            msg: msg,
+            nickname: Cow::Borrowed(nickname),
+            msg: msg.map(Cow::Borrowed),
         }
     }
 
-    pub fn nickname(&self) -> &'a str {
-        self.nickname
+    pub fn nickname(&self) -> &str {
+        &self.nickname
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.msg.as_ref().map(|m| &m[..])
+    }
+
+    pub fn into_owned(self) -> KillCommand<'static> {
+        KillCommand {
+            nickname: Cow::Owned(self.nickname.into_owned()),
+            msg: self.msg.map(|m| Cow::Owned(m.into_owned())),
+        }
     }
 
-    pub fn message(&self) -> Option<&'a str> {
-        self.msg
+    pub fn as_borrowed<'b>(&'b self) -> KillCommand<'b> {
+        KillCommand {
+            nickname: Cow::Borrowed(&self.nickname[..]),
+            msg: self.msg.as_ref().map(|m| Cow::Borrowed(&m[..])),
+        }
     }
 }
 
@@ -31,7 +46,7 @@ impl<'a> fmt::Display for KillCommand<'a> {
         try!(write!(f, "{} {}", CMD_KILL, self.nickname));
 
         match self.msg {
-            Some(m) => write!(f, " {}", m),
+            Some(ref m) => write!(f, " :{}", m),
             None => Ok(()),
         }
     }
@@ -39,7 +54,7 @@ impl<'a> fmt::Display for KillCommand<'a> {
 
 impl<'a> IrcMessage<'a> for KillCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<KillCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
         let (nick, msg) = match (params.next(), params.next()) {
             (Some(nick), Some(msg)) => (nick, Some(msg)),
@@ -50,6 +65,9 @@ impl<'a> IrcMessage<'a> for KillCommand<'a> {
             }
         };
 
-        Ok(KillCommand::new(nick, msg))
+        Ok(KillCommand {
+            nickname: nick,
+            msg: msg,
+        })
     }
 }
@@ -1,12 +1,13 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_LINKS;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct LinksCommand<'a> {
-    remote: Option<&'a str>,
-    mask: Option<&'a str>,
+    remote: Option<Cow<'a, str>>,
+    mask: Option<Cow<'a, str>>,
 }
 
 impl<'a> LinksCommand<'a> {
@@ -18,17 +19,31 @@ impl<'a> LinksCommand<'a> {
             };
 
         LinksCommand {
-            remote: remote,
-            mask: tar,
+            remote: remote.map(Cow::Borrowed),
+            mask: tar.map(Cow::Borrowed),
         }
     }
 
-    pub fn remote(&self) -> Option<&'a str> {
-        self.remote
+    pub fn remote(&self) -> Option<&str> {
+        self.remote.as_ref().map(|r| &r[..])
+    }
+
+    pub fn mask(&self) -> Option<&str> {
+        self.mask.as_ref().map(|m| &m[..])
+    }
+
+    pub fn into_owned(self) -> LinksCommand<'static> {
+        LinksCommand {
+            remote: self.remote.map(|r| Cow::Owned(r.into_owned())),
+            mask: self.mask.map(|m| Cow::Owned(m.into_owned())),
+        }
     }
 
-    pub fn mask(&self) -> Option<&'a str> {
-        self.mask
+    pub fn as_borrowed<'b>(&'b self) -> LinksCommand<'b> {
+        LinksCommand {
+            remote: self.remote.as_ref().map(|r| Cow::Borrowed(&r[..])),
+            mask: self.mask.as_ref().map(|m| Cow::Borrowed(&m[..])),
+        }
     }
 }
 
@@ -37,10 +52,10 @@ impl<'a> fmt::Display for LinksCommand<'a> {
         try!(write!(f, "{}", CMD_LINKS));
         match self.mask {
             None => Ok(()),
-            Some(q) => {
+            Some(ref q) => {
                 match self.remote {
-                    None => write!(f, " {}", q),
-                    Some(t) => write!(f, " {} {}", t, q),
+                    None => write!(f, " {}", LastParam(q)),
+                    Some(ref t) => write!(f, " {} {}", t, LastParam(q)),
                 }
             }
         }
@@ -49,14 +64,17 @@ impl<'a> fmt::Display for LinksCommand<'a> {
 
 impl<'a> IrcMessage<'a> for LinksCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<LinksCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
-        let q = match (params.next(), params.next()) {
-            (None, _) => None,
-            (Some(r), Some(m)) => Some((Some(r), m)),
-            (Some(m), None) => Some((None, m)),
+        let (remote, mask) = match (params.next(), params.next()) {
+            (None, _) => (None, None),
+            (Some(r), Some(m)) => (Some(r), Some(m)),
+            (Some(m), None) => (None, Some(m)),
         };
 
-        Ok(LinksCommand::new(q))
+        Ok(LinksCommand {
+            remote: remote,
+            mask: mask,
+        })
     }
 }
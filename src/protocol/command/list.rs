@@ -1,12 +1,13 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_LIST;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ListCommand<'a> {
-    channels: Option<&'a str>,
-    target: Option<&'a str>,
+    channels: Option<Cow<'a, str>>,
+    target: Option<Cow<'a, str>>,
 }
 
 impl<'a> ListCommand<'a> {
@@ -18,17 +19,31 @@ impl<'a> ListCommand<'a> {
             };
 
         ListCommand {
-            channels: chan,
-            target: tar,
+            channels: chan.map(Cow::Borrowed),
+            target: tar.map(Cow::Borrowed),
         }
     }
 
-    pub fn channels(&self) -> Option<&'a str> {
-        self.channels
+    pub fn channels(&self) -> Option<&str> {
+        self.channels.as_ref().map(|x| &x[..])
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|x| &x[..])
+    }
+
+    pub fn into_owned(self) -> ListCommand<'static> {
+        ListCommand {
+            channels: self.channels.map(|x| Cow::Owned(x.into_owned())),
+            target: self.target.map(|x| Cow::Owned(x.into_owned())),
+        }
     }
 
-    pub fn target(&self) -> Option<&'a str> {
-        self.target
+    pub fn as_borrowed<'b>(&'b self) -> ListCommand<'b> {
+        ListCommand {
+            channels: self.channels.as_ref().map(|x| Cow::Borrowed(&x[..])),
+            target: self.target.as_ref().map(|x| Cow::Borrowed(&x[..])),
+        }
     }
 }
 
@@ -37,10 +52,10 @@ impl<'a> fmt::Display for ListCommand<'a> {
         try!(write!(f, "{}", CMD_LIST));
         match self.channels {
             None => Ok(()),
-            Some(chan) => {
+            Some(ref chan) => {
                 match self.target {
-                    None => write!(f, " {}", chan),
-                    Some(t) => write!(f, " {} {}", chan, t),
+                    None => write!(f, " {}", LastParam(chan)),
+                    Some(ref t) => write!(f, " {} {}", chan, LastParam(t)),
                 }
             }
         }
@@ -49,13 +64,17 @@ impl<'a> fmt::Display for ListCommand<'a> {
 
 impl<'a> IrcMessage<'a> for ListCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<ListCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
-        let chan = match params.next() {
+        let chan = params.next();
+        let target = match chan {
             None => None,
-            Some(chan) => Some((chan, params.next())),
+            Some(..) => params.next(),
         };
 
-        Ok(ListCommand::new(chan))
+        Ok(ListCommand {
+            channels: chan,
+            target: target,
+        })
     }
 }
@@ -1,12 +1,13 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_LUSERS;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct LusersCommand<'a> {
-    mask: Option<&'a str>,
-    target: Option<&'a str>,
+    mask: Option<Cow<'a, str>>,
+    target: Option<Cow<'a, str>>,
 }
 
 impl<'a> LusersCommand<'a> {
@@ -18,17 +19,31 @@ impl<'a> LusersCommand<'a> {
             };
 
         LusersCommand {
-            mask: mask,
-            target: tar,
+            mask: mask.map(Cow::Borrowed),
+            target: tar.map(Cow::Borrowed),
         }
     }
 
-    pub fn mask(&self) -> Option<&'a str> {
-        self.mask
+    pub fn mask(&self) -> Option<&str> {
+        self.mask.as_ref().map(|x| &x[..])
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|x| &x[..])
+    }
+
+    pub fn into_owned(self) -> LusersCommand<'static> {
+        LusersCommand {
+            mask: self.mask.map(|x| Cow::Owned(x.into_owned())),
+            target: self.target.map(|x| Cow::Owned(x.into_owned())),
+        }
     }
 
-    pub fn target(&self) -> Option<&'a str> {
-        self.target
+    pub fn as_borrowed<'b>(&'b self) -> LusersCommand<'b> {
+        LusersCommand {
+            mask: self.mask.as_ref().map(|x| Cow::Borrowed(&x[..])),
+            target: self.target.as_ref().map(|x| Cow::Borrowed(&x[..])),
+        }
     }
 }
 
@@ -37,10 +52,10 @@ impl<'a> fmt::Display for LusersCommand<'a> {
         try!(write!(f, "{}", CMD_LUSERS));
         match self.mask {
             None => Ok(()),
-            Some(chan) => {
+            Some(ref chan) => {
                 match self.target {
-                    None => write!(f, " {}", chan),
-                    Some(t) => write!(f, " {} {}", chan, t),
+                    None => write!(f, " {}", LastParam(chan)),
+                    Some(ref t) => write!(f, " {} {}", chan, LastParam(t)),
                 }
             }
         }
@@ -49,13 +64,17 @@ impl<'a> fmt::Display for LusersCommand<'a> {
 
 impl<'a> IrcMessage<'a> for LusersCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<LusersCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
-        let chan = match params.next() {
+        let mask = params.next();
+        let target = match mask {
             None => None,
-            Some(chan) => Some((chan, params.next())),
+            Some(..) => params.next(),
         };
 
-        Ok(LusersCommand::new(chan))
+        Ok(LusersCommand {
+            mask: mask,
+            target: target,
+        })
     }
 }
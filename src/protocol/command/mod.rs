@@ -42,11 +42,14 @@ pub use self::rehash::RehashCommand;
 pub use self::die::DieCommand;
 pub use self::restart::RestartCommand;
 pub use self::whowas::WhowasCommand;
+pub use self::kill::KillCommand;
 pub use self::summon::SummonCommand;
 pub use self::users::UsersCommand;
 pub use self::wallops::WallopsCommand;
 pub use self::userhost::UserhostCommand;
 pub use self::ison::IsonCommand;
+pub use self::cap::CapCommand;
+pub use self::authenticate::AuthenticateCommand;
 
 pub mod pass;
 pub mod nick;
@@ -83,6 +86,7 @@ pub mod error;
 pub mod who;
 pub mod whois;
 pub mod whowas;
+pub mod kill;
 pub mod away;
 pub mod rehash;
 pub mod die;
@@ -92,6 +96,8 @@ pub mod users;
 pub mod wallops;
 pub mod userhost;
 pub mod ison;
+pub mod cap;
+pub mod authenticate;
 
 // Connection Registration
 pub const CMD_PASS: &'static str = "PASS";
@@ -154,6 +160,10 @@ pub const CMD_WALLOPS: &'static str = "WALLOPS";
 pub const CMD_USERHOST: &'static str = "USERHOST";
 pub const CMD_ISON: &'static str = "ISON";
 
+// IRCv3 extensions
+pub const CMD_CAP: &'static str = "CAP";
+pub const CMD_AUTHENTICATE: &'static str = "AUTHENTICATE";
+
 
 impl<'a> Command<'a> {
     pub fn new<C>(c: C) -> Command<'a>
@@ -186,11 +196,31 @@ macro_rules! impl_cmd {
             fn from_raw(raw: &RawMessage<'a>) -> Result<Command<'a>, ParseMessageError> {
                 match raw.command() {
                     $(
-                        $cmd => Ok(Command::$name(try!(IrcMessage::from_raw(raw)))),
+                        $cmd => IrcMessage::from_raw(raw).map(Command::$name).map_err(|err| err.with_raw(raw)),
                     )+
 
                     _ => Err(ParseMessageError::new(ParseMessageErrorKind::UnrecognizedCommand,
-                                                    "Unrecognized command")),
+                                                    "Unrecognized command").with_command(raw.command())),
+                }
+            }
+        }
+
+        impl<'a> Command<'a> {
+            /// Convert to a `Command` which owns all its data
+            pub fn into_owned(self) -> Command<'static> {
+                match self {
+                    $(
+                        Command::$name(c) => Command::$name(c.into_owned()),
+                    )+
+                }
+            }
+
+            /// Borrow a `Command` from this one without copying
+            pub fn as_borrowed<'b>(&'b self) -> Command<'b> {
+                match self {
+                    $(
+                        &Command::$name(ref c) => Command::$name(c.as_borrowed()),
+                    )+
                 }
             }
         }
@@ -236,6 +266,7 @@ impl_cmd! {
     CMD_TOPIC   # Topic     => TopicCommand<'a>,
     CMD_NAMES   # Names     => NamesCommand<'a>,
     CMD_LIST    # List      => ListCommand<'a>,
+    CMD_INVITE  # Invite    => InviteCommand<'a>,
     CMD_KICK    # Kick      => KickCommand<'a>,
     CMD_PRIVMSG # Privmsg   => PrivmsgCommand<'a>,
     CMD_NOTICE  # Notice    => NoticeCommand<'a>,
@@ -244,23 +275,31 @@ impl_cmd! {
     CMD_VERSION # Version   => VersionCommand<'a>,
     CMD_STATS   # Stats     => StatsCommand<'a>,
     CMD_LINKS   # Links     => LinksCommand<'a>,
+    CMD_TIME    # Time      => TimeCommand<'a>,
     CMD_CONNECT # Connect   => ConnectCommand<'a>,
     CMD_TRACE   # Trace     => TraceCommand<'a>,
     CMD_ADMIN   # Admin     => AdminCommand<'a>,
+    CMD_INFO    # Info      => InfoCommand<'a>,
+    CMD_SERVLIST # Servlist => ServlistCommand<'a>,
     CMD_SQUERY  # Squery    => SqueryCommand<'a>,
     CMD_PING    # Ping      => PingCommand<'a>,
     CMD_PONG    # Pong      => PongCommand<'a>,
     CMD_ERROR   # Error     => ErrorCommand<'a>,
     CMD_WHO     # Who       => WhoCommand<'a>,
     CMD_WHOIS   # Whois     => WhoisCommand<'a>,
+    CMD_WHOWAS  # Whowas    => WhowasCommand<'a>,
+    CMD_KILL    # Kill      => KillCommand<'a>,
     CMD_AWAY    # Away      => AwayCommand<'a>,
     CMD_REHASH  # Rehash    => RehashCommand,
     CMD_DIE     # Die       => DieCommand,
     CMD_RESTART # Restart   => RestartCommand,
     CMD_SUMMON  # Summon    => SummonCommand<'a>,
+    CMD_USERS   # Users     => UsersCommand<'a>,
     CMD_WALLOPS # Wallops   => WallopsCommand<'a>,
     CMD_USERHOST # UserHost => UserhostCommand<'a>,
     CMD_ISON    # Ison      => IsonCommand<'a>,
+    CMD_CAP     # Cap       => CapCommand<'a>,
+    CMD_AUTHENTICATE # Authenticate => AuthenticateCommand<'a>,
 }
 
 pub struct MultipleFieldIter<'a> {
@@ -297,3 +336,210 @@ impl<'a> Iterator for MultipleFieldIter<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::{IrcMessage, Message, RawMessage};
+
+    /// Every command in `impl_cmd!` with the forms it accepts
+    const CORPUS: &'static [&'static str] = &[
+        "PASS secretpasswordhere",
+        "PASS :secret password",
+        "NICK Wiz",
+        "NICK :Wiz",
+        "USER guest 0 * :Ronnie Reagan",
+        "USER guest 8 * :",
+        "OPER foo bar",
+        "OPER foo :bar baz",
+        "MODE #Finnish +imI *!*@*.fi",
+        "MODE WiZ -w",
+        "MODE #42",
+        "SERVICE dict * *.fr 0 0 :French Dictionary",
+        "QUIT :Gone to have lunch",
+        "QUIT",
+        "SQUIT tolsun.oulu.fi :Bad Link ?",
+        "SQUIT tolsun.oulu.fi",
+        "JOIN #foo,#bar fubar,foobar",
+        "JOIN #foobar",
+        "JOIN 0",
+        "PART #twilight_zone :I lost",
+        "PART #oz-ops,&group5",
+        "TOPIC #test :New topic",
+        "TOPIC #test :",
+        "TOPIC #test",
+        "NAMES #twilight_zone,#42",
+        "NAMES #42 remote.server",
+        "NAMES",
+        "LIST #twilight_zone,#42",
+        "LIST #42 remote.server",
+        "LIST",
+        "INVITE Wiz #Twilight_Zone",
+        "KICK &Melbourne Matthew",
+        "KICK #Finnish,#English John,Paul :Speaking English",
+        "PRIVMSG Angel :yes I'm receiving it !",
+        "PRIVMSG #rust :",
+        "PRIVMSG #rust ::)",
+        "NOTICE #rust :hello world",
+        "MOTD",
+        "MOTD irc.example.com",
+        "MOTD :",
+        "LUSERS",
+        "LUSERS *.fi",
+        "LUSERS *.fi irc.example.com",
+        "VERSION",
+        "VERSION *.se",
+        "STATS",
+        "STATS m",
+        "STATS m *.fi",
+        "LINKS",
+        "LINKS *.au",
+        "LINKS *.edu *.bu.edu",
+        "TIME",
+        "TIME tolsun.oulu.fi",
+        "CONNECT tolsun.oulu.fi 6667",
+        "CONNECT tolsun.oulu.fi 6667 *.edu",
+        "TRACE",
+        "TRACE *.oulu.fi",
+        "ADMIN",
+        "ADMIN tolsun.oulu.fi",
+        "INFO",
+        "INFO csd.bu.edu",
+        "SERVLIST",
+        "SERVLIST *.fr",
+        "SERVLIST *.fr 0",
+        "SQUERY irchelp :HELP privmsg",
+        "WHOWAS Wiz",
+        "WHOWAS Mermaid 9",
+        "WHOWAS Trillian,WiZ 1 *.edu",
+        "KILL WiZ :Spamming",
+        "KILL WiZ",
+        "PING tolsun.oulu.fi",
+        "PING :irc.funet.fi",
+        "PING WiZ tolsun.oulu.fi",
+        "PONG csd.bu.edu tolsun.oulu.fi",
+        "ERROR :Server *.fi already exists",
+        "WHO",
+        "WHO *.fi",
+        "WHO jto* o",
+        "WHOIS wiz",
+        "WHOIS eff.org trillian",
+        "WHOIS wiz,trillian",
+        "WHOIS eff.org ::wiz",
+        "AWAY :Gone to lunch.  Back in 5",
+        "AWAY",
+        "REHASH",
+        "DIE",
+        "RESTART",
+        "SUMMON jto",
+        "SUMMON jto tolsun.oulu.fi",
+        "SUMMON jto tolsun.oulu.fi #rust",
+        "USERS",
+        "USERS eff.org",
+        "WALLOPS :Connect '*.uiuc.edu 6667' from Joshua",
+        "USERHOST Wiz Michael syrk",
+        "ISON phone trillian WiZ",
+        "CAP LS 302",
+        "CAP REQ :multi-prefix sasl",
+        "CAP END",
+        "CAP * LS * :multi-prefix sasl=PLAIN,EXTERNAL",
+        "CAP WiZ NAK :sasl",
+        "AUTHENTICATE PLAIN",
+        "AUTHENTICATE +",
+    ];
+
+    macro_rules! assert_dispatch {
+        ($cmd:ident, $params:expr, $name:ident) => {{
+            let line = format!("{} {}", $cmd, $params);
+            match Command::from_str(line.trim()) {
+                Ok(Command::$name(..)) => {},
+                other => panic!("{:?} parsed to {:?}", line, other),
+            }
+        }}
+    }
+
+    #[test]
+    fn test_command_dispatch() {
+        assert_dispatch!(CMD_PASS, "secret", Pass);
+        assert_dispatch!(CMD_NICK, "Wiz", Nick);
+        assert_dispatch!(CMD_USER, "guest 0 * :Ronnie Reagan", User);
+        assert_dispatch!(CMD_OPER, "foo bar", Oper);
+        assert_dispatch!(CMD_MODE, "WiZ -w", Mode);
+        assert_dispatch!(CMD_SERVICE, "dict * *.fr 0 0 :French Dictionary", Service);
+        assert_dispatch!(CMD_QUIT, "", Quit);
+        assert_dispatch!(CMD_SQUIT, "tolsun.oulu.fi :Bad Link", SQuit);
+        assert_dispatch!(CMD_JOIN, "#rust", Join);
+        assert_dispatch!(CMD_PART, "#rust", Part);
+        assert_dispatch!(CMD_TOPIC, "#rust", Topic);
+        assert_dispatch!(CMD_NAMES, "", Names);
+        assert_dispatch!(CMD_LIST, "", List);
+        assert_dispatch!(CMD_INVITE, "Wiz #rust", Invite);
+        assert_dispatch!(CMD_KICK, "#rust Wiz", Kick);
+        assert_dispatch!(CMD_PRIVMSG, "#rust :Hello", Privmsg);
+        assert_dispatch!(CMD_NOTICE, "#rust :Hello", Notice);
+        assert_dispatch!(CMD_MOTD, "", Motd);
+        assert_dispatch!(CMD_LUSERS, "", Lusers);
+        assert_dispatch!(CMD_VERSION, "", Version);
+        assert_dispatch!(CMD_STATS, "m", Stats);
+        assert_dispatch!(CMD_LINKS, "", Links);
+        assert_dispatch!(CMD_TIME, "", Time);
+        assert_dispatch!(CMD_CONNECT, "tolsun.oulu.fi 6667", Connect);
+        assert_dispatch!(CMD_TRACE, "", Trace);
+        assert_dispatch!(CMD_ADMIN, "", Admin);
+        assert_dispatch!(CMD_INFO, "", Info);
+        assert_dispatch!(CMD_SERVLIST, "", Servlist);
+        assert_dispatch!(CMD_SQUERY, "irchelp :HELP privmsg", Squery);
+        assert_dispatch!(CMD_WHO, "", Who);
+        assert_dispatch!(CMD_WHOIS, "wiz", Whois);
+        assert_dispatch!(CMD_WHOWAS, "Wiz", Whowas);
+        assert_dispatch!(CMD_KILL, "WiZ :Spamming", Kill);
+        assert_dispatch!(CMD_PING, "tolsun.oulu.fi", Ping);
+        assert_dispatch!(CMD_PONG, "tolsun.oulu.fi", Pong);
+        assert_dispatch!(CMD_ERROR, ":Closing link", Error);
+        assert_dispatch!(CMD_AWAY, "", Away);
+        assert_dispatch!(CMD_REHASH, "", Rehash);
+        assert_dispatch!(CMD_DIE, "", Die);
+        assert_dispatch!(CMD_RESTART, "", Restart);
+        assert_dispatch!(CMD_SUMMON, "jto", Summon);
+        assert_dispatch!(CMD_USERS, "", Users);
+        assert_dispatch!(CMD_WALLOPS, ":Hello", Wallops);
+        assert_dispatch!(CMD_USERHOST, "Wiz", UserHost);
+        assert_dispatch!(CMD_ISON, "Wiz", Ison);
+        assert_dispatch!(CMD_CAP, "LS 302", Cap);
+        assert_dispatch!(CMD_AUTHENTICATE, "PLAIN", Authenticate);
+    }
+
+    #[test]
+    fn test_command_roundtrip() {
+        for data in CORPUS {
+            let cmd = match Command::from_str(data) {
+                Ok(cmd) => cmd,
+                Err(err) => panic!("Failed to parse {:?}: {:?}", data, err),
+            };
+
+            let serialized = cmd.to_string();
+            match Command::from_str(&serialized) {
+                Ok(ref parsed) if *parsed == cmd => {},
+                other => panic!("{:?} serialized to {:?} which parses to {:?}", data, serialized, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_roundtrip() {
+        for data in CORPUS {
+            for prefix in &["", ":irc.example.com ", ":abc!abc@example.com ", "@time=now;+a=b\\sc :abc "] {
+                let line = format!("{}{}", prefix, data);
+
+                let msg = Message::from_str(&line).unwrap();
+                let serialized = msg.to_string();
+                assert_eq!(Message::from_str(&serialized).unwrap(), msg, "{:?} != {:?}", line, serialized);
+
+                let raw = RawMessage::from_str(&line).unwrap();
+                let serialized = raw.to_string();
+                assert_eq!(RawMessage::from_str(&serialized).unwrap(), raw, "{:?} != {:?}", line, serialized);
+            }
+        }
+    }
+}
@@ -1,28 +1,65 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_MODE;
+use protocol::chanmode::{ChannelModes, ModeChangeIter};
+use protocol::channel::{RFC2811_CHANTYPES, is_channel_name};
+use protocol::usermode::UserModeChangeIter;
 use protocol::message::{IrcMessage, MessageParamIter, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ModeCommand<'a> {
-    target: &'a str,
-    modes: &'a str,
+    target: Cow<'a, str>,
+    modes: Cow<'a, str>,
 }
 
 impl<'a> ModeCommand<'a> {
     pub fn new(target: &'a str, modes: &'a str) -> ModeCommand<'a> {
         ModeCommand {
-            target: target,
-            modes: modes,
+            target: Cow::Borrowed(target),
+            modes: Cow::Borrowed(modes),
         }
     }
 
-    pub fn target(&self) -> &'a str {
-        self.target
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn modes(&self) -> MessageParamIter<'_> {
+        MessageParamIter::wrap(&self.modes)
+    }
+
+    /// Parse the channel mode changes with the modes supported by the server
+    pub fn channel_changes<'m>(&self, modes: &'m ChannelModes) -> ModeChangeIter<'_, 'm> {
+        ModeChangeIter::new(modes, self.modes())
+    }
+
+    /// Whether the target is a nickname rather than a channel
+    pub fn is_user_mode(&self) -> bool {
+        !is_channel_name(&self.target, RFC2811_CHANTYPES)
     }
 
-    pub fn modes(&self) -> MessageParamIter<'a> {
-        MessageParamIter::wrap(self.modes)
+    /// Parse the user mode changes, `None` if the target is a channel
+    pub fn user_changes(&self) -> Option<UserModeChangeIter<'_>> {
+        if self.is_user_mode() {
+            Some(UserModeChangeIter::new(self.modes().next().unwrap_or("")))
+        } else {
+            None
+        }
+    }
+
+    pub fn into_owned(self) -> ModeCommand<'static> {
+        ModeCommand {
+            target: Cow::Owned(self.target.into_owned()),
+            modes: Cow::Owned(self.modes.into_owned()),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> ModeCommand<'b> {
+        ModeCommand {
+            target: Cow::Borrowed(&self.target[..]),
+            modes: Cow::Borrowed(&self.modes[..]),
+        }
     }
 }
 
@@ -40,7 +77,7 @@ impl<'a> fmt::Display for ModeCommand<'a> {
 
 impl<'a> IrcMessage<'a> for ModeCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<ModeCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
         let target = match params.next() {
             None => {
@@ -50,6 +87,35 @@ impl<'a> IrcMessage<'a> for ModeCommand<'a> {
             Some(t) => t,
         };
 
-        Ok(ModeCommand::new(target, params.get()))
+        Ok(ModeCommand {
+            target: target,
+            modes: params.get(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::chanmode::ModeSign;
+    use protocol::message::IrcMessage;
+    use protocol::usermode::{UserMode, UserModeChange};
+
+    #[test]
+    fn test_mode_user_changes() {
+        let cmd = ModeCommand::from_str("MODE WiZ -w+i").unwrap();
+        assert!(cmd.is_user_mode());
+        let changes: Vec<UserModeChange> = cmd.user_changes().unwrap().collect();
+        assert_eq!(changes, vec![
+            UserModeChange::new(ModeSign::Remove, UserMode::ReceiveWallops),
+            UserModeChange::new(ModeSign::Add, UserMode::Invisible),
+        ]);
+
+        assert_eq!(ModeCommand::from_str("MODE WiZ").unwrap().user_changes().unwrap().count(), 0);
+
+        let cmd = ModeCommand::from_str("MODE #rust +o WiZ").unwrap();
+        assert!(!cmd.is_user_mode());
+        assert!(cmd.user_changes().is_none());
     }
 }
@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_MOTD;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct MotdCommand<'a> {
-    target: Option<&'a str>,
+    target: Option<Cow<'a, str>>,
 }
 
 impl<'a> MotdCommand<'a> {
     pub fn new(target: Option<&'a str>) -> MotdCommand<'a> {
         MotdCommand {
-            target: target,
+            target: target.map(Cow::Borrowed),
         }
     }
 
-    pub fn target(&self) -> Option<&'a str> {
-        self.target
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|t| &t[..])
+    }
+
+    pub fn into_owned(self) -> MotdCommand<'static> {
+        MotdCommand {
+            target: self.target.map(|t| Cow::Owned(t.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> MotdCommand<'b> {
+        MotdCommand {
+            target: self.target.as_ref().map(|t| Cow::Borrowed(&t[..])),
+        }
     }
 }
 
@@ -25,14 +38,16 @@ impl<'a> fmt::Display for MotdCommand<'a> {
         try!(write!(f, "{}", CMD_MOTD));
         match self.target {
             None => Ok(()),
-            Some(t) => write!(f, " {}", t),
+            Some(ref t) => write!(f, " {}", LastParam(t)),
         }
     }
 }
 
 impl<'a> IrcMessage<'a> for MotdCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<MotdCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
-        Ok(MotdCommand::new(params.next()))
+        let mut params = raw.cow_parameters();
+        Ok(MotdCommand {
+            target: params.next(),
+        })
     }
 }
@@ -1,12 +1,13 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_NAMES;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct NamesCommand<'a> {
-    channels: Option<&'a str>,
-    target: Option<&'a str>,
+    channels: Option<Cow<'a, str>>,
+    target: Option<Cow<'a, str>>,
 }
 
 impl<'a> NamesCommand<'a> {
@@ -18,17 +19,31 @@ impl<'a> NamesCommand<'a> {
             };
 
         NamesCommand {
-            channels: chan,
-            target: tar,
+            channels: chan.map(Cow::Borrowed),
+            target: tar.map(Cow::Borrowed),
         }
     }
 
-    pub fn channels(&self) -> Option<&'a str> {
-        self.channels
+    pub fn channels(&self) -> Option<&str> {
+        self.channels.as_ref().map(|x| &x[..])
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|x| &x[..])
+    }
+
+    pub fn into_owned(self) -> NamesCommand<'static> {
+        NamesCommand {
+            channels: self.channels.map(|x| Cow::Owned(x.into_owned())),
+            target: self.target.map(|x| Cow::Owned(x.into_owned())),
+        }
     }
 
-    pub fn target(&self) -> Option<&'a str> {
-        self.target
+    pub fn as_borrowed<'b>(&'b self) -> NamesCommand<'b> {
+        NamesCommand {
+            channels: self.channels.as_ref().map(|x| Cow::Borrowed(&x[..])),
+            target: self.target.as_ref().map(|x| Cow::Borrowed(&x[..])),
+        }
     }
 }
 
@@ -37,10 +52,10 @@ impl<'a> fmt::Display for NamesCommand<'a> {
         try!(write!(f, "{}", CMD_NAMES));
         match self.channels {
             None => Ok(()),
-            Some(chan) => {
+            Some(ref chan) => {
                 match self.target {
-                    None => write!(f, " {}", chan),
-                    Some(t) => write!(f, " {} {}", chan, t),
+                    None => write!(f, " {}", LastParam(chan)),
+                    Some(ref t) => write!(f, " {} {}", chan, LastParam(t)),
                 }
             }
         }
@@ -49,13 +64,17 @@ impl<'a> fmt::Display for NamesCommand<'a> {
 
 impl<'a> IrcMessage<'a> for NamesCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<NamesCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
-        let chan = match params.next() {
+        let chan = params.next();
+        let target = match chan {
             None => None,
-            Some(chan) => Some((chan, params.next())),
+            Some(..) => params.next(),
         };
 
-        Ok(NamesCommand::new(chan))
+        Ok(NamesCommand {
+            channels: chan,
+            target: target,
+        })
     }
 }
@@ -1,30 +1,41 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_NICK;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
-/// `NICK` command is used to give user a nickname or change the existing
-/// one.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct NickCommand<'a> {
-    nickname: &'a str,
+    nickname: Cow<'a, str>,
 }
 
 impl<'a> NickCommand<'a> {
     pub fn new(nick: &'a str) -> NickCommand<'a> {
         NickCommand {
-            nickname: nick,
+            nickname: Cow::Borrowed(nick),
         }
     }
 
-    pub fn nickname(&'a self) -> &'a str {
+    pub fn nickname(&self) -> &str {
         &self.nickname
     }
+
+    pub fn into_owned(self) -> NickCommand<'static> {
+        NickCommand {
+            nickname: Cow::Owned(self.nickname.into_owned()),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> NickCommand<'b> {
+        NickCommand {
+            nickname: Cow::Borrowed(&self.nickname[..]),
+        }
+    }
 }
 
 impl<'a> fmt::Display for NickCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {}", CMD_NICK, self.nickname)
+        write!(f, "{} {}", CMD_NICK, LastParam(&self.nickname))
     }
 }
 
@@ -32,11 +43,11 @@ impl<'a> IrcMessage<'a> for NickCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<NickCommand<'a>, ParseMessageError> {
         debug_assert!(raw.command() == CMD_NICK);
 
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         match params.next() {
             None => Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
                                                "NICK command needs one parameter")),
-            Some(pwd) => Ok(NickCommand::new(pwd)),
+            Some(nick) => Ok(NickCommand { nickname: nick }),
         }
     }
 }
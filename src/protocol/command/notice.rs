@@ -1,28 +1,52 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_NOTICE;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::validate::{ValidationError, validate_target, validate_trailing};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct NoticeCommand<'a> {
-    msgtarget: &'a str,
-    msg: &'a str,
+    msgtarget: Cow<'a, str>,
+    msg: Cow<'a, str>,
 }
 
 impl<'a> NoticeCommand<'a> {
     pub fn new(msgtarget: &'a str, msg: &'a str) -> NoticeCommand<'a> {
         NoticeCommand {
-            msgtarget: msgtarget,
-            msg: msg,
+            msgtarget: Cow::Borrowed(msgtarget),
+            msg: Cow::Borrowed(msg),
         }
     }
 
-    pub fn target(&self) -> &'a str {
-        self.msgtarget
+    /// Create a command after checking that the target and the message can
+    /// be sent as is
+    pub fn try_new(msgtarget: &'a str, msg: &'a str) -> Result<NoticeCommand<'a>, ValidationError> {
+        try!(validate_target(msgtarget, 0));
+        try!(validate_trailing(msg, 1));
+        Ok(NoticeCommand::new(msgtarget, msg))
+    }
+
+    pub fn target(&self) -> &str {
+        &self.msgtarget
+    }
+
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    pub fn into_owned(self) -> NoticeCommand<'static> {
+        NoticeCommand {
+            msgtarget: Cow::Owned(self.msgtarget.into_owned()),
+            msg: Cow::Owned(self.msg.into_owned()),
+        }
     }
 
-    pub fn message(&self) -> &'a str {
-        self.msg
+    pub fn as_borrowed<'b>(&'b self) -> NoticeCommand<'b> {
+        NoticeCommand {
+            msgtarget: Cow::Borrowed(&self.msgtarget[..]),
+            msg: Cow::Borrowed(&self.msg[..]),
+        }
     }
 }
 
@@ -34,7 +58,7 @@ impl<'a> fmt::Display for NoticeCommand<'a> {
 
 impl<'a> IrcMessage<'a> for NoticeCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<NoticeCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let target = match params.next() {
             None => {
                 return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
@@ -51,6 +75,9 @@ impl<'a> IrcMessage<'a> for NoticeCommand<'a> {
             Some(m) => m,
         };
 
-        Ok(NoticeCommand::new(target, msg))
+        Ok(NoticeCommand {
+            msgtarget: target,
+            msg: msg,
+        })
     }
 }
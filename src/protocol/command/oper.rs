@@ -1,40 +1,55 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_OPER;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct OperCommand<'a> {
-    name: &'a str,
-    password: &'a str,
+    name: Cow<'a, str>,
+    password: Cow<'a, str>,
 }
 
 impl<'a> OperCommand<'a> {
     pub fn new(name: &'a str, password: &'a str) -> OperCommand<'a> {
         OperCommand {
-            name: name,
-            password: password,
+            name: Cow::Borrowed(name),
+            password: Cow::Borrowed(password),
         }
     }
 
-    pub fn name(&self) -> &'a str {
-        self.name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    pub fn into_owned(self) -> OperCommand<'static> {
+        OperCommand {
+            name: Cow::Owned(self.name.into_owned()),
+            password: Cow::Owned(self.password.into_owned()),
+        }
     }
 
-    pub fn password(&self) -> &'a str {
-        self.password
+    pub fn as_borrowed<'b>(&'b self) -> OperCommand<'b> {
+        OperCommand {
+            name: Cow::Borrowed(&self.name[..]),
+            password: Cow::Borrowed(&self.password[..]),
+        }
     }
 }
 
 impl<'a> fmt::Display for OperCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {} {}", CMD_OPER, self.name, self.password)
+        write!(f, "{} {} {}", CMD_OPER, self.name, LastParam(&self.password))
     }
 }
 
 impl<'a> IrcMessage<'a> for OperCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<OperCommand<'a>, ParseMessageError> {
-        let mut param = raw.parameters();
+        let mut param = raw.cow_parameters();
 
         let (name, pwd) = match (param.next(), param.next()) {
             (Some(name), Some(pwd)) => (name, pwd),
@@ -42,6 +57,9 @@ impl<'a> IrcMessage<'a> for OperCommand<'a> {
                                                    "OPER command requires 2 parameters")),
         };
 
-        Ok(OperCommand::new(name, pwd))
+        Ok(OperCommand {
+            name: name,
+            password: pwd,
+        })
     }
 }
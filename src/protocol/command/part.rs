@@ -1,28 +1,49 @@
 use std::fmt;
+use std::borrow::Cow;
 
+use protocol::channel::ChannelName;
 use protocol::command::{CMD_PART, MultipleFieldIter};
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct PartCommand<'a> {
-    channels: &'a str,
-    message: Option<&'a str>,
+    channels: Cow<'a, str>,
+    message: Option<Cow<'a, str>>,
 }
 
 impl<'a> PartCommand<'a> {
     pub fn new(channels: &'a str, message: Option<&'a str>) -> PartCommand<'a> {
         PartCommand {
-            channels: channels,
-            message: message,
+            channels: Cow::Borrowed(channels),
+            message: message.map(Cow::Borrowed),
         }
     }
 
-    pub fn channels(&self) -> MultipleFieldIter<'a> {
-        MultipleFieldIter::wrap(self.channels)
+    pub fn channels(&self) -> MultipleFieldIter<'_> {
+        MultipleFieldIter::wrap(&self.channels)
     }
 
-    pub fn message(&self) -> Option<&'a str> {
-        self.message
+    /// Parse the channels as `ChannelName`s
+    pub fn channel_names(&self) -> Result<Vec<ChannelName<'_>>, ParseMessageError> {
+        ChannelName::parse_list(&self.channels).map_err(|err| err.with_param_index(0))
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref().map(|m| &m[..])
+    }
+
+    pub fn into_owned(self) -> PartCommand<'static> {
+        PartCommand {
+            channels: Cow::Owned(self.channels.into_owned()),
+            message: self.message.map(|m| Cow::Owned(m.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> PartCommand<'b> {
+        PartCommand {
+            channels: Cow::Borrowed(&self.channels[..]),
+            message: self.message.as_ref().map(|m| Cow::Borrowed(&m[..])),
+        }
     }
 }
 
@@ -30,8 +51,8 @@ impl<'a> fmt::Display for PartCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         try!(write!(f, "{} {}", CMD_PART, self.channels));
 
-        if let Some(msg) = self.message {
-            write!(f, " {}", msg)
+        if let Some(ref msg) = self.message {
+            write!(f, " :{}", msg)
         } else {
             Ok(())
         }
@@ -40,7 +61,7 @@ impl<'a> fmt::Display for PartCommand<'a> {
 
 impl<'a> IrcMessage<'a> for PartCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<PartCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
         let chan = match params.next() {
             Some(c) => c,
@@ -50,6 +71,9 @@ impl<'a> IrcMessage<'a> for PartCommand<'a> {
             }
         };
 
-        Ok(PartCommand::new(chan, params.next()))
+        Ok(PartCommand {
+            channels: chan,
+            message: params.next(),
+        })
     }
 }
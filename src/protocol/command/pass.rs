@@ -1,32 +1,41 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_PASS;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
-/// The `PASS` command is used to set a 'connection password'.  The
-/// optional password can and MUST be set before any attempt to register
-/// the connection is made.  Currently this requires that user send a
-/// `PASS` command before sending the `NICK`/`USER` combination.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct PassCommand<'a> {
-    password: &'a str,
+    password: Cow<'a, str>,
 }
 
 impl<'a> PassCommand<'a> {
     pub fn new(pass: &'a str) -> PassCommand<'a> {
         PassCommand {
-            password: pass,
+            password: Cow::Borrowed(pass),
         }
     }
 
-    pub fn password(&'a self) -> &'a str {
+    pub fn password(&self) -> &str {
         &self.password
     }
+
+    pub fn into_owned(self) -> PassCommand<'static> {
+        PassCommand {
+            password: Cow::Owned(self.password.into_owned()),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> PassCommand<'b> {
+        PassCommand {
+            password: Cow::Borrowed(&self.password[..]),
+        }
+    }
 }
 
 impl<'a> fmt::Display for PassCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {}", CMD_PASS, self.password)
+        write!(f, "{} {}", CMD_PASS, LastParam(&self.password))
     }
 }
 
@@ -34,11 +43,11 @@ impl<'a> IrcMessage<'a> for PassCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<PassCommand<'a>, ParseMessageError> {
         debug_assert!(raw.command() == CMD_PASS);
 
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         match params.next() {
             None => Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
                                                "PASS command needs one parameter")),
-            Some(pwd) => Ok(PassCommand::new(pwd)),
+            Some(pwd) => Ok(PassCommand { password: pwd }),
         }
     }
 }
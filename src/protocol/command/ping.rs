@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_PING;
 use protocol::message::{IrcMessage, MessageParamIter, RawMessage, ParseMessageError};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct PingCommand<'a> {
-    servers: &'a str,
+    servers: Cow<'a, str>,
 }
 
 impl<'a> PingCommand<'a> {
     pub fn new(servers: &'a str) -> PingCommand<'a> {
         PingCommand {
-            servers: servers,
+            servers: Cow::Borrowed(servers),
         }
     }
 
-    pub fn servers(&self) -> MessageParamIter<'a> {
-        MessageParamIter::wrap(self.servers)
+    pub fn servers(&self) -> MessageParamIter<'_> {
+        MessageParamIter::wrap(&self.servers)
+    }
+
+    pub fn into_owned(self) -> PingCommand<'static> {
+        PingCommand {
+            servers: Cow::Owned(self.servers.into_owned()),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> PingCommand<'b> {
+        PingCommand {
+            servers: Cow::Borrowed(&self.servers[..]),
+        }
     }
 }
 
@@ -28,6 +41,8 @@ impl<'a> fmt::Display for PingCommand<'a> {
 
 impl<'a> IrcMessage<'a> for PingCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<PingCommand<'a>, ParseMessageError> {
-        Ok(PingCommand::new(raw.parameters().get()))
+        Ok(PingCommand {
+            servers: raw.cow_parameters().get(),
+        })
     }
 }
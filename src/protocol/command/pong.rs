@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_PONG;
 use protocol::message::{IrcMessage, MessageParamIter, RawMessage, ParseMessageError};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct PongCommand<'a> {
-    servers: &'a str,
+    servers: Cow<'a, str>,
 }
 
 impl<'a> PongCommand<'a> {
     pub fn new(servers: &'a str) -> PongCommand<'a> {
         PongCommand {
-            servers: servers,
+            servers: Cow::Borrowed(servers),
         }
     }
 
-    pub fn servers(&self) -> MessageParamIter<'a> {
-        MessageParamIter::wrap(self.servers)
+    pub fn servers(&self) -> MessageParamIter<'_> {
+        MessageParamIter::wrap(&self.servers)
+    }
+
+    pub fn into_owned(self) -> PongCommand<'static> {
+        PongCommand {
+            servers: Cow::Owned(self.servers.into_owned()),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> PongCommand<'b> {
+        PongCommand {
+            servers: Cow::Borrowed(&self.servers[..]),
+        }
     }
 }
 
@@ -28,6 +41,8 @@ impl<'a> fmt::Display for PongCommand<'a> {
 
 impl<'a> IrcMessage<'a> for PongCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<PongCommand<'a>, ParseMessageError> {
-        Ok(PongCommand::new(raw.parameters().get()))
+        Ok(PongCommand {
+            servers: raw.cow_parameters().get(),
+        })
     }
 }
@@ -1,28 +1,52 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_PRIVMSG;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::validate::{ValidationError, validate_target, validate_trailing};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct PrivmsgCommand<'a> {
-    msgtarget: &'a str,
-    msg: &'a str,
+    msgtarget: Cow<'a, str>,
+    msg: Cow<'a, str>,
 }
 
 impl<'a> PrivmsgCommand<'a> {
     pub fn new(msgtarget: &'a str, msg: &'a str) -> PrivmsgCommand<'a> {
         PrivmsgCommand {
-            msgtarget: msgtarget,
-            msg: msg,
+            msgtarget: Cow::Borrowed(msgtarget),
+            msg: Cow::Borrowed(msg),
         }
     }
 
-    pub fn target(&self) -> &'a str {
-        self.msgtarget
+    /// Create a command after checking that the target and the message can
+    /// be sent as is
+    pub fn try_new(msgtarget: &'a str, msg: &'a str) -> Result<PrivmsgCommand<'a>, ValidationError> {
+        try!(validate_target(msgtarget, 0));
+        try!(validate_trailing(msg, 1));
+        Ok(PrivmsgCommand::new(msgtarget, msg))
+    }
+
+    pub fn target(&self) -> &str {
+        &self.msgtarget
+    }
+
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    pub fn into_owned(self) -> PrivmsgCommand<'static> {
+        PrivmsgCommand {
+            msgtarget: Cow::Owned(self.msgtarget.into_owned()),
+            msg: Cow::Owned(self.msg.into_owned()),
+        }
     }
 
-    pub fn message(&self) -> &'a str {
-        self.msg
+    pub fn as_borrowed<'b>(&'b self) -> PrivmsgCommand<'b> {
+        PrivmsgCommand {
+            msgtarget: Cow::Borrowed(&self.msgtarget[..]),
+            msg: Cow::Borrowed(&self.msg[..]),
+        }
     }
 }
 
@@ -34,11 +58,11 @@ impl<'a> fmt::Display for PrivmsgCommand<'a> {
 
 impl<'a> IrcMessage<'a> for PrivmsgCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<PrivmsgCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let target = match params.next() {
             None => {
                 return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
-                                                  "NOTICE requires a target"));
+                                                  "PRIVMSG requires a target"));
             },
             Some(t) => t,
         };
@@ -46,11 +70,14 @@ impl<'a> IrcMessage<'a> for PrivmsgCommand<'a> {
         let msg = match params.next() {
             None => {
                 return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
-                                                  "NOTICE requires a message"));
+                                                  "PRIVMSG requires a message"));
             },
             Some(m) => m,
         };
 
-        Ok(PrivmsgCommand::new(target, msg))
+        Ok(PrivmsgCommand {
+            msgtarget: target,
+            msg: msg,
+        })
     }
 }
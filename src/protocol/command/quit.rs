@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_QUIT;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct QuitCommand<'a> {
-    msg: Option<&'a str>,
+    msg: Option<Cow<'a, str>>,
 }
 
 impl<'a> QuitCommand<'a> {
     pub fn new(msg: Option<&'a str>) -> QuitCommand<'a> {
         QuitCommand {
-            msg: msg,
+            msg: msg.map(Cow::Borrowed),
         }
     }
 
-    pub fn message(&self) -> Option<&'a str> {
-        self.msg
+    pub fn message(&self) -> Option<&str> {
+        self.msg.as_ref().map(|m| &m[..])
+    }
+
+    pub fn into_owned(self) -> QuitCommand<'static> {
+        QuitCommand {
+            msg: self.msg.map(|m| Cow::Owned(m.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> QuitCommand<'b> {
+        QuitCommand {
+            msg: self.msg.as_ref().map(|m| Cow::Borrowed(&m[..])),
+        }
     }
 }
 
@@ -25,7 +38,7 @@ impl<'a> fmt::Display for QuitCommand<'a> {
         try!(write!(f, "{}", CMD_QUIT));
 
         match self.msg {
-            Some(m) => write!(f, " :{}", m),
+            Some(ref m) => write!(f, " :{}", m),
             None => Ok(()),
         }
     }
@@ -33,6 +46,8 @@ impl<'a> fmt::Display for QuitCommand<'a> {
 
 impl<'a> IrcMessage<'a> for QuitCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<QuitCommand<'a>, ParseMessageError> {
-        Ok(QuitCommand::new(raw.parameters().next()))
+        Ok(QuitCommand {
+            msg: raw.cow_parameters().next(),
+        })
     }
 }
@@ -6,6 +6,16 @@ use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct RehashCommand;
 
+impl RehashCommand {
+    pub fn into_owned(self) -> RehashCommand {
+        self
+    }
+
+    pub fn as_borrowed(&self) -> RehashCommand {
+        self.clone()
+    }
+}
+
 impl fmt::Display for RehashCommand {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", CMD_REHASH)
@@ -6,6 +6,16 @@ use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct RestartCommand;
 
+impl RestartCommand {
+    pub fn into_owned(self) -> RestartCommand {
+        self
+    }
+
+    pub fn as_borrowed(&self) -> RestartCommand {
+        self.clone()
+    }
+}
+
 impl fmt::Display for RestartCommand {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", CMD_RESTART)
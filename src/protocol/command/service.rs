@@ -1,53 +1,72 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_SERVICE;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ServiceCommand<'a> {
-    nickname: &'a str,
-    distribution: &'a str,
-    service_type: &'a str,
-    info: &'a str,
+    nickname: Cow<'a, str>,
+    distribution: Cow<'a, str>,
+    service_type: Cow<'a, str>,
+    info: Cow<'a, str>,
 }
 
 impl<'a> ServiceCommand<'a> {
     pub fn new(nickname: &'a str, distribution: &'a str, service_type: &'a str, info: &'a str)
             -> ServiceCommand<'a> {
         ServiceCommand {
-            nickname: nickname,
-            distribution: distribution,
-            service_type: service_type,
-            info: info,
+            nickname: Cow::Borrowed(nickname),
+            distribution: Cow::Borrowed(distribution),
+            service_type: Cow::Borrowed(service_type),
+            info: Cow::Borrowed(info),
         }
     }
 
-    pub fn nickname(&self) -> &'a str {
-        self.nickname
+    pub fn nickname(&self) -> &str {
+        &self.nickname
+    }
+
+    pub fn distribution(&self) -> &str {
+        &self.distribution
+    }
+
+    pub fn service_type(&self) -> &str {
+        &self.service_type
     }
 
-    pub fn distribution(&self) -> &'a str {
-        self.distribution
+    pub fn info(&self) -> &str {
+        &self.info
     }
 
-    pub fn service_type(&self) -> &'a str {
-        self.service_type
+    pub fn into_owned(self) -> ServiceCommand<'static> {
+        ServiceCommand {
+            nickname: Cow::Owned(self.nickname.into_owned()),
+            distribution: Cow::Owned(self.distribution.into_owned()),
+            service_type: Cow::Owned(self.service_type.into_owned()),
+            info: Cow::Owned(self.info.into_owned()),
+        }
     }
 
-    pub fn info(&self) -> &'a str {
-        self.info
+    pub fn as_borrowed<'b>(&'b self) -> ServiceCommand<'b> {
+        ServiceCommand {
+            nickname: Cow::Borrowed(&self.nickname[..]),
+            distribution: Cow::Borrowed(&self.distribution[..]),
+            service_type: Cow::Borrowed(&self.service_type[..]),
+            info: Cow::Borrowed(&self.info[..]),
+        }
     }
 }
 
 impl<'a> fmt::Display for ServiceCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {} * {} {} * {}", CMD_SERVICE, self.nickname, self.distribution, self.service_type, self.info)
+        write!(f, "{} {} * {} {} * :{}", CMD_SERVICE, self.nickname, self.distribution, self.service_type, self.info)
     }
 }
 
 impl<'a> IrcMessage<'a> for ServiceCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<ServiceCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
         let (nick, dist, service_type, info) =
             match (params.next(), params.next(), params.next(), params.next(), params.next(), params.next()) {
@@ -58,6 +77,11 @@ impl<'a> IrcMessage<'a> for ServiceCommand<'a> {
                 }
             };
 
-        Ok(ServiceCommand::new(nick, dist, service_type, info))
+        Ok(ServiceCommand {
+            nickname: nick,
+            distribution: dist,
+            service_type: service_type,
+            info: info,
+        })
     }
 }
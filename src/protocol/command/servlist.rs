@@ -1,12 +1,13 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_SERVLIST;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct ServlistCommand<'a> {
-    mask: Option<&'a str>,
-    stype: Option<&'a str>,
+    mask: Option<Cow<'a, str>>,
+    stype: Option<Cow<'a, str>>,
 }
 
 impl<'a> ServlistCommand<'a> {
@@ -18,17 +19,31 @@ impl<'a> ServlistCommand<'a> {
             };
 
         ServlistCommand {
-            mask: chan,
-            stype: tar,
+            mask: chan.map(Cow::Borrowed),
+            stype: tar.map(Cow::Borrowed),
         }
     }
 
-    pub fn mask(&self) -> Option<&'a str> {
-        self.mask
+    pub fn mask(&self) -> Option<&str> {
+        self.mask.as_ref().map(|x| &x[..])
     }
 
-    pub fn server_type(&self) -> Option<&'a str> {
-        self.stype
+    pub fn server_type(&self) -> Option<&str> {
+        self.stype.as_ref().map(|x| &x[..])
+    }
+
+    pub fn into_owned(self) -> ServlistCommand<'static> {
+        ServlistCommand {
+            mask: self.mask.map(|x| Cow::Owned(x.into_owned())),
+            stype: self.stype.map(|x| Cow::Owned(x.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> ServlistCommand<'b> {
+        ServlistCommand {
+            mask: self.mask.as_ref().map(|x| Cow::Borrowed(&x[..])),
+            stype: self.stype.as_ref().map(|x| Cow::Borrowed(&x[..])),
+        }
     }
 }
 
@@ -37,10 +52,10 @@ impl<'a> fmt::Display for ServlistCommand<'a> {
         try!(write!(f, "{}", CMD_SERVLIST));
         match self.mask {
             None => Ok(()),
-            Some(chan) => {
+            Some(ref chan) => {
                 match self.stype {
-                    None => write!(f, " {}", chan),
-                    Some(t) => write!(f, " {} {}", chan, t),
+                    None => write!(f, " {}", LastParam(chan)),
+                    Some(ref t) => write!(f, " {} {}", chan, LastParam(t)),
                 }
             }
         }
@@ -49,13 +64,17 @@ impl<'a> fmt::Display for ServlistCommand<'a> {
 
 impl<'a> IrcMessage<'a> for ServlistCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<ServlistCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
-        let chan = match params.next() {
+        let mask = params.next();
+        let stype = match mask {
             None => None,
-            Some(chan) => Some((chan, params.next())),
+            Some(..) => params.next(),
         };
 
-        Ok(ServlistCommand::new(chan))
+        Ok(ServlistCommand {
+            mask: mask,
+            stype: stype,
+        })
     }
 }
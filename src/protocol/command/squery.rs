@@ -1,28 +1,43 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_SQUERY;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SqueryCommand<'a> {
-    servicename: &'a str,
-    text: &'a str,
+    servicename: Cow<'a, str>,
+    text: Cow<'a, str>,
 }
 
 impl<'a> SqueryCommand<'a> {
     pub fn new(servicename: &'a str, text: &'a str) -> SqueryCommand<'a> {
         SqueryCommand {
-            servicename: servicename,
-            text: text,
+            servicename: Cow::Borrowed(servicename),
+            text: Cow::Borrowed(text),
         }
     }
 
-    pub fn service_name(&self) -> &'a str {
-        self.servicename
+    pub fn service_name(&self) -> &str {
+        &self.servicename
     }
 
-    pub fn text(&self) -> &'a str {
-        self.text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_owned(self) -> SqueryCommand<'static> {
+        SqueryCommand {
+            servicename: Cow::Owned(self.servicename.into_owned()),
+            text: Cow::Owned(self.text.into_owned()),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> SqueryCommand<'b> {
+        SqueryCommand {
+            servicename: Cow::Borrowed(&self.servicename[..]),
+            text: Cow::Borrowed(&self.text[..]),
+        }
     }
 }
 
@@ -34,7 +49,7 @@ impl<'a> fmt::Display for SqueryCommand<'a> {
 
 impl<'a> IrcMessage<'a> for SqueryCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<SqueryCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let (sn, t) =
             match (params.next(), params.next()) {
                 (Some(sn), Some(t)) => (sn, t),
@@ -44,6 +59,9 @@ impl<'a> IrcMessage<'a> for SqueryCommand<'a> {
                 }
             };
 
-        Ok(SqueryCommand::new(sn, t))
+        Ok(SqueryCommand {
+            servicename: sn,
+            text: t,
+        })
     }
 }
@@ -1,28 +1,43 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_SQUIT;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SQuitCommand<'a> {
-    server: &'a str,
-    msg: Option<&'a str>,
+    server: Cow<'a, str>,
+    msg: Option<Cow<'a, str>>,
 }
 
 impl<'a> SQuitCommand<'a> {
     pub fn new(server: &'a str, msg: Option<&'a str>) -> SQuitCommand<'a> {
         SQuitCommand {
-            server: server,
-            msg: msg,
+            server: Cow::Borrowed(server),
+            msg: msg.map(Cow::Borrowed),
         }
     }
 
-    pub fn server(&self) -> &'a str {
-        self.server
+    pub fn server(&self) -> &str {
+        &self.server
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.msg.as_ref().map(|m| &m[..])
+    }
+
+    pub fn into_owned(self) -> SQuitCommand<'static> {
+        SQuitCommand {
+            server: Cow::Owned(self.server.into_owned()),
+            msg: self.msg.map(|m| Cow::Owned(m.into_owned())),
+        }
     }
 
-    pub fn message(&self) -> Option<&'a str> {
-        self.msg
+    pub fn as_borrowed<'b>(&'b self) -> SQuitCommand<'b> {
+        SQuitCommand {
+            server: Cow::Borrowed(&self.server[..]),
+            msg: self.msg.as_ref().map(|m| Cow::Borrowed(&m[..])),
+        }
     }
 }
 
@@ -31,7 +46,7 @@ impl<'a> fmt::Display for SQuitCommand<'a> {
         try!(write!(f, "{} {}", CMD_SQUIT, self.server));
 
         match self.msg {
-            Some(m) => write!(f, " :{}", m),
+            Some(ref m) => write!(f, " :{}", m),
             None => Ok(()),
         }
     }
@@ -39,7 +54,7 @@ impl<'a> fmt::Display for SQuitCommand<'a> {
 
 impl<'a> IrcMessage<'a> for SQuitCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<SQuitCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
         let (svr, msg) = match (params.next(), params.next()) {
             (Some(svr), Some(msg)) => (svr, Some(msg)),
@@ -50,6 +65,9 @@ impl<'a> IrcMessage<'a> for SQuitCommand<'a> {
             }
         };
 
-        Ok(SQuitCommand::new(svr, msg))
+        Ok(SQuitCommand {
+            server: svr,
+            msg: msg,
+        })
     }
 }
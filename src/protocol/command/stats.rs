@@ -1,12 +1,13 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_STATS;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StatsCommand<'a> {
-    query: Option<&'a str>,
-    target: Option<&'a str>,
+    query: Option<Cow<'a, str>>,
+    target: Option<Cow<'a, str>>,
 }
 
 impl<'a> StatsCommand<'a> {
@@ -18,17 +19,31 @@ impl<'a> StatsCommand<'a> {
             };
 
         StatsCommand {
-            query: query,
-            target: tar,
+            query: query.map(Cow::Borrowed),
+            target: tar.map(Cow::Borrowed),
         }
     }
 
-    pub fn query(&self) -> Option<&'a str> {
-        self.query
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_ref().map(|x| &x[..])
     }
 
-    pub fn target(&self) -> Option<&'a str> {
-        self.target
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|x| &x[..])
+    }
+
+    pub fn into_owned(self) -> StatsCommand<'static> {
+        StatsCommand {
+            query: self.query.map(|x| Cow::Owned(x.into_owned())),
+            target: self.target.map(|x| Cow::Owned(x.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> StatsCommand<'b> {
+        StatsCommand {
+            query: self.query.as_ref().map(|x| Cow::Borrowed(&x[..])),
+            target: self.target.as_ref().map(|x| Cow::Borrowed(&x[..])),
+        }
     }
 }
 
@@ -37,10 +52,10 @@ impl<'a> fmt::Display for StatsCommand<'a> {
         try!(write!(f, "{}", CMD_STATS));
         match self.query {
             None => Ok(()),
-            Some(q) => {
+            Some(ref q) => {
                 match self.target {
-                    None => write!(f, " {}", q),
-                    Some(t) => write!(f, " {} {}", q, t),
+                    None => write!(f, " {}", LastParam(q)),
+                    Some(ref t) => write!(f, " {} {}", q, LastParam(t)),
                 }
             }
         }
@@ -49,13 +64,17 @@ impl<'a> fmt::Display for StatsCommand<'a> {
 
 impl<'a> IrcMessage<'a> for StatsCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<StatsCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
-        let q = match params.next() {
+        let q = params.next();
+        let target = match q {
             None => None,
-            Some(q) => Some((q, params.next())),
+            Some(..) => params.next(),
         };
 
-        Ok(StatsCommand::new(q))
+        Ok(StatsCommand {
+            query: q,
+            target: target,
+        })
     }
 }
@@ -1,17 +1,18 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::{CMD_SUMMON, MultipleFieldIter};
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SummonCommand<'a> {
-    user: &'a str,
-    target: Option<&'a str>,
-    channel: Option<&'a str>,
+    user: Cow<'a, str>,
+    target: Option<Cow<'a, str>>,
+    channel: Option<Cow<'a, str>>,
 }
 
 impl<'a> SummonCommand<'a> {
-    fn new(user: &'a str, target_with_channel: Option<(&'a str, Option<&'a str>)>) -> SummonCommand<'a> {
+    pub fn new(user: &'a str, target_with_channel: Option<(&'a str, Option<&'a str>)>) -> SummonCommand<'a> {
         let (cnt, t) =
             match target_with_channel {
                 None => (None, None),
@@ -19,22 +20,38 @@ impl<'a> SummonCommand<'a> {
             };
 
         SummonCommand {
-            user: user,
-            target: cnt,
-            channel: t,
+            user: Cow::Borrowed(user),
+            target: cnt.map(Cow::Borrowed),
+            channel: t.map(Cow::Borrowed),
         }
     }
 
-    pub fn user(&self) -> MultipleFieldIter<'a> {
-        MultipleFieldIter::wrap(self.user)
+    pub fn user(&self) -> MultipleFieldIter<'_> {
+        MultipleFieldIter::wrap(&self.user)
+    }
+
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|t| &t[..])
     }
 
-    pub fn target(&self) -> Option<&'a str> {
-        self.target
+    pub fn channel(&self) -> Option<&str> {
+        self.channel.as_ref().map(|c| &c[..])
     }
 
-    pub fn channel(&self) -> Option<&'a str> {
-        self.channel
+    pub fn into_owned(self) -> SummonCommand<'static> {
+        SummonCommand {
+            user: Cow::Owned(self.user.into_owned()),
+            target: self.target.map(|t| Cow::Owned(t.into_owned())),
+            channel: self.channel.map(|c| Cow::Owned(c.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> SummonCommand<'b> {
+        SummonCommand {
+            user: Cow::Borrowed(&self.user[..]),
+            target: self.target.as_ref().map(|t| Cow::Borrowed(&t[..])),
+            channel: self.channel.as_ref().map(|c| Cow::Borrowed(&c[..])),
+        }
     }
 }
 
@@ -44,10 +61,10 @@ impl<'a> fmt::Display for SummonCommand<'a> {
 
         match self.target {
             None => Ok(()),
-            Some(c) => {
+            Some(ref c) => {
                 match self.channel {
-                    None => write!(f, " {}", c),
-                    Some(t) => write!(f, " {} {}", c, t),
+                    None => write!(f, " {}", LastParam(c)),
+                    Some(ref t) => write!(f, " {} {}", c, LastParam(t)),
                 }
             }
         }
@@ -56,7 +73,7 @@ impl<'a> fmt::Display for SummonCommand<'a> {
 
 impl<'a> IrcMessage<'a> for SummonCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<SummonCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let (nm, c, t) =
             match (params.next(), params.next(), params.next()) {
                 (Some(nm), Some(c), t) => (nm, Some(c), t),
@@ -67,11 +84,10 @@ impl<'a> IrcMessage<'a> for SummonCommand<'a> {
                 }
             };
 
-        let cnt = match c {
-            None => None,
-            Some(c) => Some((c, t)),
-        };
-
-        Ok(SummonCommand::new(nm, cnt))
+        Ok(SummonCommand {
+            user: nm,
+            target: c,
+            channel: t,
+        })
     }
 }
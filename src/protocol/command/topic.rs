@@ -1,28 +1,49 @@
 use std::fmt;
+use std::borrow::Cow;
 
+use protocol::channel::ChannelName;
 use protocol::command::CMD_TOPIC;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct TopicCommand<'a> {
-    channel: &'a str,
-    topic: Option<&'a str>,
+    channel: Cow<'a, str>,
+    topic: Option<Cow<'a, str>>,
 }
 
 impl<'a> TopicCommand<'a> {
     pub fn new(channel: &'a str, topic: Option<&'a str>) -> TopicCommand<'a> {
         TopicCommand {
-            channel: channel,
-            topic: topic,
+            channel: Cow::Borrowed(channel),
+            topic: topic.map(Cow::Borrowed),
         }
     }
 
-    pub fn channel(&self) -> &'a str {
-        self.channel
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Parse the channel as a `ChannelName`
+    pub fn channel_name(&self) -> Result<ChannelName<'_>, ParseMessageError> {
+        ChannelName::parse(&self.channel).map_err(|err| err.with_param_index(0))
+    }
+
+    pub fn topic(&self) -> Option<&str> {
+        self.topic.as_ref().map(|t| &t[..])
+    }
+
+    pub fn into_owned(self) -> TopicCommand<'static> {
+        TopicCommand {
+            channel: Cow::Owned(self.channel.into_owned()),
+            topic: self.topic.map(|t| Cow::Owned(t.into_owned())),
+        }
     }
 
-    pub fn topic(&self) -> Option<&'a str> {
-        self.topic
+    pub fn as_borrowed<'b>(&'b self) -> TopicCommand<'b> {
+        TopicCommand {
+            channel: Cow::Borrowed(&self.channel[..]),
+            topic: self.topic.as_ref().map(|t| Cow::Borrowed(&t[..])),
+        }
     }
 }
 
@@ -31,14 +52,14 @@ impl<'a> fmt::Display for TopicCommand<'a> {
         try!(write!(f, "{} {}", CMD_TOPIC, self.channel));
         match self.topic {
             None => Ok(()),
-            Some(t) => write!(f, " :{}", t),
+            Some(ref t) => write!(f, " :{}", t),
         }
     }
 }
 
 impl<'a> IrcMessage<'a> for TopicCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<TopicCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let channel = match params.next() {
             None => {
                 return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
@@ -47,6 +68,9 @@ impl<'a> IrcMessage<'a> for TopicCommand<'a> {
             Some(t) => t,
         };
 
-        Ok(TopicCommand::new(channel, params.next()))
+        Ok(TopicCommand {
+            channel: channel,
+            topic: params.next(),
+        })
     }
 }
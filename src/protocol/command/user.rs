@@ -1,13 +1,14 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_USER;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct UserCommand<'a> {
-    user: &'a str,
+    user: Cow<'a, str>,
     mode: u16,
-    realname: &'a str,
+    realname: Cow<'a, str>,
 }
 
 impl<'a> UserCommand<'a> {
@@ -15,13 +16,13 @@ impl<'a> UserCommand<'a> {
         debug_assert!((mode & !0x6) == 0, "Only bit 2 and 3 having any signification");
 
         UserCommand {
-            user: user,
+            user: Cow::Borrowed(user),
             mode: mode,
-            realname: realname,
+            realname: Cow::Borrowed(realname),
         }
     }
 
-    pub fn user(&'a self) -> &'a str {
+    pub fn user(&self) -> &str {
         &self.user
     }
 
@@ -29,9 +30,25 @@ impl<'a> UserCommand<'a> {
         self.mode
     }
 
-    pub fn realname(&'a self) -> &'a str {
+    pub fn realname(&self) -> &str {
         &self.realname
     }
+
+    pub fn into_owned(self) -> UserCommand<'static> {
+        UserCommand {
+            user: Cow::Owned(self.user.into_owned()),
+            mode: self.mode,
+            realname: Cow::Owned(self.realname.into_owned()),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> UserCommand<'b> {
+        UserCommand {
+            user: Cow::Borrowed(&self.user[..]),
+            mode: self.mode,
+            realname: Cow::Borrowed(&self.realname[..]),
+        }
+    }
 }
 
 impl<'a> fmt::Display for UserCommand<'a> {
@@ -44,14 +61,14 @@ impl<'a> IrcMessage<'a> for UserCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<UserCommand<'a>, ParseMessageError> {
         debug_assert!(raw.command() == CMD_USER);
 
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let (user, mode, realname) = match (params.next(), params.next(), params.next(), params.next()) {
             (Some(user), Some(mode), _, Some(realname)) => {
                 let mode = match mode.parse::<u16>() {
                     Ok(m) => m,
                     Err(..) => {
                         return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
-                                                          "Invalid mode"));
+                                                          "Invalid mode").with_param_index(1));
                     }
                 };
 
@@ -63,6 +80,10 @@ impl<'a> IrcMessage<'a> for UserCommand<'a> {
             }
         };
 
-        Ok(UserCommand::new(user, mode, realname))
+        Ok(UserCommand {
+            user: user,
+            mode: mode,
+            realname: realname,
+        })
     }
 }
@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_USERHOST;
 use protocol::message::{IrcMessage, MessageParamIter, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct UserhostCommand<'a> {
-    nicknames: &'a str,
+    nicknames: Cow<'a, str>,
 }
 
 impl<'a> UserhostCommand<'a> {
     pub fn new(nicknames: &'a str) -> UserhostCommand<'a> {
         UserhostCommand {
-            nicknames: nicknames,
+            nicknames: Cow::Borrowed(nicknames),
+        }
+    }
+
+    pub fn nicknames(&self) -> MessageParamIter<'_> {
+        MessageParamIter::wrap(&self.nicknames)
+    }
+
+    pub fn into_owned(self) -> UserhostCommand<'static> {
+        UserhostCommand {
+            nicknames: Cow::Owned(self.nicknames.into_owned()),
         }
     }
 
-    pub fn nicknames(&self) -> MessageParamIter<'a> {
-        MessageParamIter::wrap(self.nicknames)
+    pub fn as_borrowed<'b>(&'b self) -> UserhostCommand<'b> {
+        UserhostCommand {
+            nicknames: Cow::Borrowed(&self.nicknames[..]),
+        }
     }
 }
 
@@ -28,13 +41,15 @@ impl<'a> fmt::Display for UserhostCommand<'a> {
 
 impl<'a> IrcMessage<'a> for UserhostCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<UserhostCommand<'a>, ParseMessageError> {
-        let nicknames = raw.parameters().get();
+        let nicknames = raw.cow_parameters().get();
 
         if nicknames.is_empty() {
             return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
                                               "USERHOST requires at least one nickname"));
         }
 
-        Ok(UserhostCommand::new(nicknames))
+        Ok(UserhostCommand {
+            nicknames: nicknames,
+        })
     }
 }
@@ -1,22 +1,35 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_WALLOPS;
 use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct WallopsCommand<'a> {
-    msg: &'a str,
+    msg: Cow<'a, str>,
 }
 
 impl<'a> WallopsCommand<'a> {
     pub fn new(msg: &'a str) -> WallopsCommand<'a> {
         WallopsCommand {
-            msg: msg,
+            msg: Cow::Borrowed(msg),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    pub fn into_owned(self) -> WallopsCommand<'static> {
+        WallopsCommand {
+            msg: Cow::Owned(self.msg.into_owned()),
         }
     }
 
-    pub fn message(&self) -> &'a str {
-        self.msg
+    pub fn as_borrowed<'b>(&'b self) -> WallopsCommand<'b> {
+        WallopsCommand {
+            msg: Cow::Borrowed(&self.msg[..]),
+        }
     }
 }
 
@@ -28,7 +41,7 @@ impl<'a> fmt::Display for WallopsCommand<'a> {
 
 impl<'a> IrcMessage<'a> for WallopsCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<WallopsCommand<'a>, ParseMessageError> {
-        let msg = match raw.parameters().next() {
+        let msg = match raw.cow_parameters().next() {
             None => {
                 return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
                                                   "WALLOPS command needs a message"));
@@ -36,6 +49,8 @@ impl<'a> IrcMessage<'a> for WallopsCommand<'a> {
             Some(m) => m,
         };
 
-        Ok(WallopsCommand::new(msg))
+        Ok(WallopsCommand {
+            msg: msg,
+        })
     }
 }
@@ -1,11 +1,12 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::CMD_WHO;
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct WhoCommand<'a> {
-    mask: Option<&'a str>,
+    mask: Option<Cow<'a, str>>,
     operator_only: bool,
 }
 
@@ -17,18 +18,32 @@ impl<'a> WhoCommand<'a> {
         };
 
         WhoCommand {
-            mask: m,
+            mask: m.map(Cow::Borrowed),
             operator_only: o,
         }
     }
 
-    pub fn mask(&self) -> Option<&'a str> {
-        self.mask
+    pub fn mask(&self) -> Option<&str> {
+        self.mask.as_ref().map(|m| &m[..])
     }
 
     pub fn is_operator_only(&self) -> bool {
         self.operator_only
     }
+
+    pub fn into_owned(self) -> WhoCommand<'static> {
+        WhoCommand {
+            mask: self.mask.map(|m| Cow::Owned(m.into_owned())),
+            operator_only: self.operator_only,
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> WhoCommand<'b> {
+        WhoCommand {
+            mask: self.mask.as_ref().map(|m| Cow::Borrowed(&m[..])),
+            operator_only: self.operator_only,
+        }
+    }
 }
 
 impl<'a> fmt::Display for WhoCommand<'a> {
@@ -36,11 +51,11 @@ impl<'a> fmt::Display for WhoCommand<'a> {
         try!(write!(f, "{}", CMD_WHO));
         match self.mask {
             None => Ok(()),
-            Some(m) => {
+            Some(ref m) => {
                 if self.operator_only {
                     write!(f, " {} o", m)
                 } else {
-                    write!(f, " {}", m)
+                    write!(f, " {}", LastParam(m))
                 }
             }
         }
@@ -49,21 +64,24 @@ impl<'a> fmt::Display for WhoCommand<'a> {
 
 impl<'a> IrcMessage<'a> for WhoCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<WhoCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
-        let mask = match (params.next(), params.next()) {
+        let (mask, op) = match (params.next(), params.next()) {
             (Some(m), Some(op)) => {
                 if op != "o" {
                     return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
-                                                      "WHO only accepts \"o\" as the last parameter"));
+                                                      "WHO only accepts \"o\" as the last parameter").with_param_index(1));
                 }
 
-                Some((m, true))
+                (Some(m), true)
             },
-            (Some(m), None) => Some((m, false)),
-            _ => None,
+            (Some(m), None) => (Some(m), false),
+            _ => (None, false),
         };
 
-        Ok(WhoCommand::new(mask))
+        Ok(WhoCommand {
+            mask: mask,
+            operator_only: op,
+        })
     }
 }
@@ -1,28 +1,43 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::{CMD_WHOIS, MultipleFieldIter};
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct WhoisCommand<'a> {
-    target: Option<&'a str>,
-    masks: &'a str,
+    target: Option<Cow<'a, str>>,
+    masks: Cow<'a, str>,
 }
 
 impl<'a> WhoisCommand<'a> {
     pub fn new(target: Option<&'a str>, masks: &'a str) -> WhoisCommand<'a> {
         WhoisCommand {
-            target: target,
-            masks: masks,
+            target: target.map(Cow::Borrowed),
+            masks: Cow::Borrowed(masks),
         }
     }
 
-    pub fn target(&self) -> Option<&'a str> {
-        self.target
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|t| &t[..])
+    }
+
+    pub fn masks(&self) -> MultipleFieldIter<'_> {
+        MultipleFieldIter::wrap(&self.masks)
+    }
+
+    pub fn into_owned(self) -> WhoisCommand<'static> {
+        WhoisCommand {
+            target: self.target.map(|t| Cow::Owned(t.into_owned())),
+            masks: Cow::Owned(self.masks.into_owned()),
+        }
     }
 
-    pub fn masks(&self) -> MultipleFieldIter<'a> {
-        MultipleFieldIter::wrap(self.masks)
+    pub fn as_borrowed<'b>(&'b self) -> WhoisCommand<'b> {
+        WhoisCommand {
+            target: self.target.as_ref().map(|t| Cow::Borrowed(&t[..])),
+            masks: Cow::Borrowed(&self.masks[..]),
+        }
     }
 }
 
@@ -30,17 +45,17 @@ impl<'a> fmt::Display for WhoisCommand<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         try!(write!(f, "{}", CMD_WHOIS));
 
-        if let Some(t) = self.target {
+        if let Some(ref t) = self.target {
             try!(write!(f, " {}", t));
         }
 
-        write!(f, " {}", self.masks)
+        write!(f, " {}", LastParam(&self.masks))
     }
 }
 
 impl<'a> IrcMessage<'a> for WhoisCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<WhoisCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
 
         let (target, masks) = match (params.next(), params.next()) {
             (Some(t), Some(m)) => (Some(t), m),
@@ -51,6 +66,9 @@ impl<'a> IrcMessage<'a> for WhoisCommand<'a> {
             }
         };
 
-        Ok(WhoisCommand::new(target, masks))
+        Ok(WhoisCommand {
+            target: target,
+            masks: masks,
+        })
     }
 }
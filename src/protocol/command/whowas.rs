@@ -1,17 +1,18 @@
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::command::{CMD_WHOWAS, MultipleFieldIter};
-use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind};
+use protocol::message::{IrcMessage, RawMessage, ParseMessageError, ParseMessageErrorKind, LastParam};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct WhowasCommand<'a> {
-    nicknames: &'a str,
+    nicknames: Cow<'a, str>,
     count: Option<u32>,
-    target: Option<&'a str>,
+    target: Option<Cow<'a, str>>,
 }
 
 impl<'a> WhowasCommand<'a> {
-    fn new(nicknames: &'a str, count_with_target: Option<(u32, Option<&'a str>)>) -> WhowasCommand<'a> {
+    pub fn new(nicknames: &'a str, count_with_target: Option<(u32, Option<&'a str>)>) -> WhowasCommand<'a> {
         let (cnt, t) =
             match count_with_target {
                 None => (None, None),
@@ -19,22 +20,38 @@ impl<'a> WhowasCommand<'a> {
             };
 
         WhowasCommand {
-            nicknames: nicknames,
+            nicknames: Cow::Borrowed(nicknames),
             count: cnt,
-            target: t,
+            target: t.map(Cow::Borrowed),
         }
     }
 
-    pub fn nicknames(&self) -> MultipleFieldIter<'a> {
-        MultipleFieldIter::wrap(self.nicknames)
+    pub fn nicknames(&self) -> MultipleFieldIter<'_> {
+        MultipleFieldIter::wrap(&self.nicknames)
     }
 
     pub fn count(&self) -> Option<u32> {
         self.count
     }
 
-    pub fn target(&self) -> Option<&'a str> {
-        self.target
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_ref().map(|t| &t[..])
+    }
+
+    pub fn into_owned(self) -> WhowasCommand<'static> {
+        WhowasCommand {
+            nicknames: Cow::Owned(self.nicknames.into_owned()),
+            count: self.count,
+            target: self.target.map(|t| Cow::Owned(t.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> WhowasCommand<'b> {
+        WhowasCommand {
+            nicknames: Cow::Borrowed(&self.nicknames[..]),
+            count: self.count,
+            target: self.target.as_ref().map(|t| Cow::Borrowed(&t[..])),
+        }
     }
 }
 
@@ -47,7 +64,7 @@ impl<'a> fmt::Display for WhowasCommand<'a> {
             Some(c) => {
                 match self.target {
                     None => write!(f, " {}", c),
-                    Some(t) => write!(f, " {} {}", c, t),
+                    Some(ref t) => write!(f, " {} {}", c, LastParam(t)),
                 }
             }
         }
@@ -56,14 +73,14 @@ impl<'a> fmt::Display for WhowasCommand<'a> {
 
 impl<'a> IrcMessage<'a> for WhowasCommand<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<WhowasCommand<'a>, ParseMessageError> {
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let (nm, c, t) =
             match (params.next(), params.next(), params.next()) {
                 (Some(nm), Some(c), t) => {
                     let c = match c.parse::<u32>() {
                         Err(..) => {
                             return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
-                                                              "Count should be a valid number"));
+                                                              "Count should be a valid number").with_param_index(1));
                         },
                         Ok(n) => n,
                     };
@@ -77,11 +94,10 @@ impl<'a> IrcMessage<'a> for WhowasCommand<'a> {
                 }
             };
 
-        let cnt = match c {
-            None => None,
-            Some(c) => Some((c, t)),
-        };
-
-        Ok(WhowasCommand::new(nm, cnt))
+        Ok(WhowasCommand {
+            nicknames: nm,
+            count: c,
+            target: t,
+        })
     }
 }
@@ -0,0 +1,512 @@
+//! Client-To-Client Protocol, carried in the text of `PRIVMSG` and `NOTICE`
+//!
+//! A request is sent in a `PRIVMSG` and answered in a `NOTICE`, the whole
+//! text being a single CTCP message delimited by `\x01`:
+//!
+//! ```plain
+//! PRIVMSG WiZ :\x01VERSION\x01
+//! NOTICE Angel :\x01VERSION rustirc 0.1\x01
+//! ```
+//!
+//! The text is quoted twice. The low-level quoting escapes `NUL`, `CR` and
+//! `LF` with `\x10`, the CTCP-level quoting escapes `\x01` and `\` with `\`.
+
+use std::fmt;
+use std::borrow::Cow;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use protocol::command::{PrivmsgCommand, NoticeCommand};
+
+/// Delimiter of CTCP messages
+pub const CTCP_DELIM: char = '\x01';
+
+const LOW_LEVEL_QUOTE: char = '\x10';
+const CTCP_LEVEL_QUOTE: char = '\\';
+
+/// Standard CTCP queries
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CtcpKind<'a> {
+    /// `ACTION text`, an emote, never replied to
+    Action,
+
+    /// `VERSION`, the name and version of the client
+    Version,
+
+    /// `PING timestamp`, echoed back to measure the lag
+    Ping,
+
+    /// `TIME`, the local time of the client
+    Time,
+
+    /// `CLIENTINFO`, the list of supported queries
+    ClientInfo,
+
+    /// `SOURCE`, where to get the client
+    Source,
+
+    /// `USERINFO`, a string set by the user
+    UserInfo,
+
+    /// `FINGER`, the real name and idle time of the user
+    Finger,
+
+    /// Any other query
+    Other(&'a str),
+}
+
+impl<'a> CtcpKind<'a> {
+    pub fn from_tag(tag: &'a str) -> CtcpKind<'a> {
+        match &tag.to_ascii_uppercase()[..] {
+            "ACTION" => CtcpKind::Action,
+            "VERSION" => CtcpKind::Version,
+            "PING" => CtcpKind::Ping,
+            "TIME" => CtcpKind::Time,
+            "CLIENTINFO" => CtcpKind::ClientInfo,
+            "SOURCE" => CtcpKind::Source,
+            "USERINFO" => CtcpKind::UserInfo,
+            "FINGER" => CtcpKind::Finger,
+            _ => CtcpKind::Other(tag),
+        }
+    }
+
+    pub fn as_tag(&self) -> &'a str {
+        match *self {
+            CtcpKind::Action => "ACTION",
+            CtcpKind::Version => "VERSION",
+            CtcpKind::Ping => "PING",
+            CtcpKind::Time => "TIME",
+            CtcpKind::ClientInfo => "CLIENTINFO",
+            CtcpKind::Source => "SOURCE",
+            CtcpKind::UserInfo => "USERINFO",
+            CtcpKind::Finger => "FINGER",
+            CtcpKind::Other(tag) => tag,
+        }
+    }
+}
+
+impl<'a> fmt::Display for CtcpKind<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_tag())
+    }
+}
+
+/// A CTCP request or reply, unquoted
+///
+/// ```
+/// use irc::protocol::ctcp::{Ctcp, CtcpKind};
+///
+/// let ctcp = Ctcp::parse("\x01ACTION waves\x01").unwrap();
+/// assert_eq!(ctcp.kind(), CtcpKind::Action);
+/// assert_eq!(ctcp.data(), Some("waves"));
+/// assert_eq!(&Ctcp::new("PING", Some("1234")).to_string()[..], "\x01PING 1234\x01");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Ctcp<'a> {
+    tag: Cow<'a, str>,
+    data: Option<Cow<'a, str>>,
+}
+
+impl<'a> Ctcp<'a> {
+    pub fn new(tag: &'a str, data: Option<&'a str>) -> Ctcp<'a> {
+        Ctcp {
+            tag: Cow::Borrowed(tag),
+            data: data.map(Cow::Borrowed),
+        }
+    }
+
+    /// Create an `ACTION`, such as `/me waves`
+    pub fn action(text: &'a str) -> Ctcp<'a> {
+        Ctcp::new("ACTION", Some(text))
+    }
+
+    /// Parse the text of a `PRIVMSG` or `NOTICE`, `None` if it is not a
+    /// CTCP message
+    ///
+    /// The closing delimiter is optional, as some clients omit it.
+    pub fn parse(text: &'a str) -> Option<Ctcp<'a>> {
+        if !text.starts_with(CTCP_DELIM) {
+            return None;
+        }
+
+        let body = &text[1..];
+        let body = match body.find(CTCP_DELIM) {
+            None => body,
+            Some(idx) => &body[..idx],
+        };
+
+        let body = match low_level_dequote(body) {
+            Cow::Borrowed(s) => ctcp_dequote(s),
+            Cow::Owned(s) => Cow::Owned(ctcp_dequote(&s).into_owned()),
+        };
+
+        let (tag, data) = match body {
+            Cow::Borrowed(s) => {
+                let (tag, data) = split_tag(s);
+                (Cow::Borrowed(tag), data.map(Cow::Borrowed))
+            },
+            Cow::Owned(s) => {
+                let (tag, data) = split_tag(&s);
+                (Cow::Owned(tag.to_owned()), data.map(|d| Cow::Owned(d.to_owned())))
+            },
+        };
+
+        if tag.is_empty() {
+            return None;
+        }
+
+        Some(Ctcp {
+            tag: tag,
+            data: data,
+        })
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn kind(&self) -> CtcpKind<'_> {
+        CtcpKind::from_tag(&self.tag)
+    }
+
+    pub fn data(&self) -> Option<&str> {
+        self.data.as_ref().map(|d| &d[..])
+    }
+
+    /// Send as a request
+    pub fn to_privmsg(&self, target: &str) -> PrivmsgCommand<'static> {
+        PrivmsgCommand::new(target, &self.to_string()).into_owned()
+    }
+
+    /// Send as a reply
+    pub fn to_notice(&self, target: &str) -> NoticeCommand<'static> {
+        NoticeCommand::new(target, &self.to_string()).into_owned()
+    }
+
+    pub fn into_owned(self) -> Ctcp<'static> {
+        Ctcp {
+            tag: Cow::Owned(self.tag.into_owned()),
+            data: self.data.map(|d| Cow::Owned(d.into_owned())),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> Ctcp<'b> {
+        Ctcp {
+            tag: Cow::Borrowed(&self.tag[..]),
+            data: self.data.as_ref().map(|d| Cow::Borrowed(&d[..])),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Ctcp<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let body = match self.data {
+            None => Cow::Borrowed(&self.tag[..]),
+            Some(ref data) => Cow::Owned(format!("{} {}", self.tag, data)),
+        };
+
+        write!(f, "{}{}{}", CTCP_DELIM, low_level_quote(&ctcp_quote(&body)), CTCP_DELIM)
+    }
+}
+
+fn split_tag(s: &str) -> (&str, Option<&str>) {
+    match s.find(' ') {
+        None => (s, None),
+        Some(idx) => (&s[..idx], Some(&s[idx + 1..])),
+    }
+}
+
+/// Escape `NUL`, `CR`, `LF` and `\x10`
+pub fn low_level_quote(s: &str) -> Cow<'_, str> {
+    if !s.contains(&['\0', '\r', '\n', LOW_LEVEL_QUOTE][..]) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut quoted = String::with_capacity(s.len() + 4);
+    for c in s.chars() {
+        match c {
+            '\0' => quoted.push_str("\x100"),
+            '\r' => quoted.push_str("\x10r"),
+            '\n' => quoted.push_str("\x10n"),
+            LOW_LEVEL_QUOTE => quoted.push_str("\x10\x10"),
+            c => quoted.push(c),
+        }
+    }
+    Cow::Owned(quoted)
+}
+
+/// Reverse `low_level_quote`, a `\x10` before any other character is dropped
+pub fn low_level_dequote(s: &str) -> Cow<'_, str> {
+    if !s.contains(LOW_LEVEL_QUOTE) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut dequoted = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != LOW_LEVEL_QUOTE {
+            dequoted.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('0') => dequoted.push('\0'),
+            Some('r') => dequoted.push('\r'),
+            Some('n') => dequoted.push('\n'),
+            Some(LOW_LEVEL_QUOTE) => dequoted.push(LOW_LEVEL_QUOTE),
+            Some(c) => dequoted.push(c),
+            None => {},
+        }
+    }
+    Cow::Owned(dequoted)
+}
+
+/// Escape `\x01` and `\`
+pub fn ctcp_quote(s: &str) -> Cow<'_, str> {
+    if !s.contains(&[CTCP_DELIM, CTCP_LEVEL_QUOTE][..]) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut quoted = String::with_capacity(s.len() + 2);
+    for c in s.chars() {
+        match c {
+            CTCP_DELIM => quoted.push_str("\\a"),
+            CTCP_LEVEL_QUOTE => quoted.push_str("\\\\"),
+            c => quoted.push(c),
+        }
+    }
+    Cow::Owned(quoted)
+}
+
+/// Reverse `ctcp_quote`, a `\` before any other character is kept
+pub fn ctcp_dequote(s: &str) -> Cow<'_, str> {
+    if !s.contains(CTCP_LEVEL_QUOTE) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut dequoted = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != CTCP_LEVEL_QUOTE {
+            dequoted.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('a') => dequoted.push(CTCP_DELIM),
+            Some(CTCP_LEVEL_QUOTE) => dequoted.push(CTCP_LEVEL_QUOTE),
+            Some(c) => {
+                dequoted.push(CTCP_LEVEL_QUOTE);
+                dequoted.push(c);
+            },
+            None => dequoted.push(CTCP_LEVEL_QUOTE),
+        }
+    }
+    Cow::Owned(dequoted)
+}
+
+impl<'a> PrivmsgCommand<'a> {
+    /// Parse the message as a CTCP request
+    pub fn ctcp(&self) -> Option<Ctcp<'_>> {
+        Ctcp::parse(self.message())
+    }
+}
+
+impl<'a> NoticeCommand<'a> {
+    /// Parse the message as a CTCP reply
+    pub fn ctcp(&self) -> Option<Ctcp<'_>> {
+        Ctcp::parse(self.message())
+    }
+}
+
+/// Answers the standard CTCP requests
+///
+/// `PING`, `TIME` and `CLIENTINFO` are always answered, `VERSION`,
+/// `SOURCE`, `USERINFO` and `FINGER` only when configured. Other requests,
+/// `ACTION` and anything sent in a `NOTICE` are never answered.
+///
+/// ```
+/// use irc::protocol::command::PrivmsgCommand;
+/// use irc::protocol::ctcp::CtcpResponder;
+///
+/// let responder = CtcpResponder::new().version("rustirc 0.1");
+/// let request = PrivmsgCommand::new("Angel", "\x01VERSION\x01");
+/// let reply = responder.respond("WiZ", &request).unwrap();
+/// assert_eq!(&reply.to_string()[..], "NOTICE WiZ :\x01VERSION rustirc 0.1\x01");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CtcpResponder<'a> {
+    version: Option<&'a str>,
+    source: Option<&'a str>,
+    userinfo: Option<&'a str>,
+    finger: Option<&'a str>,
+}
+
+impl<'a> CtcpResponder<'a> {
+    pub fn new() -> CtcpResponder<'a> {
+        CtcpResponder {
+            version: None,
+            source: None,
+            userinfo: None,
+            finger: None,
+        }
+    }
+
+    /// Answer `VERSION`
+    pub fn version(mut self, version: &'a str) -> CtcpResponder<'a> {
+        self.version = Some(version);
+        self
+    }
+
+    /// Answer `SOURCE`
+    pub fn source(mut self, source: &'a str) -> CtcpResponder<'a> {
+        self.source = Some(source);
+        self
+    }
+
+    /// Answer `USERINFO`
+    pub fn userinfo(mut self, userinfo: &'a str) -> CtcpResponder<'a> {
+        self.userinfo = Some(userinfo);
+        self
+    }
+
+    /// Answer `FINGER`
+    pub fn finger(mut self, finger: &'a str) -> CtcpResponder<'a> {
+        self.finger = Some(finger);
+        self
+    }
+
+    /// Queries which are answered, as listed by `CLIENTINFO`
+    pub fn supported(&self) -> Vec<CtcpKind<'static>> {
+        let mut kinds = vec![CtcpKind::Action, CtcpKind::ClientInfo];
+        if self.finger.is_some() {
+            kinds.push(CtcpKind::Finger);
+        }
+        kinds.push(CtcpKind::Ping);
+        if self.source.is_some() {
+            kinds.push(CtcpKind::Source);
+        }
+        kinds.push(CtcpKind::Time);
+        if self.userinfo.is_some() {
+            kinds.push(CtcpKind::UserInfo);
+        }
+        if self.version.is_some() {
+            kinds.push(CtcpKind::Version);
+        }
+        kinds
+    }
+
+    /// Build the reply to a `PRIVMSG` sent by `sender`, `None` if the
+    /// message should not be answered
+    pub fn respond(&self, sender: &str, msg: &PrivmsgCommand) -> Option<NoticeCommand<'static>> {
+        let ctcp = match msg.ctcp() {
+            None => return None,
+            Some(ctcp) => ctcp,
+        };
+
+        let data = match ctcp.kind() {
+            CtcpKind::Action | CtcpKind::Other(..) => None,
+            CtcpKind::Version => self.version.map(str::to_owned),
+            CtcpKind::Source => self.source.map(str::to_owned),
+            CtcpKind::UserInfo => self.userinfo.map(str::to_owned),
+            CtcpKind::Finger => self.finger.map(str::to_owned),
+            CtcpKind::Ping => Some(ctcp.data().unwrap_or("").to_owned()),
+            CtcpKind::Time => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+                Some(format_time(now))
+            },
+            CtcpKind::ClientInfo => {
+                Some(self.supported().iter().map(|k| k.as_tag()).collect::<Vec<&str>>().join(" "))
+            },
+        };
+
+        data.map(|data| {
+            let data = if data.is_empty() { None } else { Some(&data[..]) };
+            Ctcp::new(ctcp.kind().as_tag(), data).to_notice(sender)
+        })
+    }
+}
+
+/// Format seconds since the epoch as `Sat, 17 Oct 2026 12:00:00 GMT`
+fn format_time(secs: u64) -> String {
+    const WEEKDAYS: [&'static str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&'static str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun",
+                                        "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+    let days = secs / 86400;
+    let rem = secs % 86400;
+
+    // Civil date from days since 1970-01-01, in 400 years eras starting in March
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+            WEEKDAYS[((days + 4) % 7) as usize], day, MONTHS[(month - 1) as usize], year,
+            rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::command::{PrivmsgCommand, NoticeCommand};
+
+    #[test]
+    fn test_ctcp_parse() {
+        let ctcp = Ctcp::parse("\x01VERSION\x01").unwrap();
+        assert_eq!(ctcp.kind(), CtcpKind::Version);
+        assert_eq!(ctcp.data(), None);
+
+        let ctcp = Ctcp::parse("\x01action waves at everyone").unwrap();
+        assert_eq!(ctcp.kind(), CtcpKind::Action);
+        assert_eq!(ctcp.data(), Some("waves at everyone"));
+
+        let ctcp = Ctcp::parse("\x01DCC SEND file 1 2 3\x01").unwrap();
+        assert_eq!(ctcp.kind(), CtcpKind::Other("DCC"));
+
+        assert!(Ctcp::parse("hello").is_none());
+        assert!(Ctcp::parse("\x01\x01").is_none());
+        assert!(PrivmsgCommand::new("#rust", "hello").ctcp().is_none());
+        assert_eq!(NoticeCommand::new("WiZ", "\x01PING 123\x01").ctcp().unwrap().data(), Some("123"));
+    }
+
+    #[test]
+    fn test_ctcp_quoting() {
+        let data = "a\\b\x01c\x10d\re\nf\0g";
+        let ctcp = Ctcp::new("USERINFO", Some(data));
+        let text = ctcp.to_string();
+        assert_eq!(&text[..], "\x01USERINFO a\\\\b\\ac\x10\x10d\x10re\x10nf\x100g\x01");
+        assert_eq!(Ctcp::parse(&text).unwrap(), ctcp);
+
+        assert_eq!(Ctcp::parse("\x01ACTION C:\\dir\x01").unwrap().data(), Some("C:\\dir"));
+    }
+
+    #[test]
+    fn test_ctcp_responder() {
+        let responder = CtcpResponder::new().version("rustirc").source("https://example.com");
+        let respond = |text: &str| responder.respond("WiZ", &PrivmsgCommand::new("Angel", text)).map(|n| n.to_string());
+
+        assert_eq!(respond("\x01PING 1234\x01").unwrap(), "NOTICE WiZ :\x01PING 1234\x01");
+        assert_eq!(respond("\x01SOURCE\x01").unwrap(), "NOTICE WiZ :\x01SOURCE https://example.com\x01");
+        assert_eq!(respond("\x01CLIENTINFO\x01").unwrap(),
+                   "NOTICE WiZ :\x01CLIENTINFO ACTION CLIENTINFO PING SOURCE TIME VERSION\x01");
+        assert!(respond("\x01TIME\x01").unwrap().ends_with(" GMT\x01"));
+        assert!(respond("\x01ACTION waves\x01").is_none());
+        assert!(respond("\x01FINGER\x01").is_none());
+        assert!(respond("\x01FOO\x01").is_none());
+        assert!(respond("hello").is_none());
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(&format_time(0)[..], "Thu, 01 Jan 1970 00:00:00 GMT");
+        assert_eq!(&format_time(951782400)[..], "Tue, 29 Feb 2000 00:00:00 GMT");
+        assert_eq!(&format_time(1792234861)[..], "Sat, 17 Oct 2026 11:01:01 GMT");
+    }
+}
@@ -0,0 +1,414 @@
+//! Direct Client-to-Client offers, carried in a CTCP `DCC` request
+//!
+//! ```plain
+//! PRIVMSG WiZ :\x01DCC SEND "my file.txt" 3232235777 5000 1024\x01
+//! PRIVMSG Angel :\x01DCC RESUME "my file.txt" 5000 512\x01
+//! PRIVMSG WiZ :\x01DCC ACCEPT "my file.txt" 5000 512\x01
+//! ```
+//!
+//! IPv4 addresses are sent as a single integer, IPv6 addresses in their
+//! usual text form. A passive (reverse) offer has port 0 and a token, the
+//! receiver answers with the same offer filled with its own address and
+//! port.
+//!
+//! The transfers themselves are in `client::dcc`.
+
+use std::fmt;
+use std::borrow::Cow;
+use std::net::{IpAddr, Ipv4Addr};
+
+use protocol::command::PrivmsgCommand;
+use protocol::ctcp::Ctcp;
+use protocol::message::{ParseMessageError, ParseMessageErrorKind};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DccMessage<'a> {
+    /// `CHAT chat <address> <port> [<token>]`
+    Chat {
+        address: IpAddr,
+        port: u16,
+        token: Option<Cow<'a, str>>,
+    },
+
+    /// `SEND <filename> <address> <port> [<size> [<token>]]`
+    Send {
+        filename: Cow<'a, str>,
+        address: IpAddr,
+        port: u16,
+        size: Option<u64>,
+        token: Option<Cow<'a, str>>,
+    },
+
+    /// `RESUME <filename> <port> <position> [<token>]`, asks the sender to
+    /// start from `position`
+    Resume {
+        filename: Cow<'a, str>,
+        port: u16,
+        position: u64,
+        token: Option<Cow<'a, str>>,
+    },
+
+    /// `ACCEPT <filename> <port> <position> [<token>]`, answers `RESUME`
+    Accept {
+        filename: Cow<'a, str>,
+        port: u16,
+        position: u64,
+        token: Option<Cow<'a, str>>,
+    },
+}
+
+impl<'a> DccMessage<'a> {
+    pub fn chat(address: IpAddr, port: u16) -> DccMessage<'a> {
+        DccMessage::Chat {
+            address: address,
+            port: port,
+            token: None,
+        }
+    }
+
+    pub fn send(filename: &'a str, address: IpAddr, port: u16, size: Option<u64>) -> DccMessage<'a> {
+        DccMessage::Send {
+            filename: Cow::Borrowed(filename),
+            address: address,
+            port: port,
+            size: size,
+            token: None,
+        }
+    }
+
+    pub fn resume(filename: &'a str, port: u16, position: u64) -> DccMessage<'a> {
+        DccMessage::Resume {
+            filename: Cow::Borrowed(filename),
+            port: port,
+            position: position,
+            token: None,
+        }
+    }
+
+    pub fn accept(filename: &'a str, port: u16, position: u64) -> DccMessage<'a> {
+        DccMessage::Accept {
+            filename: Cow::Borrowed(filename),
+            port: port,
+            position: position,
+            token: None,
+        }
+    }
+
+    /// Set the token of a passive offer
+    pub fn with_token(mut self, new_token: &'a str) -> DccMessage<'a> {
+        match self {
+            DccMessage::Chat { ref mut token, .. } |
+            DccMessage::Send { ref mut token, .. } |
+            DccMessage::Resume { ref mut token, .. } |
+            DccMessage::Accept { ref mut token, .. } => *token = Some(Cow::Borrowed(new_token)),
+        }
+        self
+    }
+
+    /// Parse the data of a CTCP `DCC` request, such as `SEND file 1 2 3`
+    pub fn parse(data: &'a str) -> Result<DccMessage<'a>, ParseMessageError> {
+        let data = data.trim_start_matches(' ');
+        let (kind, rest) = match data.find(' ') {
+            None => (data, ""),
+            Some(idx) => (&data[..idx], &data[idx + 1..]),
+        };
+
+        let (argument, rest) = try!(split_argument(rest));
+        let mut params = rest.split(' ').filter(|p| !p.is_empty());
+
+        match &kind.to_ascii_uppercase()[..] {
+            "CHAT" => {
+                let address = try!(parse_address(params.next()));
+                let port = try!(parse_number(params.next(), "DCC CHAT needs a port"));
+
+                Ok(DccMessage::Chat {
+                    address: address,
+                    port: port,
+                    token: params.next().map(Cow::Borrowed),
+                })
+            },
+            "SEND" => {
+                let address = try!(parse_address(params.next()));
+                let port = try!(parse_number(params.next(), "DCC SEND needs a port"));
+                let size = match params.next() {
+                    None => None,
+                    Some(s) => Some(try!(parse_number(Some(s), "DCC SEND needs a size"))),
+                };
+
+                Ok(DccMessage::Send {
+                    filename: argument,
+                    address: address,
+                    port: port,
+                    size: size,
+                    token: params.next().map(Cow::Borrowed),
+                })
+            },
+            "RESUME" | "ACCEPT" => {
+                let port = try!(parse_number(params.next(), "DCC RESUME and ACCEPT need a port"));
+                let position = try!(parse_number(params.next(), "DCC RESUME and ACCEPT need a position"));
+                let token = params.next().map(Cow::Borrowed);
+
+                if kind.eq_ignore_ascii_case("RESUME") {
+                    Ok(DccMessage::Resume {
+                        filename: argument,
+                        port: port,
+                        position: position,
+                        token: token,
+                    })
+                } else {
+                    Ok(DccMessage::Accept {
+                        filename: argument,
+                        port: port,
+                        position: position,
+                        token: token,
+                    })
+                }
+            },
+            _ => {
+                Err(ParseMessageError::new_with_detail(ParseMessageErrorKind::UnrecognizedCommand,
+                                                       "Unrecognized DCC type",
+                                                       kind.to_owned()))
+            },
+        }
+    }
+
+    /// Parse a CTCP request, `None` if it is not a `DCC` request
+    pub fn from_ctcp(ctcp: &'a Ctcp) -> Option<Result<DccMessage<'a>, ParseMessageError>> {
+        if !ctcp.tag().eq_ignore_ascii_case("DCC") {
+            return None;
+        }
+
+        Some(DccMessage::parse(ctcp.data().unwrap_or("")))
+    }
+
+    /// The port to connect to, 0 for a passive offer
+    pub fn port(&self) -> u16 {
+        match *self {
+            DccMessage::Chat { port, .. } |
+            DccMessage::Send { port, .. } |
+            DccMessage::Resume { port, .. } |
+            DccMessage::Accept { port, .. } => port,
+        }
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        match *self {
+            DccMessage::Chat { ref token, .. } |
+            DccMessage::Send { ref token, .. } |
+            DccMessage::Resume { ref token, .. } |
+            DccMessage::Accept { ref token, .. } => token.as_ref().map(|t| &t[..]),
+        }
+    }
+
+    /// Whether the sender asks the receiver to listen instead
+    pub fn is_passive(&self) -> bool {
+        self.port() == 0 && self.token().is_some()
+    }
+
+    pub fn to_ctcp(&self) -> Ctcp<'static> {
+        let data = self.to_string();
+        Ctcp::new("DCC", Some(&data)).into_owned()
+    }
+
+    pub fn to_privmsg(&self, target: &str) -> PrivmsgCommand<'static> {
+        self.to_ctcp().to_privmsg(target)
+    }
+
+    pub fn into_owned(self) -> DccMessage<'static> {
+        let owned = |c: Cow<'a, str>| -> Cow<'static, str> { Cow::Owned(c.into_owned()) };
+
+        match self {
+            DccMessage::Chat { address, port, token } => DccMessage::Chat {
+                address: address,
+                port: port,
+                token: token.map(owned),
+            },
+            DccMessage::Send { filename, address, port, size, token } => DccMessage::Send {
+                filename: owned(filename),
+                address: address,
+                port: port,
+                size: size,
+                token: token.map(owned),
+            },
+            DccMessage::Resume { filename, port, position, token } => DccMessage::Resume {
+                filename: owned(filename),
+                port: port,
+                position: position,
+                token: token.map(owned),
+            },
+            DccMessage::Accept { filename, port, position, token } => DccMessage::Accept {
+                filename: owned(filename),
+                port: port,
+                position: position,
+                token: token.map(owned),
+            },
+        }
+    }
+}
+
+impl<'a> fmt::Display for DccMessage<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let token = match *self {
+            DccMessage::Chat { address, port, .. } => {
+                try!(write!(f, "CHAT chat {} {}", FormatAddress(address), port));
+                self.token()
+            },
+            DccMessage::Send { ref filename, address, port, size, ref token } => {
+                try!(write!(f, "SEND {} {} {}", FormatFilename(filename), FormatAddress(address), port));
+                if size.is_some() || token.is_some() {
+                    try!(write!(f, " {}", size.unwrap_or(0)));
+                }
+                self.token()
+            },
+            DccMessage::Resume { ref filename, port, position, .. } => {
+                try!(write!(f, "RESUME {} {} {}", FormatFilename(filename), port, position));
+                self.token()
+            },
+            DccMessage::Accept { ref filename, port, position, .. } => {
+                try!(write!(f, "ACCEPT {} {} {}", FormatFilename(filename), port, position));
+                self.token()
+            },
+        };
+
+        match token {
+            None => Ok(()),
+            Some(token) => write!(f, " {}", token),
+        }
+    }
+}
+
+impl<'a> PrivmsgCommand<'a> {
+    /// Parse the message as a DCC offer
+    pub fn dcc(&self) -> Option<Result<DccMessage<'static>, ParseMessageError>> {
+        self.ctcp().and_then(|ctcp| DccMessage::from_ctcp(&ctcp).map(|r| r.map(DccMessage::into_owned)))
+    }
+}
+
+struct FormatAddress(IpAddr);
+
+impl fmt::Display for FormatAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            IpAddr::V4(ip) => write!(f, "{}", u32::from(ip)),
+            IpAddr::V6(ip) => write!(f, "{}", ip),
+        }
+    }
+}
+
+struct FormatFilename<'a>(&'a str);
+
+impl<'a> fmt::Display for FormatFilename<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() || self.0.contains(' ') {
+            write!(f, "\"{}\"", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+/// Split the filename, which may be quoted, from the rest of the parameters
+fn split_argument(s: &str) -> Result<(Cow<'_, str>, &str), ParseMessageError> {
+    let s = s.trim_start_matches(' ');
+
+    if let Some(quoted) = s.strip_prefix('"') {
+        return match quoted.find('"') {
+            None => Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                               "DCC filename has no closing quote")),
+            Some(idx) => Ok((Cow::Borrowed(&quoted[..idx]), &quoted[idx + 1..])),
+        };
+    }
+
+    match s.find(' ') {
+        None if s.is_empty() => {
+            Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
+                                       "DCC needs an argument"))
+        },
+        None => Ok((Cow::Borrowed(s), "")),
+        Some(idx) => Ok((Cow::Borrowed(&s[..idx]), &s[idx + 1..])),
+    }
+}
+
+fn parse_address(s: Option<&str>) -> Result<IpAddr, ParseMessageError> {
+    let s = match s {
+        None => {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
+                                              "DCC needs an address"));
+        },
+        Some(s) => s,
+    };
+
+    if let Ok(ip) = s.parse::<u32>() {
+        return Ok(IpAddr::V4(Ipv4Addr::from(ip)));
+    }
+
+    s.parse().map_err(|_| {
+        ParseMessageError::new_with_detail(ParseMessageErrorKind::InvalidParam, "Invalid DCC address", s.to_owned())
+    })
+}
+
+fn parse_number<T: ::std::str::FromStr>(s: Option<&str>, missing: &'static str) -> Result<T, ParseMessageError> {
+    match s {
+        None => Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams, missing)),
+        Some(s) => {
+            s.parse().map_err(|_| {
+                ParseMessageError::new_with_detail(ParseMessageErrorKind::InvalidParam, "Invalid DCC number", s.to_owned())
+            })
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use protocol::command::PrivmsgCommand;
+    use protocol::message::ParseMessageErrorKind;
+
+    #[test]
+    fn test_dcc_roundtrip() {
+        let corpus = [
+            "CHAT chat 3232235777 5000",
+            "CHAT chat 3232235777 0 12",
+            "SEND file.txt 3232235777 5000",
+            "SEND \"my file.txt\" 3232235777 5000 1024",
+            "SEND file.txt 2001:db8::1 0 1024 7",
+            "RESUME \"my file.txt\" 5000 512",
+            "ACCEPT file.txt 0 512 7",
+        ];
+
+        for data in &corpus {
+            let dcc = DccMessage::parse(data).unwrap();
+            assert_eq!(&dcc.to_string()[..], *data);
+        }
+    }
+
+    #[test]
+    fn test_dcc_parse() {
+        let dcc = DccMessage::parse("SEND \"my file.txt\" 3232235777 5000 1024").unwrap();
+        assert_eq!(dcc, DccMessage::send("my file.txt", IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)), 5000, Some(1024)));
+        assert!(!dcc.is_passive());
+
+        let dcc = DccMessage::parse("SEND file 2001:db8::1 0 1024 7").unwrap();
+        assert!(dcc.is_passive());
+        assert_eq!(dcc.token(), Some("7"));
+        assert_eq!(dcc.port(), 0);
+
+        assert_eq!(DccMessage::parse("FOO bar").unwrap_err().kind(), ParseMessageErrorKind::UnrecognizedCommand);
+        assert_eq!(DccMessage::parse("SEND file").unwrap_err().kind(), ParseMessageErrorKind::NeedMoreParams);
+        assert_eq!(DccMessage::parse("SEND file 1.2.3 5000").unwrap_err().kind(), ParseMessageErrorKind::InvalidParam);
+        assert_eq!(DccMessage::parse("RESUME \"file 5000 1").unwrap_err().kind(), ParseMessageErrorKind::InvalidParam);
+    }
+
+    #[test]
+    fn test_dcc_privmsg() {
+        let offer = DccMessage::chat(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0).with_token("42");
+        let msg = offer.to_privmsg("WiZ");
+        assert_eq!(&msg.to_string()[..], "PRIVMSG WiZ :\x01DCC CHAT chat 2130706433 0 42\x01");
+        assert_eq!(msg.dcc().unwrap().unwrap(), offer);
+
+        assert!(PrivmsgCommand::new("WiZ", "\x01VERSION\x01").dcc().is_none());
+    }
+}
@@ -0,0 +1,184 @@
+use std::fmt;
+use std::error;
+use std::str;
+use std::borrow::Cow;
+
+/// Single byte legacy encodings which are still seen on IRC networks
+///
+/// Both are ASCII compatible, so the message structure (spaces, `:`, `CR-LF`)
+/// can be split on raw bytes before decoding.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LegacyEncoding {
+    /// ISO 8859-1, every byte maps to the code point of the same value
+    Latin1,
+
+    /// Windows code page 1252, Latin-1 with printable characters in `0x80..0x9F`
+    Windows1252,
+}
+
+/// Code points of the `0x80..0x9F` range in CP1252
+///
+/// The five unassigned bytes map to the C1 control of the same value, as
+/// browsers do, so decoding never fails.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+impl LegacyEncoding {
+    /// Decode a byte, never fails
+    pub fn decode_byte(&self, b: u8) -> char {
+        match *self {
+            LegacyEncoding::Windows1252 if (0x80..0xA0).contains(&b) => WINDOWS_1252_HIGH[(b - 0x80) as usize],
+            _ => b as char,
+        }
+    }
+
+    /// Encode a character, returns `None` if it has no representation
+    pub fn encode_char(&self, c: char) -> Option<u8> {
+        let code = c as u32;
+        match *self {
+            LegacyEncoding::Latin1 if code < 0x100 => Some(code as u8),
+            LegacyEncoding::Latin1 => None,
+            LegacyEncoding::Windows1252 => {
+                if code < 0x80 || (0xA0..0x100).contains(&code) {
+                    Some(code as u8)
+                } else {
+                    WINDOWS_1252_HIGH.iter().position(|&x| x == c).map(|idx| 0x80 + idx as u8)
+                }
+            }
+        }
+    }
+
+    /// Decode a byte slice
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+        if bytes.is_ascii() {
+            // ASCII is valid UTF-8
+            return Cow::Borrowed(str::from_utf8(bytes).unwrap());
+        }
+
+        Cow::Owned(bytes.iter().map(|&b| self.decode_byte(b)).collect())
+    }
+
+    /// Encode a string
+    pub fn encode<'a>(&self, s: &'a str) -> Result<Cow<'a, [u8]>, EncodingError> {
+        if s.is_ascii() {
+            return Ok(Cow::Borrowed(s.as_bytes()));
+        }
+
+        let mut encoded = Vec::with_capacity(s.len());
+        for (idx, c) in s.char_indices() {
+            match self.encode_char(c) {
+                Some(b) => encoded.push(b),
+                None => return Err(EncodingError::Unrepresentable(c, idx)),
+            }
+        }
+
+        Ok(Cow::Owned(encoded))
+    }
+}
+
+/// Strategy to convert between text and the bytes on the wire
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Encoding {
+    /// UTF-8 only, invalid bytes are an error
+    Utf8,
+
+    /// UTF-8, falling back to a legacy encoding for data which is not
+    /// valid UTF-8. Always encodes as UTF-8
+    Utf8WithFallback(LegacyEncoding),
+
+    /// A fixed legacy encoding
+    Legacy(LegacyEncoding),
+}
+
+impl Default for Encoding {
+    fn default() -> Encoding {
+        Encoding::Utf8
+    }
+}
+
+impl Encoding {
+    /// Decode a byte slice
+    ///
+    /// Borrows the input whenever it is already valid text.
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, str>, EncodingError> {
+        match *self {
+            Encoding::Utf8 => match str::from_utf8(bytes) {
+                Ok(s) => Ok(Cow::Borrowed(s)),
+                Err(err) => Err(EncodingError::InvalidBytes(err.valid_up_to())),
+            },
+            Encoding::Utf8WithFallback(legacy) => match str::from_utf8(bytes) {
+                Ok(s) => Ok(Cow::Borrowed(s)),
+                Err(..) => Ok(legacy.decode(bytes)),
+            },
+            Encoding::Legacy(legacy) => Ok(legacy.decode(bytes)),
+        }
+    }
+
+    /// Encode a string
+    pub fn encode<'a>(&self, s: &'a str) -> Result<Cow<'a, [u8]>, EncodingError> {
+        match *self {
+            Encoding::Utf8 | Encoding::Utf8WithFallback(..) => Ok(Cow::Borrowed(s.as_bytes())),
+            Encoding::Legacy(legacy) => legacy.encode(s),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EncodingError {
+    /// The input is not valid in the encoding, carries the byte offset
+    /// of the first invalid byte
+    InvalidBytes(usize),
+
+    /// The character at the given byte offset can't be represented in
+    /// the encoding
+    Unrepresentable(char, usize),
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EncodingError::InvalidBytes(pos) => write!(f, "Invalid byte sequence at offset {}", pos),
+            EncodingError::Unrepresentable(c, pos) =>
+                write!(f, "Character {:?} at offset {} can't be encoded", c, pos),
+        }
+    }
+}
+
+impl error::Error for EncodingError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_utf8() {
+        assert_eq!(Encoding::Utf8.decode("café".as_bytes()), Ok(Cow::Borrowed("café")));
+        assert_eq!(Encoding::Utf8.decode(b"caf\xe9"), Err(EncodingError::InvalidBytes(3)));
+    }
+
+    #[test]
+    fn test_decode_fallback() {
+        let enc = Encoding::Utf8WithFallback(LegacyEncoding::Latin1);
+        assert_eq!(&enc.decode("café".as_bytes()).unwrap()[..], "café");
+        assert_eq!(&enc.decode(b"caf\xe9").unwrap()[..], "café");
+
+        let enc = Encoding::Utf8WithFallback(LegacyEncoding::Windows1252);
+        assert_eq!(&enc.decode(b"\x93quoted\x94 \x80").unwrap()[..], "\u{201C}quoted\u{201D} \u{20AC}");
+    }
+
+    #[test]
+    fn test_legacy_roundtrip() {
+        let enc = Encoding::Legacy(LegacyEncoding::Windows1252);
+        let bytes: Vec<u8> = (0u8..0xFF).collect();
+        let text = enc.decode(&bytes).unwrap();
+        assert_eq!(&enc.encode(&text).unwrap()[..], &bytes[..]);
+
+        let enc = Encoding::Legacy(LegacyEncoding::Latin1);
+        assert_eq!(&enc.encode("café").unwrap()[..], &b"caf\xe9"[..]);
+        assert_eq!(enc.encode("a\u{20AC}"), Err(EncodingError::Unrepresentable('\u{20AC}', 1)));
+    }
+}
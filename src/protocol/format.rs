@@ -0,0 +1,605 @@
+//! Formatting codes in the text of messages
+//!
+//! ```plain
+//! \x02 bold           \x1d italic         \x1f underline
+//! \x1e strikethrough  \x11 monospace      \x16 reverse colors
+//! \x03fg[,bg] colors from the palette, 2 digits each
+//! \x04RRGGBB[,RRGGBB] hex colors
+//! \x0f reset
+//! ```
+//!
+//! Each code toggles its attribute, a color code without any color resets
+//! the colors. Color 99 is the default color of the client.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+const BOLD: char = '\x02';
+const COLOR: char = '\x03';
+const HEX_COLOR: char = '\x04';
+const RESET: char = '\x0f';
+const MONOSPACE: char = '\x11';
+const REVERSE: char = '\x16';
+const ITALIC: char = '\x1d';
+const STRIKETHROUGH: char = '\x1e';
+const UNDERLINE: char = '\x1f';
+
+/// Color code meaning the default color
+const DEFAULT_COLOR: u8 = 99;
+
+/// RGB values of the colors 0 to 98
+const PALETTE: [u32; 99] = [
+    0xffffff, 0x000000, 0x00007f, 0x009300, 0xff0000, 0x7f0000, 0x9c009c, 0xfc7f00,
+    0xffff00, 0x00fc00, 0x009393, 0x00ffff, 0x0000fc, 0xff00ff, 0x7f7f7f, 0xd2d2d2,
+    0x470000, 0x472100, 0x474700, 0x324700, 0x004700, 0x00472c, 0x004747, 0x002747,
+    0x000047, 0x2e0047, 0x470047, 0x47002a, 0x740000, 0x743a00, 0x747400, 0x517400,
+    0x007400, 0x007449, 0x007474, 0x004074, 0x000074, 0x4b0074, 0x740074, 0x740045,
+    0xb50000, 0xb56300, 0xb5b500, 0x7db500, 0x00b500, 0x00b571, 0x00b5b5, 0x0063b5,
+    0x0000b5, 0x7500b5, 0xb500b5, 0xb5006b, 0xff0000, 0xff8c00, 0xffff00, 0xb2ff00,
+    0x00ff00, 0x00ffa0, 0x00ffff, 0x008cff, 0x0000ff, 0xa500ff, 0xff00ff, 0xff0098,
+    0xff5959, 0xffb459, 0xffff71, 0xcfff60, 0x6fff6f, 0x65ffc9, 0x6dffff, 0x59b4ff,
+    0x5959ff, 0xc459ff, 0xff66ff, 0xff59bc, 0xff9c9c, 0xffd39c, 0xffff9c, 0xe2ff9c,
+    0x9cff9c, 0x9cffdb, 0x9cffff, 0x9cd3ff, 0x9c9cff, 0xdc9cff, 0xff9cff, 0xff94d3,
+    0x000000, 0x131313, 0x282828, 0x363636, 0x4d4d4d, 0x656565, 0x818181, 0x9f9f9f,
+    0xbcbcbc, 0xe2e2e2, 0xffffff,
+];
+
+/// SGR foreground codes of the colors 0 to 15
+const ANSI_PALETTE: [u8; 16] = [97, 30, 34, 32, 91, 31, 35, 33, 93, 92, 36, 96, 94, 95, 90, 37];
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Color {
+    /// Color from the palette, 0 to 15 are the usual colors, 16 to 98 the
+    /// extended ones
+    Palette(u8),
+
+    /// Hex color
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    pub const WHITE: Color = Color::Palette(0);
+    pub const BLACK: Color = Color::Palette(1);
+    pub const BLUE: Color = Color::Palette(2);
+    pub const GREEN: Color = Color::Palette(3);
+    pub const RED: Color = Color::Palette(4);
+    pub const BROWN: Color = Color::Palette(5);
+    pub const MAGENTA: Color = Color::Palette(6);
+    pub const ORANGE: Color = Color::Palette(7);
+    pub const YELLOW: Color = Color::Palette(8);
+    pub const LIGHT_GREEN: Color = Color::Palette(9);
+    pub const CYAN: Color = Color::Palette(10);
+    pub const LIGHT_CYAN: Color = Color::Palette(11);
+    pub const LIGHT_BLUE: Color = Color::Palette(12);
+    pub const PINK: Color = Color::Palette(13);
+    pub const GREY: Color = Color::Palette(14);
+    pub const LIGHT_GREY: Color = Color::Palette(15);
+
+    /// RGB values of the color, `None` for a code outside of the palette
+    pub fn to_rgb(&self) -> Option<(u8, u8, u8)> {
+        match *self {
+            Color::Rgb(r, g, b) => Some((r, g, b)),
+            Color::Palette(n) => {
+                PALETTE.get(n as usize).map(|&c| ((c >> 16) as u8, (c >> 8) as u8, c as u8))
+            },
+        }
+    }
+}
+
+/// Formatting attributes of a span of text
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Style {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub monospace: bool,
+    pub reverse: bool,
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+}
+
+impl Style {
+    pub fn new() -> Style {
+        Style::default()
+    }
+
+    pub fn is_plain(&self) -> bool {
+        *self == Style::default()
+    }
+
+    pub fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Style {
+        self.italic = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Style {
+        self.underline = true;
+        self
+    }
+
+    pub fn strikethrough(mut self) -> Style {
+        self.strikethrough = true;
+        self
+    }
+
+    pub fn monospace(mut self) -> Style {
+        self.monospace = true;
+        self
+    }
+
+    pub fn reverse(mut self) -> Style {
+        self.reverse = true;
+        self
+    }
+
+    pub fn foreground(mut self, color: Color) -> Style {
+        self.foreground = Some(color);
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Style {
+        self.background = Some(color);
+        self
+    }
+}
+
+/// Text with a single style
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Span<'a> {
+    pub style: Style,
+    pub text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    pub fn new(style: Style, text: &'a str) -> Span<'a> {
+        Span {
+            style: style,
+            text: text,
+        }
+    }
+
+    pub fn plain(text: &'a str) -> Span<'a> {
+        Span::new(Style::default(), text)
+    }
+}
+
+fn is_format_char(c: char) -> bool {
+    matches!(c, BOLD | COLOR | HEX_COLOR | RESET | MONOSPACE | REVERSE | ITALIC | STRIKETHROUGH | UNDERLINE)
+}
+
+/// Foreground and background set by a color sequence, the background is
+/// `None` if it is unchanged
+type ColorChange = (Option<Color>, Option<Option<Color>>);
+
+/// Parse the colors of a color sequence
+///
+/// Returns the length of the sequence and the new colors, `None` if the
+/// sequence resets them.
+fn parse_color(s: &str) -> (usize, Option<ColorChange>) {
+    let bytes = s.as_bytes();
+    let hex = match bytes.first() {
+        Some(&0x03) => false,
+        Some(&0x04) => true,
+        _ => return (0, None),
+    };
+
+    let (is_digit, max_digits): (fn(&u8) -> bool, usize) = if hex {
+        (u8::is_ascii_hexdigit, 6)
+    } else {
+        (u8::is_ascii_digit, 2)
+    };
+
+    let count = |start: usize| bytes[start..].iter().take(max_digits).take_while(|b| is_digit(b)).count();
+    let color = |start: usize, len: usize| {
+        let digits = &s[start..start + len];
+        if hex {
+            let c = u32::from_str_radix(digits, 16).unwrap_or(0);
+            Some(Color::Rgb((c >> 16) as u8, (c >> 8) as u8, c as u8))
+        } else {
+            match digits.parse().unwrap_or(DEFAULT_COLOR) {
+                DEFAULT_COLOR => None,
+                n => Some(Color::Palette(n)),
+            }
+        }
+    };
+
+    let fg = count(1);
+    if fg == 0 || (hex && fg < max_digits) {
+        return (1, None);
+    }
+    let foreground = color(1, fg);
+    let mut len = 1 + fg;
+
+    let mut background = None;
+    if bytes.len() > len + 1 && bytes[len] == b',' {
+        let bg = count(len + 1);
+        if bg > 0 && (!hex || bg == max_digits) {
+            background = Some(color(len + 1, bg));
+            len += 1 + bg;
+        }
+    }
+
+    (len, Some((foreground, background)))
+}
+
+/// Length in bytes of the formatting sequence at the start of `s`, 0 if `s`
+/// doesn't start with one
+pub fn sequence_len(s: &str) -> usize {
+    match s.chars().next() {
+        Some(COLOR) | Some(HEX_COLOR) => parse_color(s).0,
+        Some(c) if is_format_char(c) => 1,
+        _ => 0,
+    }
+}
+
+/// Iterator of the styled spans of a text
+///
+/// ```
+/// use irc::protocol::format::{Span, Spans, Style, Color};
+///
+/// let spans: Vec<Span> = Spans::new("\x02Hello\x02, \x0304world").collect();
+/// assert_eq!(spans, vec![
+///     Span::new(Style::new().bold(), "Hello"),
+///     Span::plain(", "),
+///     Span::new(Style::new().foreground(Color::RED), "world"),
+/// ]);
+/// ```
+pub struct Spans<'a> {
+    rest: &'a str,
+    style: Style,
+}
+
+impl<'a> Spans<'a> {
+    pub fn new(text: &'a str) -> Spans<'a> {
+        Spans {
+            rest: text,
+            style: Style::default(),
+        }
+    }
+
+    fn apply(&mut self, sequence: &str) {
+        let style = &mut self.style;
+        match sequence.chars().next() {
+            Some(BOLD) => style.bold = !style.bold,
+            Some(ITALIC) => style.italic = !style.italic,
+            Some(UNDERLINE) => style.underline = !style.underline,
+            Some(STRIKETHROUGH) => style.strikethrough = !style.strikethrough,
+            Some(MONOSPACE) => style.monospace = !style.monospace,
+            Some(REVERSE) => style.reverse = !style.reverse,
+            Some(RESET) => *style = Style::default(),
+            Some(COLOR) | Some(HEX_COLOR) => {
+                match parse_color(sequence).1 {
+                    None => {
+                        style.foreground = None;
+                        style.background = None;
+                    },
+                    Some((fg, bg)) => {
+                        style.foreground = fg;
+                        if let Some(bg) = bg {
+                            style.background = bg;
+                        }
+                    },
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+impl<'a> Iterator for Spans<'a> {
+    type Item = Span<'a>;
+
+    fn next(&mut self) -> Option<Span<'a>> {
+        loop {
+            let rest = self.rest;
+            if rest.is_empty() {
+                return None;
+            }
+
+            let len = sequence_len(rest);
+            if len > 0 {
+                self.apply(&rest[..len]);
+                self.rest = &rest[len..];
+                continue;
+            }
+
+            let end = rest.find(is_format_char).unwrap_or(rest.len());
+            self.rest = &rest[end..];
+            return Some(Span::new(self.style, &rest[..end]));
+        }
+    }
+}
+
+/// Remove all formatting codes
+pub fn strip(text: &str) -> Cow<'_, str> {
+    if !text.contains(is_format_char) {
+        return Cow::Borrowed(text);
+    }
+
+    Cow::Owned(Spans::new(text).map(|s| s.text).collect())
+}
+
+/// Format spans into text ready to be sent
+///
+/// Colors from the palette are always written with 2 digits, so that text
+/// starting with digits is never taken as part of the color. A background
+/// without a foreground uses the default color as foreground, except for hex
+/// colors which have no default and use black.
+pub fn format_spans(spans: &[Span]) -> String {
+    let mut formatted = String::new();
+    let mut current = Style::default();
+
+    for span in spans {
+        if span.text.is_empty() {
+            continue;
+        }
+
+        let style = span.style;
+        let colors_removed = (current.foreground.is_some() && style.foreground.is_none()) ||
+                             (current.background.is_some() && style.background.is_none());
+        if style != current && (style.is_plain() || colors_removed) {
+            formatted.push(RESET);
+            current = Style::default();
+        }
+
+        let toggles = [(current.bold, style.bold, BOLD),
+                       (current.italic, style.italic, ITALIC),
+                       (current.underline, style.underline, UNDERLINE),
+                       (current.strikethrough, style.strikethrough, STRIKETHROUGH),
+                       (current.monospace, style.monospace, MONOSPACE),
+                       (current.reverse, style.reverse, REVERSE)];
+        for &(old, new, code) in &toggles {
+            if old != new {
+                formatted.push(code);
+            }
+        }
+
+        if (current.foreground, current.background) != (style.foreground, style.background) {
+            push_colors(&mut formatted, style.foreground, style.background);
+
+            // A comma would be taken as the start of a background
+            if style.background.is_none() && span.text.starts_with(',') {
+                formatted.push(BOLD);
+                formatted.push(BOLD);
+            }
+        }
+
+        formatted.push_str(span.text);
+        current = style;
+    }
+
+    formatted
+}
+
+fn push_colors(formatted: &mut String, fg: Option<Color>, bg: Option<Color>) {
+    let is_rgb = |c: Option<Color>| matches!(c, Some(Color::Rgb(..)));
+
+    if is_rgb(fg) || is_rgb(bg) {
+        let hex = |c: Option<Color>| {
+            let (r, g, b) = c.and_then(|c| c.to_rgb()).unwrap_or((0, 0, 0));
+            format!("{:02X}{:02X}{:02X}", r, g, b)
+        };
+
+        formatted.push(HEX_COLOR);
+        formatted.push_str(&hex(fg));
+        if bg.is_some() {
+            formatted.push(',');
+            formatted.push_str(&hex(bg));
+        }
+    } else {
+        let code = |c: Option<Color>| match c {
+            Some(Color::Palette(n)) => n,
+            _ => DEFAULT_COLOR,
+        };
+
+        let _ = write!(formatted, "{}{:02}", COLOR, code(fg));
+        if bg.is_some() {
+            let _ = write!(formatted, ",{:02}", code(bg));
+        }
+    }
+}
+
+fn ansi_color(codes: &mut Vec<String>, color: Color, background: bool) {
+    let offset = if background { 10 } else { 0 };
+    match color {
+        Color::Palette(n) if (n as usize) < ANSI_PALETTE.len() => {
+            codes.push((ANSI_PALETTE[n as usize] + offset).to_string());
+        },
+        color => {
+            if let Some((r, g, b)) = color.to_rgb() {
+                codes.push(format!("{};2;{};{};{}", 38 + offset, r, g, b));
+            }
+        },
+    }
+}
+
+/// Render formatting codes as ANSI terminal escapes
+pub fn to_ansi(text: &str) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut current = Style::default();
+
+    for span in Spans::new(text) {
+        if span.style != current {
+            let style = span.style;
+            let mut codes = vec!["0".to_owned()];
+
+            let attributes = [(style.bold, "1"), (style.italic, "3"), (style.underline, "4"),
+                              (style.reverse, "7"), (style.strikethrough, "9")];
+            for &(enabled, code) in &attributes {
+                if enabled {
+                    codes.push(code.to_owned());
+                }
+            }
+            if let Some(fg) = style.foreground {
+                ansi_color(&mut codes, fg, false);
+            }
+            if let Some(bg) = style.background {
+                ansi_color(&mut codes, bg, true);
+            }
+
+            let _ = write!(rendered, "\x1b[{}m", codes.join(";"));
+            current = style;
+        }
+
+        rendered.push_str(span.text);
+    }
+
+    if !current.is_plain() {
+        rendered.push_str("\x1b[0m");
+    }
+    rendered
+}
+
+fn css_color(color: Color) -> Option<String> {
+    color.to_rgb().map(|(r, g, b)| format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+fn escape_html(rendered: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => rendered.push_str("&amp;"),
+            '<' => rendered.push_str("&lt;"),
+            '>' => rendered.push_str("&gt;"),
+            '"' => rendered.push_str("&quot;"),
+            '\'' => rendered.push_str("&#39;"),
+            c => rendered.push(c),
+        }
+    }
+}
+
+/// Render formatting codes as HTML, the text is escaped
+///
+/// Reversed colors default to white text on black.
+pub fn to_html(text: &str) -> String {
+    let mut rendered = String::with_capacity(text.len());
+
+    for span in Spans::new(text) {
+        let style = span.style;
+        if style.is_plain() {
+            escape_html(&mut rendered, span.text);
+            continue;
+        }
+
+        let mut css = Vec::new();
+        if style.bold {
+            css.push("font-weight:bold".to_owned());
+        }
+        if style.italic {
+            css.push("font-style:italic".to_owned());
+        }
+        match (style.underline, style.strikethrough) {
+            (true, true) => css.push("text-decoration:underline line-through".to_owned()),
+            (true, false) => css.push("text-decoration:underline".to_owned()),
+            (false, true) => css.push("text-decoration:line-through".to_owned()),
+            (false, false) => {},
+        }
+        if style.monospace {
+            css.push("font-family:monospace".to_owned());
+        }
+
+        let (fg, bg) = if style.reverse {
+            (Some(style.background.unwrap_or(Color::WHITE)), Some(style.foreground.unwrap_or(Color::BLACK)))
+        } else {
+            (style.foreground, style.background)
+        };
+        if let Some(color) = fg.and_then(css_color) {
+            css.push(format!("color:{}", color));
+        }
+        if let Some(color) = bg.and_then(css_color) {
+            css.push(format!("background-color:{}", color));
+        }
+
+        let _ = write!(rendered, "<span style=\"{}\">", css.join(";"));
+        escape_html(&mut rendered, span.text);
+        rendered.push_str("</span>");
+    }
+
+    rendered
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sequence_len() {
+        assert_eq!(sequence_len("\x03"), 1);
+        assert_eq!(sequence_len("\x031a"), 2);
+        assert_eq!(sequence_len("\x03123"), 3);
+        assert_eq!(sequence_len("\x0312,"), 3);
+        assert_eq!(sequence_len("\x0312,4"), 5);
+        assert_eq!(sequence_len("\x04FF00FF,000000"), 14);
+        assert_eq!(sequence_len("\x04FF00"), 1);
+        assert_eq!(sequence_len("\x02"), 1);
+        assert_eq!(sequence_len("a"), 0);
+    }
+
+    #[test]
+    fn test_spans() {
+        let spans: Vec<Span> = Spans::new("a\x02\x1db\x0f\x0312,4c\x03,d\x0399e\x03f\x04FF8000g\x16\x1e\x1f\x11h").collect();
+        assert_eq!(spans, vec![
+            Span::plain("a"),
+            Span::new(Style::new().bold().italic(), "b"),
+            Span::new(Style::new().foreground(Color::LIGHT_BLUE).background(Color::RED), "c"),
+            Span::plain(",d"),
+            Span::plain("e"),
+            Span::plain("f"),
+            Span::new(Style::new().foreground(Color::Rgb(0xff, 0x80, 0)), "g"),
+            Span::new(Style::new().foreground(Color::Rgb(0xff, 0x80, 0)).reverse().strikethrough()
+                      .underline().monospace(), "h"),
+        ]);
+
+        let spans: Vec<Span> = Spans::new("\x0304,02a\x0303b").collect();
+        assert_eq!(spans[1].style, Style::new().foreground(Color::GREEN).background(Color::BLUE));
+    }
+
+    #[test]
+    fn test_strip() {
+        assert_eq!(strip("plain text"), Cow::Borrowed("plain text"));
+        assert_eq!(&strip("\x02bold\x02 \x0304,12red\x03 \x04FF0000hex\x0f")[..], "bold red hex");
+        assert_eq!(&strip("\x0312345")[..], "345");
+    }
+
+    #[test]
+    fn test_format_spans() {
+        let spans = [
+            Span::plain("a"),
+            Span::new(Style::new().bold(), "b"),
+            Span::new(Style::new().bold().foreground(Color::RED), "1c"),
+            Span::new(Style::new().foreground(Color::RED).background(Color::BLUE), "d"),
+            Span::new(Style::new().foreground(Color::RED), ",e"),
+            Span::new(Style::new().background(Color::Rgb(1, 2, 3)), "f"),
+            Span::plain("g"),
+        ];
+
+        let formatted = format_spans(&spans);
+        assert_eq!(&formatted[..], "a\x02b\x03041c\x02\x0304,02d\x0f\x0304\x02\x02,e\x0f\x04000000,010203f\x0fg");
+
+        let parsed: Vec<Span> = Spans::new(&formatted).collect();
+        assert_eq!(&parsed[..5], &spans[..5]);
+        assert_eq!(parsed[5].style.background, Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(parsed[6], spans[6]);
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        assert_eq!(&to_ansi("plain")[..], "plain");
+        assert_eq!(&to_ansi("a\x02b\x0304,02c\x0fd")[..], "a\x1b[0;1mb\x1b[0;1;91;44mc\x1b[0md");
+        assert_eq!(&to_ansi("\x0352x\x04010203,x")[..], "\x1b[0;38;2;255;0;0mx\x1b[0;38;2;1;2;3m,x\x1b[0m");
+    }
+
+    #[test]
+    fn test_to_html() {
+        assert_eq!(&to_html("<a & b>")[..], "&lt;a &amp; b&gt;");
+        assert_eq!(&to_html("\x02\x1fbold\x0f \x0304red")[..],
+                   "<span style=\"font-weight:bold;text-decoration:underline\">bold</span> \
+                    <span style=\"color:#ff0000\">red</span>");
+        assert_eq!(&to_html("\x16rev")[..], "<span style=\"color:#ffffff;background-color:#000000\">rev</span>");
+    }
+}
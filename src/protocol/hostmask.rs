@@ -0,0 +1,327 @@
+//! Wildcard masks over `nick!user@host`, as used by bans, ignores and
+//! `WHO`
+//!
+//! ```plain
+//! *!*@*.example.com
+//! WiZ!*@*
+//! *!~jto@192.168.0.0/16
+//! ```
+//!
+//! `*` matches any sequence and `?` any single character, `\` escapes
+//! them. The host may also be written in CIDR notation to match IP
+//! addresses.
+
+use std::fmt;
+use std::net::IpAddr;
+
+use protocol::casemap::CaseMapping;
+use protocol::name::NickName;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Token {
+    Char(char),
+    Any,
+    Star,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum HostPattern {
+    Glob(Vec<Token>),
+    Cidr(IpAddr, u8),
+}
+
+/// A `nick!user@host` pattern
+///
+/// ```
+/// use irc::protocol::casemap::CaseMapping;
+/// use irc::protocol::hostmask::HostMask;
+/// use irc::protocol::name::NickName;
+///
+/// let mask = HostMask::new("*!*@*.fi");
+/// let name = NickName::from_str("WiZ!jto@tolsun.oulu.fi").unwrap();
+/// assert!(mask.matches(&name, CaseMapping::Rfc1459));
+///
+/// assert_eq!(HostMask::new("WiZ").as_str(), "WiZ!*@*");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HostMask {
+    mask: String,
+    nick_user: Vec<Token>,
+    host: HostPattern,
+}
+
+impl HostMask {
+    /// Create a mask, completing partial masks
+    ///
+    /// `nick` becomes `nick!*@*`, `user@host` becomes `*!user@host` and a
+    /// bare host with a `.` or a `:` becomes `*!*@host`.
+    pub fn new(mask: &str) -> HostMask {
+        let mask = normalize(mask);
+
+        let (nick_user, host) = match mask.rfind('@') {
+            Some(idx) => (&mask[..idx], &mask[idx + 1..]),
+            None => (&mask[..], "*"),
+        };
+
+        let nick_user = parse_glob(nick_user);
+        let host = match parse_cidr(host) {
+            Some((addr, bits)) => HostPattern::Cidr(addr, bits),
+            None => HostPattern::Glob(parse_glob(host)),
+        };
+
+        HostMask {
+            mask: mask,
+            nick_user: nick_user,
+            host: host,
+        }
+    }
+
+    /// The normalized mask
+    pub fn as_str(&self) -> &str {
+        &self.mask
+    }
+
+    /// Whether the mask contains no wildcard
+    pub fn is_exact(&self) -> bool {
+        let literal = |t: &Token| match *t {
+            Token::Char(..) => true,
+            Token::Any | Token::Star => false,
+        };
+
+        self.nick_user.iter().all(&literal) && match self.host {
+            HostPattern::Glob(ref host) => host.iter().all(&literal),
+            HostPattern::Cidr(..) => false,
+        }
+    }
+
+    /// Match a full name, a missing user or host is matched as empty
+    pub fn matches(&self, name: &NickName, mapping: CaseMapping) -> bool {
+        self.matches_parts(name.nick(), name.user().unwrap_or(""), name.host().unwrap_or(""), mapping)
+    }
+
+    /// Match a `nick!user@host` string
+    pub fn matches_str(&self, name: &str, mapping: CaseMapping) -> bool {
+        let (nick_user, host) = match name.rfind('@') {
+            Some(idx) => (&name[..idx], &name[idx + 1..]),
+            None => (name, ""),
+        };
+        let (nick, user) = match nick_user.find('!') {
+            Some(idx) => (&nick_user[..idx], &nick_user[idx + 1..]),
+            None => (nick_user, ""),
+        };
+
+        self.matches_parts(nick, user, host, mapping)
+    }
+
+    fn matches_parts(&self, nick: &str, user: &str, host: &str, mapping: CaseMapping) -> bool {
+        let host_matches = match self.host {
+            HostPattern::Glob(ref pattern) => glob_match(pattern, &[host], mapping),
+            HostPattern::Cidr(network, bits) => {
+                match host.parse::<IpAddr>() {
+                    Ok(addr) => cidr_match(network, bits, addr),
+                    Err(..) => false,
+                }
+            },
+        };
+
+        host_matches && glob_match(&self.nick_user, &[nick, "!", user], mapping)
+    }
+}
+
+impl fmt::Display for HostMask {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.mask)
+    }
+}
+
+fn normalize(mask: &str) -> String {
+    let has_nick = mask.contains('!');
+    let has_host = mask.contains('@');
+
+    match (has_nick, has_host) {
+        (true, true) => mask.to_owned(),
+        (true, false) => format!("{}@*", mask),
+        (false, true) => format!("*!{}", mask),
+        (false, false) if mask.contains(&['.', ':'][..]) => format!("*!*@{}", mask),
+        (false, false) => format!("{}!*@*", mask),
+    }
+}
+
+fn parse_glob(s: &str) -> Vec<Token> {
+    let mut tokens = Vec::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '*' => Token::Star,
+            '?' => Token::Any,
+            '\\' => Token::Char(chars.next().unwrap_or('\\')),
+            c => Token::Char(c),
+        };
+
+        // Consecutive stars are the same as one
+        if token == Token::Star && tokens.last() == Some(&Token::Star) {
+            continue;
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn parse_cidr(s: &str) -> Option<(IpAddr, u8)> {
+    let idx = match s.find('/') {
+        None => return None,
+        Some(idx) => idx,
+    };
+
+    let addr = match s[..idx].parse::<IpAddr>() {
+        Err(..) => return None,
+        Ok(addr) => addr,
+    };
+    let max = match addr {
+        IpAddr::V4(..) => 32,
+        IpAddr::V6(..) => 128,
+    };
+
+    match s[idx + 1..].parse::<u8>() {
+        Ok(bits) if bits <= max => Some((addr, bits)),
+        _ => None,
+    }
+}
+
+fn cidr_match(network: IpAddr, bits: u8, addr: IpAddr) -> bool {
+    match (network, addr) {
+        (IpAddr::V4(network), IpAddr::V4(addr)) => {
+            let mask = if bits == 0 { 0 } else { !0u32 << (32 - bits) };
+            (u32::from(network) & mask) == (u32::from(addr) & mask)
+        },
+        (IpAddr::V6(network), IpAddr::V6(addr)) => {
+            let mask = if bits == 0 { 0 } else { !0u128 << (128 - bits) };
+            (u128::from(network) & mask) == (u128::from(addr) & mask)
+        },
+        _ => false,
+    }
+}
+
+/// Match the concatenation of `parts`, backtracking to the last star only
+fn glob_match(pattern: &[Token], parts: &[&str], mapping: CaseMapping) -> bool {
+    let mut subject = parts.iter().flat_map(|p| p.chars());
+
+    let mut p = 0;
+    let mut backtrack = None;
+    loop {
+        if pattern.get(p) == Some(&Token::Star) {
+            p += 1;
+            if p == pattern.len() {
+                return true;
+            }
+            backtrack = Some((p, subject.clone()));
+            continue;
+        }
+
+        let c = subject.next();
+        let matched = match (pattern.get(p), c) {
+            (None, None) => return true,
+            (Some(&Token::Any), Some(..)) => true,
+            (Some(&Token::Char(pc)), Some(c)) => mapping.fold_char(pc) == mapping.fold_char(c),
+            _ => false,
+        };
+
+        if matched {
+            p += 1;
+            continue;
+        }
+
+        match backtrack.take() {
+            Some((star_p, mut star_subject)) => {
+                if star_subject.next().is_none() {
+                    return false;
+                }
+                p = star_p;
+                subject = star_subject.clone();
+                backtrack = Some((star_p, star_subject));
+            },
+            None => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::casemap::CaseMapping;
+    use protocol::name::NickName;
+
+    fn matches(mask: &str, name: &str) -> bool {
+        HostMask::new(mask).matches_str(name, CaseMapping::Rfc1459)
+    }
+
+    #[test]
+    fn test_hostmask_normalize() {
+        assert_eq!(HostMask::new("WiZ").as_str(), "WiZ!*@*");
+        assert_eq!(HostMask::new("WiZ!jto").as_str(), "WiZ!jto@*");
+        assert_eq!(HostMask::new("jto@tolsun.oulu.fi").as_str(), "*!jto@tolsun.oulu.fi");
+        assert_eq!(HostMask::new("*.oulu.fi").as_str(), "*!*@*.oulu.fi");
+        assert_eq!(HostMask::new("2001:db8::/32").as_str(), "*!*@2001:db8::/32");
+        assert_eq!(HostMask::new("*!*@*").as_str(), "*!*@*");
+        assert!(HostMask::new("WiZ!jto@tolsun.oulu.fi").is_exact());
+        assert!(!HostMask::new("WiZ").is_exact());
+    }
+
+    #[test]
+    fn test_hostmask_wildcards() {
+        assert!(matches("*!*@*", "WiZ!jto@tolsun.oulu.fi"));
+        assert!(matches("*!*@*.fi", "WiZ!jto@tolsun.oulu.fi"));
+        assert!(matches("W?Z!*@*", "WiZ!jto@tolsun.oulu.fi"));
+        assert!(matches("*!j*o@*", "WiZ!jto@tolsun.oulu.fi"));
+        assert!(matches("*!*@*o*u*u*", "WiZ!jto@tolsun.oulu.fi"));
+        assert!(!matches("*!*@*.com", "WiZ!jto@tolsun.oulu.fi"));
+        assert!(!matches("W?!*@*", "WiZ!jto@tolsun.oulu.fi"));
+        assert!(!matches("*!jto@*", "WiZ!~jto@tolsun.oulu.fi"));
+        assert!(matches("WiZ", "WiZ!jto@tolsun.oulu.fi"));
+        assert!(!matches("WiZ", "WiZard!jto@tolsun.oulu.fi"));
+        assert!(matches("*a*a*a*a*a*b", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaab!x@y"));
+        assert!(!matches("*a*a*a*a*a*b!*@*", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaa!x@y"));
+    }
+
+    #[test]
+    fn test_hostmask_case() {
+        assert!(matches("[wiz]!*@*.OULU.fi", "{WiZ}!jto@tolsun.oulu.fi"));
+        assert!(!HostMask::new("[wiz]").matches_str("{WiZ}!jto@tolsun.oulu.fi", CaseMapping::Ascii));
+        assert!(HostMask::new("wiz").matches_str("WIZ!jto@tolsun.oulu.fi", CaseMapping::Ascii));
+    }
+
+    #[test]
+    fn test_hostmask_escape() {
+        assert!(matches("a\\*b", "a*b!x@y"));
+        assert!(!matches("a\\*b", "axb!x@y"));
+        assert!(matches("a\\?", "a?!x@y"));
+        assert!(!matches("a\\?", "ab!x@y"));
+    }
+
+    #[test]
+    fn test_hostmask_cidr() {
+        assert!(matches("*!*@192.168.0.0/16", "WiZ!jto@192.168.42.1"));
+        assert!(!matches("*!*@192.168.0.0/16", "WiZ!jto@192.169.42.1"));
+        assert!(!matches("*!*@192.168.0.0/16", "WiZ!jto@tolsun.oulu.fi"));
+        assert!(matches("*!*@0.0.0.0/0", "WiZ!jto@10.0.0.1"));
+        assert!(matches("*!*@2001:db8::/32", "WiZ!jto@2001:db8:1::42"));
+        assert!(!matches("*!*@2001:db8::/32", "WiZ!jto@2001:db9::42"));
+        assert!(!matches("*!*@2001:db8::/32", "WiZ!jto@192.168.42.1"));
+        assert!(!matches("*!nobody@192.168.0.0/16", "WiZ!jto@192.168.42.1"));
+
+        // Not a valid prefix length, matched as a glob
+        assert!(matches("*!*@1.2.3.4/99", "WiZ!jto@1.2.3.4/99"));
+    }
+
+    #[test]
+    fn test_hostmask_nick_name() {
+        let mask = HostMask::new("*!*@*.mozilla.org");
+        assert!(mask.matches(&NickName::from_str("zonyitoo!zony@www.mozilla.org").unwrap(), CaseMapping::Rfc1459));
+        assert!(!mask.matches(&NickName::from_str("zonyitoo").unwrap(), CaseMapping::Rfc1459));
+        assert!(HostMask::new("zonyitoo").matches(&NickName::from_str("ZONYITOO").unwrap(), CaseMapping::Rfc1459));
+        let cloaked = NickName::from_str("zonyitoo!~zony@user/zonyitoo").unwrap();
+        assert!(HostMask::new("*!*@user/*").matches(&cloaked, CaseMapping::Rfc1459));
+    }
+}
@@ -0,0 +1,360 @@
+//! Features advertised by the server in `RPL_ISUPPORT`
+//!
+//! ```plain
+//! :irc.example.com 005 WiZ CHANTYPES=# PREFIX=(ov)@+ NICKLEN=30 :are supported by this server
+//! :irc.example.com 005 WiZ -NICKLEN EXCEPTS :are supported by this server
+//! ```
+//!
+//! Tokens may be sent in several replies, at registration or later, and a
+//! token prefixed with `-` is no longer supported.
+
+use protocol::casemap::CaseMapping;
+use protocol::channel::RFC2811_CHANTYPES;
+use protocol::chanmode::ChannelModes;
+use protocol::message::{ParseMessageError, ParseMessageErrorKind};
+use protocol::reply::{Reply, ReplyCode};
+
+/// Tokens received in `RPL_ISUPPORT` replies
+///
+/// Accessors of the well known tokens return the RFC default when the
+/// server didn't send the token.
+///
+/// ```
+/// use irc::protocol::IrcMessage;
+/// use irc::protocol::isupport::Isupport;
+/// use irc::protocol::reply::Reply;
+///
+/// let reply = Reply::from_str("005 WiZ CHANTYPES=# NICKLEN=30 :are supported by this server").unwrap();
+///
+/// let mut isupport = Isupport::new();
+/// isupport.apply_reply(&reply).unwrap();
+/// assert_eq!(isupport.chantypes(), "#");
+/// assert_eq!(isupport.nicklen(), 30);
+/// assert_eq!(isupport.topiclen(), None);
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Isupport {
+    tokens: Vec<(String, Option<String>)>,
+}
+
+impl Isupport {
+    pub fn new() -> Isupport {
+        Isupport {
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Apply the tokens of a `RPL_ISUPPORT` reply
+    ///
+    /// The last parameter is the human readable text and is ignored.
+    pub fn apply_reply(&mut self, reply: &Reply) -> Result<(), ParseMessageError> {
+        if reply.code() != ReplyCode::RPL_ISUPPORT {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidReplyCode,
+                                              "ISUPPORT tokens require a RPL_ISUPPORT reply"));
+        }
+
+        let params: Vec<&str> = reply.parameters().collect();
+        if params.len() < 2 {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
+                                              "RPL_ISUPPORT requires at least one token"));
+        }
+
+        for token in &params[..params.len() - 1] {
+            self.apply_token(token);
+        }
+        Ok(())
+    }
+
+    /// Apply a single token, such as `NICKLEN=30`, `EXCEPTS` or `-EXCEPTS`
+    pub fn apply_token(&mut self, token: &str) {
+        if let Some(name) = token.strip_prefix('-') {
+            self.remove(name);
+            return;
+        }
+
+        let (name, value) = match token.find('=') {
+            None => (token, None),
+            Some(idx) => (&token[..idx], Some(unescape_value(&token[idx + 1..]))),
+        };
+        if name.is_empty() {
+            return;
+        }
+
+        match self.tokens.iter().position(|&(ref n, _)| n == name) {
+            Some(idx) => self.tokens[idx].1 = value,
+            None => self.tokens.push((name.to_owned(), value)),
+        }
+    }
+
+    /// Forget a token
+    pub fn remove(&mut self, name: &str) {
+        self.tokens.retain(|&(ref n, _)| n != name);
+    }
+
+    /// Whether the server advertised this token
+    pub fn contains(&self, name: &str) -> bool {
+        self.tokens.iter().any(|&(ref n, _)| n == name)
+    }
+
+    /// Value of a token, `None` if it was not advertised or has no value
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.tokens.iter()
+            .find(|&&(ref n, _)| n == name)
+            .and_then(|&(_, ref v)| v.as_ref().map(|v| &v[..]))
+    }
+
+    /// Tokens in the order the server advertised them
+    pub fn iter(&self) -> IsupportIter<'_> {
+        IsupportIter {
+            tokens: self.tokens.iter(),
+        }
+    }
+
+    fn number(&self, name: &str) -> Option<usize> {
+        self.value(name).and_then(|v| v.parse().ok())
+    }
+
+    /// `CHANTYPES`, the channel prefixes, `#&+!` of RFC 2811 by default
+    pub fn chantypes(&self) -> &str {
+        match self.tokens.iter().find(|&&(ref n, _)| n == "CHANTYPES") {
+            None => RFC2811_CHANTYPES,
+            Some(&(_, ref v)) => v.as_ref().map(|v| &v[..]).unwrap_or(""),
+        }
+    }
+
+    /// `PREFIX`, such as `(ov)@+`
+    pub fn prefix(&self) -> Option<&str> {
+        self.value("PREFIX")
+    }
+
+    /// `CHANMODES`, such as `beI,k,l,imnpst`
+    pub fn chanmodes(&self) -> Option<&str> {
+        self.value("CHANMODES")
+    }
+
+    /// Channel mode table from `CHANMODES` and `PREFIX`, RFC 2811 for the
+    /// missing ones
+    pub fn channel_modes(&self) -> Result<ChannelModes, ParseMessageError> {
+        let mut modes = ChannelModes::rfc2811();
+        if let Some(chanmodes) = self.chanmodes() {
+            try!(modes.set_chanmodes(chanmodes));
+        }
+        if self.contains("PREFIX") {
+            try!(modes.set_prefix(self.prefix().unwrap_or("")));
+        }
+        Ok(modes)
+    }
+
+    /// `CASEMAPPING`, `rfc1459` by default
+    pub fn casemapping(&self) -> &str {
+        self.value("CASEMAPPING").unwrap_or("rfc1459")
+    }
+
+    /// Mapping of `CASEMAPPING`, `rfc1459` for unknown mappings
+    pub fn case_mapping(&self) -> CaseMapping {
+        CaseMapping::from_name(self.casemapping()).unwrap_or_default()
+    }
+
+    /// `NICKLEN`, 9 by default
+    pub fn nicklen(&self) -> usize {
+        self.number("NICKLEN").unwrap_or(9)
+    }
+
+    /// `CHANNELLEN`, 50 by default
+    pub fn channellen(&self) -> usize {
+        self.number("CHANNELLEN").unwrap_or(50)
+    }
+
+    /// `TOPICLEN`, `None` without limit
+    pub fn topiclen(&self) -> Option<usize> {
+        self.number("TOPICLEN")
+    }
+
+    /// Maximum number of targets of a command in `TARGMAX`, such as
+    /// `PRIVMSG:4,NOTICE:4,JOIN:`, `None` without limit
+    pub fn targmax(&self, command: &str) -> Option<usize> {
+        self.value("TARGMAX").and_then(|targmax| {
+            targmax.split(',')
+                .filter_map(|entry| {
+                    let mut parts = entry.splitn(2, ':');
+                    match (parts.next(), parts.next()) {
+                        (Some(cmd), Some(limit)) if cmd.eq_ignore_ascii_case(command) => Some(limit),
+                        _ => None,
+                    }
+                })
+                .next()
+                .and_then(|limit| limit.parse().ok())
+        })
+    }
+
+    /// `MODES`, the maximum number of modes with an argument in a `MODE`,
+    /// 3 by default and `None` without limit
+    pub fn modes(&self) -> Option<usize> {
+        if self.contains("MODES") {
+            self.number("MODES")
+        } else {
+            Some(3)
+        }
+    }
+
+    /// `NETWORK`, the name of the network
+    pub fn network(&self) -> Option<&str> {
+        self.value("NETWORK")
+    }
+
+    /// `EXCEPTS`, the ban exception mode, `e` if the token has no value
+    pub fn excepts(&self) -> Option<char> {
+        self.mode_letter("EXCEPTS", 'e')
+    }
+
+    /// `INVEX`, the invite exception mode, `I` if the token has no value
+    pub fn invex(&self) -> Option<char> {
+        self.mode_letter("INVEX", 'I')
+    }
+
+    fn mode_letter(&self, name: &str, default: char) -> Option<char> {
+        if !self.contains(name) {
+            return None;
+        }
+        Some(self.value(name).and_then(|v| v.chars().next()).unwrap_or(default))
+    }
+
+    /// `STATUSMSG`, the prefixes to message channel members with a status,
+    /// such as `@+`
+    pub fn statusmsg(&self) -> &str {
+        self.value("STATUSMSG").unwrap_or("")
+    }
+
+    /// `ELIST`, the search extensions of `LIST`, such as `MNU`
+    pub fn elist(&self) -> &str {
+        self.value("ELIST").unwrap_or("")
+    }
+}
+
+/// Iterator of the tokens and their values
+pub struct IsupportIter<'a> {
+    tokens: ::std::slice::Iter<'a, (String, Option<String>)>,
+}
+
+impl<'a> Iterator for IsupportIter<'a> {
+    type Item = (&'a str, Option<&'a str>);
+
+    fn next(&mut self) -> Option<(&'a str, Option<&'a str>)> {
+        self.tokens.next().map(|&(ref n, ref v)| (&n[..], v.as_ref().map(|v| &v[..])))
+    }
+}
+
+/// Unescape the `\xHH` sequences of a token value
+fn unescape_value(value: &str) -> String {
+    if !value.contains("\\x") {
+        return value.to_owned();
+    }
+
+    let bytes = value.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() && bytes[i + 1] == b'x' &&
+           bytes[i + 2].is_ascii_hexdigit() && bytes[i + 3].is_ascii_hexdigit() {
+            // Both digits are ASCII, so the slice is on char boundaries
+            if let Ok(b) = u8::from_str_radix(&value[i + 2..i + 4], 16) {
+                result.push(b);
+                i += 4;
+                continue;
+            }
+        }
+        result.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::chanmode::ChannelModeKind;
+    use protocol::name::NameLimits;
+    use protocol::message::IrcMessage;
+    use protocol::reply::Reply;
+
+    fn isupport(lines: &[&str]) -> Isupport {
+        let mut isupport = Isupport::new();
+        for line in lines {
+            isupport.apply_reply(&Reply::from_str(line).unwrap()).unwrap();
+        }
+        isupport
+    }
+
+    #[test]
+    fn test_isupport_defaults() {
+        let isupport = Isupport::new();
+        assert_eq!(isupport.chantypes(), RFC2811_CHANTYPES);
+        assert_eq!(NameLimits::from_isupport(&isupport), NameLimits::rfc2812());
+        assert_eq!(isupport.casemapping(), "rfc1459");
+        assert_eq!(isupport.case_mapping(), CaseMapping::Rfc1459);
+        assert_eq!(isupport.nicklen(), 9);
+        assert_eq!(isupport.channellen(), 50);
+        assert_eq!(isupport.topiclen(), None);
+        assert_eq!(isupport.modes(), Some(3));
+        assert_eq!(isupport.targmax("PRIVMSG"), None);
+        assert_eq!(isupport.excepts(), None);
+        assert_eq!(isupport.statusmsg(), "");
+        assert_eq!(isupport.channel_modes().unwrap(), ChannelModes::rfc2811());
+    }
+
+    #[test]
+    fn test_isupport_tokens() {
+        let isupport = isupport(&[
+            "005 WiZ AWAYLEN=200 CASEMAPPING=ascii CHANMODES=Zbeg,k,FLfjl,ABCDKMNOQRSTcimnprstuz \
+             CHANTYPES=# PREFIX=(ov)@+ :are supported by this server",
+            "005 WiZ EXCEPTS INVEX=J MODES NETWORK=Example\\x20Net NICKLEN=30 STATUSMSG=@+ ELIST=MNU \
+             TARGMAX=PRIVMSG:4,NOTICE:4,JOIN: TOPICLEN=390 :are supported by this server",
+        ]);
+
+        assert_eq!(isupport.casemapping(), "ascii");
+        assert_eq!(isupport.case_mapping(), CaseMapping::Ascii);
+        assert_eq!(isupport.chantypes(), "#");
+        assert_eq!(isupport.nicklen(), 30);
+        assert_eq!(isupport.topiclen(), Some(390));
+        assert_eq!(isupport.modes(), None);
+        assert_eq!(isupport.network(), Some("Example Net"));
+        assert_eq!(isupport.excepts(), Some('e'));
+        assert_eq!(isupport.invex(), Some('J'));
+        assert_eq!(isupport.statusmsg(), "@+");
+        assert_eq!(isupport.elist(), "MNU");
+        assert_eq!(isupport.targmax("privmsg"), Some(4));
+        assert_eq!(isupport.targmax("JOIN"), None);
+        assert_eq!(isupport.value("AWAYLEN"), Some("200"));
+
+        let modes = isupport.channel_modes().unwrap();
+        assert_eq!(modes.kind('F'), Some(ChannelModeKind::SetOnly));
+        assert_eq!(modes.kind('o'), Some(ChannelModeKind::Prefix));
+        assert_eq!(modes.kind('h'), None);
+    }
+
+    #[test]
+    fn test_isupport_update() {
+        let mut isupport = isupport(&["005 WiZ NICKLEN=30 EXCEPTS CHANTYPES= :are supported by this server"]);
+        assert_eq!(isupport.chantypes(), "");
+
+        isupport.apply_reply(&Reply::from_str("005 WiZ -EXCEPTS NICKLEN=16 :are supported").unwrap()).unwrap();
+        assert_eq!(isupport.nicklen(), 16);
+        assert!(!isupport.contains("EXCEPTS"));
+        assert_eq!(isupport.iter().collect::<Vec<_>>(), vec![("NICKLEN", Some("16")), ("CHANTYPES", Some(""))]);
+
+        assert!(isupport.apply_reply(&Reply::from_str("001 WiZ :Welcome").unwrap()).is_err());
+        assert!(isupport.apply_reply(&Reply::from_str("005 WiZ :are supported").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_isupport_escapes() {
+        let mut isupport = Isupport::new();
+        isupport.apply_token("NETWORK=\\xaé");
+        assert_eq!(isupport.network(), Some("\\xaé"));
+        isupport.apply_token("FOO=\\x猫");
+        assert_eq!(isupport.value("FOO"), Some("\\x猫"));
+        isupport.apply_token("BAR=a\\x3Db\\x\\x2");
+        assert_eq!(isupport.value("BAR"), Some("a=b\\x\\x2"));
+    }
+}
@@ -1,18 +1,23 @@
 
 use std::fmt;
+use std::error;
+use std::borrow::Cow;
 use std::iter::Iterator;
 use std::str::FromStr;
 use std::convert::From;
 
 use protocol::command::Command;
-use protocol::reply::Reply;
+use protocol::reply::{Reply, ReplyCode};
+use protocol::tag::MessageTags;
+use protocol::encoding::{Encoding, EncodingError};
+use protocol::name::Prefix;
 
 pub trait IrcMessage<'a>: fmt::Display + Sized {
     fn from_raw(raw: &RawMessage<'a>) -> Result<Self, ParseMessageError>;
 
     fn from_str(s: &'a str) -> Result<Self, ParseMessageError> {
         let raw_msg = try!(RawMessage::from_str(s));
-        Self::from_raw(&raw_msg)
+        Self::from_raw(&raw_msg).map_err(|err| err.locate(s.as_bytes()))
     }
 }
 
@@ -24,12 +29,16 @@ pub enum ParseMessageErrorKind {
     UnrecognizedCommand,
     UnrecognizedReply,
     MissingPrefix,
+    InvalidEncoding,
 }
 
 pub struct ParseMessageError {
     kind: ParseMessageErrorKind,
     desc: &'static str,
     detail: Option<String>,
+    command: Option<String>,
+    param_index: Option<usize>,
+    offset: Option<usize>,
 }
 
 impl ParseMessageError {
@@ -38,6 +47,9 @@ impl ParseMessageError {
             kind: kind,
             desc: desc,
             detail: Some(detail),
+            command: None,
+            param_index: None,
+            offset: None,
         }
     }
 
@@ -46,9 +58,54 @@ impl ParseMessageError {
             kind: kind,
             desc: desc,
             detail: None,
+            command: None,
+            param_index: None,
+            offset: None,
         }
     }
 
+    /// Attach the name of the command which failed to parse
+    pub fn with_command(mut self, command: &str) -> ParseMessageError {
+        self.command = Some(command.to_owned());
+        self
+    }
+
+    /// Attach the index of the parameter which failed to parse. For a missing
+    /// parameter, this is the index it should have had
+    pub fn with_param_index(mut self, index: usize) -> ParseMessageError {
+        self.param_index = Some(index);
+        self
+    }
+
+    /// Attach the byte offset in the line where parsing failed
+    pub fn with_offset(mut self, offset: usize) -> ParseMessageError {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Fill in the command and, for missing parameters, the parameter index
+    /// from the message which failed to parse
+    pub fn with_raw(mut self, raw: &RawMessage) -> ParseMessageError {
+        if self.command.is_none() {
+            self.command = Some(raw.command().to_owned());
+        }
+
+        if self.param_index.is_none() && self.kind == ParseMessageErrorKind::NeedMoreParams {
+            self.param_index = Some(raw.parameters().count());
+        }
+
+        self
+    }
+
+    /// Compute the byte offset from the parameter index, `line` is the line
+    /// the message was parsed from
+    pub fn locate(mut self, line: &[u8]) -> ParseMessageError {
+        if self.offset.is_none() {
+            self.offset = self.param_index.map(|idx| param_offset(line, idx));
+        }
+        self
+    }
+
     pub fn kind(&self) -> ParseMessageErrorKind {
         self.kind
     }
@@ -60,23 +117,113 @@ impl ParseMessageError {
     pub fn detail<'a>(&'a self) -> Option<&'a str> {
         self.detail.as_ref().map(|x| &x[..])
     }
+
+    /// Name of the command which failed to parse
+    pub fn command(&self) -> Option<&str> {
+        self.command.as_ref().map(|x| &x[..])
+    }
+
+    /// Index of the parameter which failed to parse
+    pub fn param_index(&self) -> Option<usize> {
+        self.param_index
+    }
+
+    /// Byte offset in the line where parsing failed
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// The numeric reply a server should send back for this error
+    ///
+    /// `target` is the nickname of the client, or `*` before registration.
+    /// Only errors which have a matching numeric and know their command
+    /// produce a reply.
+    pub fn to_reply(&self, target: &str) -> Option<Reply<'static>> {
+        let command = match self.command {
+            None => return None,
+            Some(ref c) => c,
+        };
+
+        let (code, text) = match self.kind {
+            ParseMessageErrorKind::NeedMoreParams => (ReplyCode::ERR_NEEDMOREPARAMS, "Not enough parameters"),
+            ParseMessageErrorKind::UnrecognizedCommand => (ReplyCode::ERR_UNKNOWNCOMMAND, "Unknown command"),
+            _ => return None,
+        };
+
+        let params = format!("{} :{}", command, text);
+        Some(Reply::new(code, target, &params).into_owned())
+    }
+
+    fn fmt_location(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sep = " (";
+        if let Some(ref cmd) = self.command {
+            try!(write!(f, "{}command {}", sep, cmd));
+            sep = ", ";
+        }
+        if let Some(idx) = self.param_index {
+            try!(write!(f, "{}parameter {}", sep, idx));
+            sep = ", ";
+        }
+        if let Some(offset) = self.offset {
+            try!(write!(f, "{}offset {}", sep, offset));
+            sep = ", ";
+        }
+
+        if sep == ", " { write!(f, ")") } else { Ok(()) }
+    }
 }
 
 impl fmt::Debug for ParseMessageError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         try!(write!(f, "{:?} {}", self.kind, self.desc));
-        match self.detail.as_ref() {
-            Some(ref det) => write!(f, " ({})", det),
-            None => Ok(())
+        if let Some(ref det) = self.detail.as_ref() {
+            try!(write!(f, " ({})", det));
         }
+        self.fmt_location(f)
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+impl fmt::Display for ParseMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{}", self.desc));
+        if let Some(ref det) = self.detail.as_ref() {
+            try!(write!(f, ": {}", det));
+        }
+        self.fmt_location(f)
+    }
+}
+
+impl error::Error for ParseMessageError {}
+
+/// Byte offset of the parameter at `index` in a line, or the end of the line
+/// if there are fewer parameters
+fn param_offset(line: &[u8], index: usize) -> usize {
+    let mut cur = trim_left_bytes(line);
+
+    if cur.starts_with(b"@") {
+        cur = split_word(cur).1;
+    }
+    if cur.starts_with(b":") {
+        cur = split_word(cur).1;
+    }
+    cur = split_word(cur).1;
+
+    for _ in 0..index {
+        if cur.is_empty() || cur.starts_with(b":") {
+            return line.len();
+        }
+        cur = split_word(cur).1;
+    }
+
+    line.len() - cur.len()
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct RawMessage<'a> {
-    prefix: Option<&'a str>,
-    command: &'a str,
-    params: &'a str,
+    tags: Option<Cow<'a, str>>,
+    prefix: Option<Cow<'a, str>>,
+    command: Cow<'a, str>,
+    params: Cow<'a, str>,
 }
 
 impl<'a> RawMessage<'a> {
@@ -88,9 +235,27 @@ impl<'a> RawMessage<'a> {
                params: &'a str) -> RawMessage<'a>
     {
         RawMessage {
-            prefix: prefix,
-            command: command,
-            params: params,
+            tags: None,
+            prefix: prefix.map(Cow::Borrowed),
+            command: Cow::Borrowed(command),
+            params: Cow::Borrowed(params),
+        }
+    }
+
+    /// Create a new `RawMessage` with IRCv3 message tags
+    ///
+    /// `tags` is the escaped tags section without the leading `@`.
+    /// This function will not validate the parameters
+    pub fn with_tags(tags: Option<&'a str>,
+                     prefix: Option<&'a str>,
+                     command: &'a str,
+                     params: &'a str) -> RawMessage<'a>
+    {
+        RawMessage {
+            tags: tags.map(Cow::Borrowed),
+            prefix: prefix.map(Cow::Borrowed),
+            command: Cow::Borrowed(command),
+            params: Cow::Borrowed(params),
         }
     }
 
@@ -98,6 +263,31 @@ impl<'a> RawMessage<'a> {
     pub fn from_str(s: &'a str) -> Result<RawMessage<'a>, ParseMessageError> {
         let mut cur = s.trim_left();
 
+        let tags = if cur.starts_with("@") {
+            let offset = s.len() - cur.len();
+            let t = match cur.find(' ') {
+                None => {
+                    let t = &cur[1..];
+                    cur = &cur[cur.len()..];
+                    t
+                },
+                Some(idx) => {
+                    let t = &cur[1..idx];
+                    cur = cur[idx..].trim_left();
+                    t
+                }
+            };
+
+            if t.is_empty() {
+                return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                                  "Tags section should not be empty").with_offset(offset));
+            }
+
+            Some(t)
+        } else {
+            None
+        };
+
         let prefix = if cur.starts_with(":") {
             match cur.find(' ') {
                 None => {
@@ -130,34 +320,207 @@ impl<'a> RawMessage<'a> {
 
         if command.is_empty() {
             return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
-                                              "Require a command or a numeric error code"));
+                                              "Require a command or a numeric error code")
+                       .with_offset(s.len()));
+        }
+
+        let msg = RawMessage {
+            tags: tags.map(Cow::Borrowed),
+            prefix: prefix.map(Cow::Borrowed),
+            command: Cow::Borrowed(command),
+            params: Cow::Borrowed(cur),
+        };
+
+        Ok(msg)
+    }
+
+    /// Parse raw bytes to a `RawMessage`, decoding text with `encoding`
+    ///
+    /// The message is split on raw bytes first and each part is decoded on
+    /// its own, so with `Encoding::Utf8WithFallback` a UTF-8 channel name
+    /// survives next to a Latin-1 trailing parameter. The result borrows
+    /// from `bytes` as long as they are valid UTF-8, or ASCII with
+    /// `Encoding::Legacy`.
+    pub fn from_bytes(bytes: &'a [u8], encoding: Encoding) -> Result<RawMessage<'a>, ParseMessageError> {
+        let decode = |part: &'a [u8]| -> Result<Cow<'a, str>, ParseMessageError> {
+            encoding.decode(part).map_err(|err| {
+                let offset = part.as_ptr() as usize - bytes.as_ptr() as usize;
+                let detail = match err {
+                    EncodingError::InvalidBytes(pos) => EncodingError::InvalidBytes(offset + pos),
+                    err => err,
+                };
+                let err_offset = match detail {
+                    EncodingError::InvalidBytes(pos) => pos,
+                    _ => offset,
+                };
+                ParseMessageError::new_with_detail(ParseMessageErrorKind::InvalidEncoding,
+                                                   "Message is not valid in the text encoding",
+                                                   detail.to_string())
+                    .with_offset(err_offset)
+            })
+        };
+
+        let mut cur = trim_left_bytes(bytes);
+
+        let tags = if cur.starts_with(b"@") {
+            let offset = bytes.len() - cur.len();
+            let (t, rest) = split_word(&cur[1..]);
+            if t.is_empty() {
+                return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                                  "Tags section should not be empty").with_offset(offset));
+            }
+            cur = rest;
+            Some(try!(decode(t)))
+        } else {
+            None
+        };
+
+        let prefix = if cur.starts_with(b":") {
+            let (p, rest) = split_word(&cur[1..]);
+            cur = rest;
+            Some(try!(decode(p)))
+        } else {
+            None
+        };
+
+        let (command, params) = split_word(cur);
+
+        if command.is_empty() {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
+                                              "Require a command or a numeric error code")
+                       .with_offset(bytes.len()));
         }
 
+        let command = try!(decode(command));
+
+        let params = match (encoding, ::std::str::from_utf8(params)) {
+            // Bytes which happen to be valid UTF-8 are still legacy text
+            (Encoding::Legacy(..), _) => try!(decode(params)),
+            (_, Ok(p)) => Cow::Borrowed(p),
+            (_, Err(..)) => {
+                // Decode every parameter on its own, keeping the separators
+                let mut decoded = String::with_capacity(params.len() + 16);
+                let mut rest = params;
+                while !rest.is_empty() {
+                    if rest[0] == b':' {
+                        decoded.push(':');
+                        decoded.push_str(&try!(decode(&rest[1..])));
+                        break;
+                    }
+
+                    let (param, next) = split_word(rest);
+                    decoded.push_str(&try!(decode(param)));
+                    for _ in 0..(rest.len() - param.len() - next.len()) {
+                        decoded.push(' ');
+                    }
+                    rest = next;
+                }
+                Cow::Owned(decoded)
+            }
+        };
+
         let msg = RawMessage {
+            tags: tags,
             prefix: prefix,
             command: command,
-            params: cur,
+            params: params,
         };
 
         Ok(msg)
     }
 
+    /// Serialize to bytes with `encoding`, without the trailing `CR-LF`
+    pub fn to_bytes(&self, encoding: Encoding) -> Result<Vec<u8>, EncodingError> {
+        encoding.encode(&self.to_string()).map(|b| b.into_owned())
+    }
+
+    /// Get IRCv3 message tags
+    pub fn tags(&self) -> MessageTags<'_> {
+        MessageTags::wrap(self.tags.as_ref().map(|t| &t[..]).unwrap_or(""))
+    }
+
     /// Get prefix
-    pub fn prefix(&self) -> Option<&'a str> {
-        self.prefix
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_ref().map(|p| &p[..])
     }
 
     /// Get command
-    pub fn command(&self) -> &'a str {
+    pub fn command(&self) -> &str {
         &self.command
     }
 
     /// Get parameters
-    pub fn parameters(&self) -> MessageParamIter<'a> {
+    pub fn parameters(&self) -> MessageParamIter<'_> {
         MessageParamIter {
             data: &self.params,
         }
     }
+
+    /// Get parameters which keep the lifetime of the parsed data
+    ///
+    /// Parameters are borrowed from the original input if this message is
+    /// borrowed, otherwise they are copied out.
+    pub fn cow_parameters<'r>(&'r self) -> CowParamIter<'r, 'a> {
+        CowParamIter {
+            src: &self.params,
+            pos: 0,
+        }
+    }
+
+    /// Convert to a `RawMessage` which owns all its data
+    pub fn into_owned(self) -> RawMessage<'static> {
+        RawMessage {
+            tags: self.tags.map(|t| Cow::Owned(t.into_owned())),
+            prefix: self.prefix.map(|p| Cow::Owned(p.into_owned())),
+            command: Cow::Owned(self.command.into_owned()),
+            params: Cow::Owned(self.params.into_owned()),
+        }
+    }
+
+    /// Borrow a `RawMessage` from this one without copying
+    pub fn as_borrowed<'b>(&'b self) -> RawMessage<'b> {
+        RawMessage {
+            tags: self.tags.as_ref().map(|t| Cow::Borrowed(&t[..])),
+            prefix: self.prefix.as_ref().map(|p| Cow::Borrowed(&p[..])),
+            command: Cow::Borrowed(&self.command[..]),
+            params: Cow::Borrowed(&self.params[..]),
+        }
+    }
+}
+
+fn trim_left_bytes(s: &[u8]) -> &[u8] {
+    let start = s.iter().position(|&b| b != b' ' && b != b'\t' && b != b'\r' && b != b'\n');
+    &s[start.unwrap_or(s.len())..]
+}
+
+/// Split off the first space separated word and the rest, with leading
+/// spaces removed
+fn split_word(s: &[u8]) -> (&[u8], &[u8]) {
+    match s.iter().position(|&b| b == b' ') {
+        None => (s, &s[s.len()..]),
+        Some(idx) => {
+            let rest = &s[idx..];
+            let start = rest.iter().position(|&b| b != b' ').unwrap_or(rest.len());
+            (&s[..idx], &rest[start..])
+        }
+    }
+}
+
+/// Displays the last parameter of a message
+///
+/// The leading `:` is only added when the parameter would not parse back
+/// as a single parameter without it, that is when it is empty, contains
+/// a space or starts with `:`.
+pub struct LastParam<'a>(pub &'a str);
+
+impl<'a> fmt::Display for LastParam<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() || self.0.starts_with(':') || self.0.contains(' ') {
+            write!(f, ":{}", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
 }
 
 pub struct MessageParamIter<'a> {
@@ -203,8 +566,70 @@ impl<'a> Iterator for MessageParamIter<'a> {
     }
 }
 
+/// Iterator of parameters in a `RawMessage` which keeps the lifetime of the
+/// parsed data
+pub struct CowParamIter<'r, 'a: 'r> {
+    src: &'r Cow<'a, str>,
+    pos: usize,
+}
+
+impl<'r, 'a: 'r> CowParamIter<'r, 'a> {
+    /// Get the rest of the parameters
+    pub fn get(&self) -> Cow<'a, str> {
+        let len = self.src.len();
+        self.slice(self.pos, len)
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Cow<'a, str> {
+        match *self.src {
+            Cow::Borrowed(s) => Cow::Borrowed(&s[start..end]),
+            Cow::Owned(ref s) => Cow::Owned(s[start..end].to_owned()),
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.src[self.pos..].starts_with(' ') {
+            self.pos += 1;
+        }
+    }
+}
+
+impl<'r, 'a: 'r> Iterator for CowParamIter<'r, 'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Cow<'a, str>> {
+        let len = self.src.len();
+
+        if self.pos >= len {
+            None
+        } else if self.src[self.pos..].starts_with(":") {
+            let cur = self.slice(self.pos + 1, len);
+            self.pos = len;
+            Some(cur)
+        } else {
+            match self.src[self.pos..].find(' ') {
+                None => {
+                    let cur = self.slice(self.pos, len);
+                    self.pos = len;
+                    Some(cur)
+                },
+                Some(idx) => {
+                    let cur = self.slice(self.pos, self.pos + idx);
+                    self.pos += idx;
+                    self.skip_spaces();
+                    Some(cur)
+                }
+            }
+        }
+    }
+}
+
 impl<'a> fmt::Display for RawMessage<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(tags) = self.tags.as_ref() {
+            try!(write!(f, "@{} ", tags))
+        }
+
         if let Some(prefix) = self.prefix.as_ref() {
             try!(write!(f, ":{} ", prefix))
         }
@@ -248,6 +673,24 @@ impl<'a> Body<'a> {
     pub fn unrecognized(raw: RawMessage<'a>) -> Body<'a> {
         Body::Unrecognized(raw)
     }
+
+    /// Convert to a `Body` which owns all its data
+    pub fn into_owned(self) -> Body<'static> {
+        match self {
+            Body::Command(c) => Body::Command(c.into_owned()),
+            Body::Reply(r) => Body::Reply(r.into_owned()),
+            Body::Unrecognized(r) => Body::Unrecognized(r.into_owned()),
+        }
+    }
+
+    /// Borrow a `Body` from this one without copying
+    pub fn as_borrowed<'b>(&'b self) -> Body<'b> {
+        match self {
+            &Body::Command(ref c) => Body::Command(c.as_borrowed()),
+            &Body::Reply(ref r) => Body::Reply(r.as_borrowed()),
+            &Body::Unrecognized(ref r) => Body::Unrecognized(r.as_borrowed()),
+        }
+    }
 }
 
 impl<'a> fmt::Display for Body<'a> {
@@ -280,31 +723,112 @@ impl<'a> From<RawMessage<'a>> for Body<'a> {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Message<'a> {
-    prefix: Option<&'a str>,
+    tags: Option<Cow<'a, str>>,
+    prefix: Option<Cow<'a, str>>,
     body: Body<'a>,
 }
 
 impl<'a> Message<'a> {
     pub fn new(prefix: Option<&'a str>, body: Body<'a>) -> Message<'a> {
         Message {
-            prefix: prefix,
+            tags: None,
+            prefix: prefix.map(Cow::Borrowed),
+            body: body,
+        }
+    }
+
+    /// Create a message with IRCv3 message tags
+    ///
+    /// `tags` is the escaped tags section without the leading `@`
+    pub fn with_tags(tags: Option<&'a str>, prefix: Option<&'a str>, body: Body<'a>) -> Message<'a> {
+        Message {
+            tags: tags.map(Cow::Borrowed),
+            prefix: prefix.map(Cow::Borrowed),
             body: body,
         }
     }
 
-    pub fn prefix<'s>(&'s self) -> Option<&'a str> {
-        self.prefix
+    pub fn tags(&self) -> MessageTags<'_> {
+        MessageTags::wrap(self.tags.as_ref().map(|t| &t[..]).unwrap_or(""))
+    }
+
+    /// Create a message with a typed prefix
+    pub fn with_source(source: Option<&Prefix>, body: Body<'a>) -> Message<'a> {
+        Message {
+            tags: None,
+            prefix: source.map(|s| Cow::Owned(s.to_string())),
+            body: body,
+        }
+    }
+
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_ref().map(|p| &p[..])
+    }
+
+    /// Parse the prefix to find out who sent the message
+    ///
+    /// Returns `Ok(None)` if the message has no prefix
+    pub fn source(&self) -> Result<Option<Prefix<'_>>, ParseMessageError> {
+        match self.prefix() {
+            None => Ok(None),
+            Some(p) => Prefix::parse(p).map(Some),
+        }
+    }
+
+    /// Get the nickname of the sender
+    ///
+    /// Returns `None` if the message has no prefix, was sent by a server, or
+    /// the prefix is invalid
+    pub fn nick(&self) -> Option<&str> {
+        match self.source() {
+            Ok(Some(source)) => source.nick(),
+            _ => None,
+        }
     }
 
     pub fn body<'s>(&'s self) -> &'s Body<'a> {
         &self.body
     }
+
+    /// Parse raw bytes to a `Message`, see `RawMessage::from_bytes`
+    pub fn from_bytes(bytes: &'a [u8], encoding: Encoding) -> Result<Message<'a>, ParseMessageError> {
+        let raw = try!(RawMessage::from_bytes(bytes, encoding));
+        Message::from_raw(&raw).map_err(|err| err.locate(bytes))
+    }
+
+    /// Serialize to bytes with `encoding`, without the trailing `CR-LF`
+    pub fn to_bytes(&self, encoding: Encoding) -> Result<Vec<u8>, EncodingError> {
+        encoding.encode(&self.to_string()).map(|b| b.into_owned())
+    }
+
+    /// Convert to a `Message` which owns all its data, so it can outlive
+    /// the buffer it was parsed from
+    pub fn into_owned(self) -> Message<'static> {
+        Message {
+            tags: self.tags.map(|t| Cow::Owned(t.into_owned())),
+            prefix: self.prefix.map(|p| Cow::Owned(p.into_owned())),
+            body: self.body.into_owned(),
+        }
+    }
+
+    /// Borrow a `Message` from this one without copying
+    pub fn as_borrowed<'b>(&'b self) -> Message<'b> {
+        Message {
+            tags: self.tags.as_ref().map(|t| Cow::Borrowed(&t[..])),
+            prefix: self.prefix.as_ref().map(|p| Cow::Borrowed(&p[..])),
+            body: self.body.as_borrowed(),
+        }
+    }
 }
 
 impl<'a> fmt::Display for Message<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(tags) = self.tags.as_ref() {
+            try!(write!(f, "@{} ", tags));
+        }
+
         if let Some(ref prefix) = self.prefix.as_ref() {
-            try!(write!(f, "{} ", prefix));
+            try!(write!(f, ":{} ", prefix));
         }
 
         write!(f, "{}", self.body)
@@ -313,14 +837,15 @@ impl<'a> fmt::Display for Message<'a> {
 
 impl<'a> IrcMessage<'a> for Message<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<Message<'a>, ParseMessageError> {
-        let prefix = raw.prefix();
+        let prefix = raw.prefix.clone();
 
         let body = {
             match Reply::from_raw(raw) {
                 Ok(cmd) => {
                     if prefix.is_none() {
                         return Err(ParseMessageError::new(ParseMessageErrorKind::MissingPrefix,
-                                                          "Prefix is required in reply"));
+                                                          "Prefix is required in reply")
+                                   .with_command(raw.command()));
                     }
 
                     Body::Reply(cmd)
@@ -332,7 +857,7 @@ impl<'a> IrcMessage<'a> for Message<'a> {
                             Ok(cmd) => Body::Command(cmd),
                             Err(err) => match err.kind() {
                                 ParseMessageErrorKind::UnrecognizedCommand => {
-                                    Body::Unrecognized(*raw)
+                                    Body::Unrecognized(raw.clone())
                                 },
                                 _ => {
                                     return Err(err);
@@ -341,7 +866,7 @@ impl<'a> IrcMessage<'a> for Message<'a> {
                         }
                     },
                     ParseMessageErrorKind::UnrecognizedReply => {
-                        Body::Unrecognized(*raw)
+                        Body::Unrecognized(raw.clone())
                     },
                     _ => {
                         return Err(err);
@@ -350,7 +875,11 @@ impl<'a> IrcMessage<'a> for Message<'a> {
             }
         };
 
-        Ok(Message::new(prefix, body))
+        Ok(Message {
+            tags: raw.tags.clone(),
+            prefix: prefix,
+            body: body,
+        })
     }
 }
 
@@ -358,15 +887,18 @@ impl<'a> IrcMessage<'a> for Message<'a> {
 mod test {
     use super::*;
 
+    use protocol::encoding::LegacyEncoding;
+
     #[test]
     fn test_raw_message_basic() {
         let data = ":fripp.mozilla.org NOTICE Auth :*** Looking up your hostname...";
         let raw_message = RawMessage::from_str(data).unwrap();
 
         let expected = RawMessage {
-            prefix: Some("fripp.mozilla.org"),
-            command: "NOTICE",
-            params: "Auth :*** Looking up your hostname...",
+            tags: None,
+            prefix: Some("fripp.mozilla.org".into()),
+            command: "NOTICE".into(),
+            params: "Auth :*** Looking up your hostname...".into(),
         };
 
         assert_eq!(expected, raw_message);
@@ -434,4 +966,177 @@ mod test {
                                                                      ":Welcome to the Mozilla IRC Network zonyitoo!zonyitoo@113.93.181.139")));
         assert_eq!(expected, msg);
     }
+
+    #[test]
+    fn test_raw_message_tags() {
+        let data = "@aaa=bbb;+example.com/ccc=d\\se :nick!user@host PRIVMSG #chan :Hello";
+        let raw_message = RawMessage::from_str(data).unwrap();
+
+        assert_eq!(raw_message.tags().get(), "aaa=bbb;+example.com/ccc=d\\se");
+        assert_eq!(raw_message.prefix(), Some("nick!user@host"));
+        assert_eq!(raw_message.command(), "PRIVMSG");
+
+        let tags = raw_message.tags();
+        assert_eq!(tags.value("aaa").as_ref().map(|v| &v[..]), Some("bbb"));
+        assert_eq!(tags.value("+example.com/ccc").as_ref().map(|v| &v[..]), Some("d e"));
+        assert_eq!(tags.client_tags().count(), 1);
+
+        assert_eq!(&raw_message.to_string()[..], data);
+    }
+
+    #[test]
+    fn test_raw_message_tags_without_prefix() {
+        let data = "@time=2011-10-19T16:40:51.620Z PING :irc.example.com";
+        let raw_message = RawMessage::from_str(data).unwrap();
+
+        assert_eq!(raw_message.prefix(), None);
+        assert_eq!(raw_message.command(), "PING");
+        assert!(raw_message.tags().contains("time"));
+
+        assert!(RawMessage::from_str("@ PING").is_err());
+    }
+
+    #[test]
+    fn test_message_tags() {
+        use protocol::command::{Command, PrivmsgCommand};
+
+        let data = "@msgid=abc :abc!abc@example.com PRIVMSG #rust :Hi";
+        let msg = Message::from_str(data).unwrap();
+
+        let expected = Message::with_tags(Some("msgid=abc"), Some("abc!abc@example.com"),
+                                          Body::Command(Command::Privmsg(PrivmsgCommand::new("#rust", "Hi"))));
+        assert_eq!(expected, msg);
+        assert_eq!(msg.tags().value("msgid").as_ref().map(|v| &v[..]), Some("abc"));
+    }
+
+    #[test]
+    fn test_message_into_owned() {
+        let msg = {
+            let data = String::from("@msgid=abc :abc!abc@example.com PRIVMSG #rust :Hi there");
+            Message::from_str(&data).unwrap().into_owned()
+        };
+
+        assert_eq!(msg.prefix(), Some("abc!abc@example.com"));
+        assert_eq!(msg.tags().get(), "msgid=abc");
+
+        match msg.body() {
+            &Body::Command(Command::Privmsg(ref privmsg)) => {
+                assert_eq!(privmsg.target(), "#rust");
+                assert_eq!(privmsg.message(), "Hi there");
+            },
+            body => panic!("Unexpected body {:?}", body),
+        }
+
+        assert_eq!(msg.as_borrowed(), msg);
+    }
+
+    #[test]
+    fn test_owned_raw_message_from_raw() {
+        use protocol::reply::{Reply, ReplyCode};
+
+        let raw = {
+            let data = String::from(":fripp.mozilla.org 001 zonyitoo :Welcome zonyitoo");
+            RawMessage::from_str(&data).unwrap().into_owned()
+        };
+
+        let msg = Message::from_raw(&raw).unwrap();
+        let expected = Message::new(Some("fripp.mozilla.org"),
+                                    Body::Reply(Reply::new(ReplyCode::RPL_WELCOME, "zonyitoo", ":Welcome zonyitoo")));
+        assert_eq!(expected, msg);
+
+        let params: Vec<String> = raw.cow_parameters().map(|p| p.into_owned()).collect();
+        assert_eq!(params, vec!["zonyitoo".to_owned(), "Welcome zonyitoo".to_owned()]);
+    }
+
+    #[test]
+    fn test_raw_message_from_bytes() {
+        let data = b"@time=now :abc!abc@example.com PRIVMSG #rust :Hello";
+        let raw = RawMessage::from_bytes(data, Encoding::Utf8).unwrap();
+        assert_eq!(raw, RawMessage::from_str("@time=now :abc!abc@example.com PRIVMSG #rust :Hello").unwrap());
+
+        let data = b"PRIVMSG #caf\xc3\xa9 :caf\xe9  cr\xe8me";
+        let err = RawMessage::from_bytes(data, Encoding::Utf8).unwrap_err();
+        assert_eq!(err.kind(), ParseMessageErrorKind::InvalidEncoding);
+        assert_eq!(err.detail(), Some("Invalid byte sequence at offset 19"));
+
+        let raw = RawMessage::from_bytes(data, Encoding::Utf8WithFallback(LegacyEncoding::Latin1)).unwrap();
+        assert_eq!(raw.parameters().collect::<Vec<&str>>(), vec!["#café", "café  crème"]);
+
+        let raw = RawMessage::from_bytes(data, Encoding::Legacy(LegacyEncoding::Latin1)).unwrap();
+        assert_eq!(raw.parameters().next(), Some("#cafÃ©"));
+
+        // Parameters which are valid UTF-8 are decoded as Latin-1 too
+        let data = b":caf\xc3\xa9 PRIVMSG #caf\xc3\xa9 :caf\xc3\xa9";
+        let raw = RawMessage::from_bytes(data, Encoding::Legacy(LegacyEncoding::Latin1)).unwrap();
+        assert_eq!(raw.prefix(), Some("cafÃ©"));
+        assert_eq!(raw.parameters().collect::<Vec<&str>>(), vec!["#cafÃ©", "cafÃ©"]);
+    }
+
+    #[test]
+    fn test_message_to_bytes() {
+        let data = b"PRIVMSG #rust :\x93caf\xe9\x94";
+        let encoding = Encoding::Legacy(LegacyEncoding::Windows1252);
+        let msg = Message::from_bytes(data, encoding).unwrap();
+        assert_eq!(&msg.to_bytes(Encoding::Utf8).unwrap()[..],
+                   "PRIVMSG #rust :\u{201C}café\u{201D}".as_bytes());
+
+        let raw = RawMessage::from_bytes(data, encoding).unwrap();
+        assert_eq!(&raw.to_bytes(encoding).unwrap()[..], &data[..]);
+    }
+
+    #[test]
+    fn test_message_source() {
+        let msg = Message::from_str(":abc!abc@example.com PRIVMSG #rust :Hello").unwrap();
+        assert_eq!(msg.nick(), Some("abc"));
+        assert_eq!(msg.source().unwrap().and_then(|p| p.nick_name().and_then(|n| n.host())),
+                   Some("example.com"));
+
+        let msg = Message::from_str(":abc!~abc@user/abc PRIVMSG #rust :Hello").unwrap();
+        assert_eq!(msg.nick(), Some("abc"));
+
+        let msg = Message::from_str(":irc.example.com NOTICE * :Hello").unwrap();
+        assert_eq!(msg.source().unwrap(), Some(Prefix::Server("irc.example.com")));
+        assert_eq!(msg.nick(), None);
+
+        let msg = Message::from_str("PING :irc.example.com").unwrap();
+        assert_eq!(msg.source().unwrap(), None);
+
+        let source = Prefix::parse("abc!abc@example.com").unwrap();
+        let msg = Message::with_source(Some(&source), msg.body().clone());
+        assert_eq!(msg.prefix(), Some("abc!abc@example.com"));
+    }
+
+    #[test]
+    fn test_parse_error_location() {
+        let err = Message::from_str(":abc PRIVMSG #rust").unwrap_err();
+        assert_eq!(err.kind(), ParseMessageErrorKind::NeedMoreParams);
+        assert_eq!(err.command(), Some("PRIVMSG"));
+        assert_eq!(err.param_index(), Some(1));
+        assert_eq!(err.offset(), Some(18));
+        assert_eq!(&err.to_string()[..],
+                   "PRIVMSG requires a message (command PRIVMSG, parameter 1, offset 18)");
+
+        let err = Command::from_str("CONNECT  tolsun.oulu.fi abc").unwrap_err();
+        assert_eq!(err.kind(), ParseMessageErrorKind::InvalidParam);
+        assert_eq!(err.param_index(), Some(1));
+        assert_eq!(err.offset(), Some(24));
+
+        let err = RawMessage::from_str("@ PING").unwrap_err();
+        assert_eq!(err.offset(), Some(0));
+        assert_eq!(err.command(), None);
+        assert_eq!(&err.to_string()[..], "Tags section should not be empty (offset 0)");
+    }
+
+    #[test]
+    fn test_parse_error_to_reply() {
+        let err = Command::from_str("PRIVMSG #rust").unwrap_err();
+        assert_eq!(&err.to_reply("Wiz").unwrap().to_string()[..], "461 Wiz PRIVMSG :Not enough parameters");
+
+        let err = Command::from_str("FOO bar").unwrap_err();
+        assert_eq!(err.kind(), ParseMessageErrorKind::UnrecognizedCommand);
+        assert_eq!(&err.to_reply("*").unwrap().to_string()[..], "421 * FOO :Unknown command");
+
+        let err = Command::from_str("CONNECT tolsun.oulu.fi abc").unwrap_err();
+        assert!(err.to_reply("Wiz").is_none());
+    }
 }
@@ -1,9 +1,37 @@
 
 pub use self::message::{IrcMessage, Message, Body, RawMessage, ParseMessageError, ParseMessageErrorKind};
+pub use self::casemap::{CaseMapping, IrcKey};
+pub use self::channel::{ChannelName, ChannelPrefix};
 pub use self::command::Command;
+pub use self::ctcp::{Ctcp, CtcpKind};
+pub use self::hostmask::HostMask;
+pub use self::isupport::Isupport;
+pub use self::payload::ReplyPayload;
 pub use self::reply::Reply;
+pub use self::replies::Replies;
+pub use self::tag::{MessageTag, MessageTags};
+pub use self::encoding::{Encoding, LegacyEncoding};
+pub use self::name::{NickName, NameLimits, Prefix};
+pub use self::usermode::{UserMode, UserModeSet};
+pub use self::validate::{MessageBuilder, ValidationError};
 
+pub mod casemap;
+pub mod channel;
+pub mod chanmode;
+pub mod codec;
 pub mod command;
+pub mod ctcp;
+pub mod dcc;
+pub mod encoding;
+pub mod format;
+pub mod hostmask;
+pub mod isupport;
 pub mod reply;
+pub mod replies;
+pub mod split;
 pub mod message;
 pub mod name;
+pub mod payload;
+pub mod tag;
+pub mod usermode;
+pub mod validate;
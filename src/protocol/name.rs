@@ -1,7 +1,11 @@
 
 use std::fmt;
 
-use regex::Regex;
+use std::net::IpAddr;
+
+use protocol::casemap::CaseMapping;
+use protocol::channel::RFC2811_CHANTYPES;
+use protocol::isupport::Isupport;
 
 use protocol::message::{ParseMessageError, ParseMessageErrorKind};
 
@@ -21,41 +25,50 @@ impl<'a> NickName<'a> {
         }
     }
 
+    /// Parse a `nick[!user][@host]` name
+    ///
+    /// The nickname length is not limited, servers allow longer nicknames
+    /// than RFC 2812. The host is any text without spaces, to accept cloaks
+    /// such as `user/nick` in the prefixes sent by servers.
     pub fn from_str(s: &'a str) -> Result<NickName<'a>, ParseMessageError>  {
-        let user_regex = Regex::new(r#"(?x)^
-            ([\w-]{1,8})    # Nick
-            (![^\r\n @]+)?  # User
-            (@(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}
-                |(?:[:xdigit:]+(?::[:xdigit:]+){7}|0:0:0:0:0:(?:0|FFFF):\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3})
-                |[:alnum:][:alnum:-]*[:alnum:]*(?:\.[:alnum:][:alnum:-]*[:alnum:]*)*))?$
-        "#).unwrap();
-
-        match user_regex.captures(s) {
-            None => {
-                return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
-                                                  "Invalid name"));
-            },
-            Some(cap) => {
-                let nick: &'a str = cap.at(1).unwrap();
-                let user: Option<&'a str> = cap.at(2).map(|u| &u[1..]);
-                let host: Option<&'a str> = cap.at(3).map(|h| &h[1..]);
+        let (nick_user, host) = match s.find('@') {
+            Some(idx) => (&s[..idx], Some(&s[idx + 1..])),
+            None => (s, None),
+        };
+        let (nick, user) = match nick_user.find('!') {
+            Some(idx) => (&nick_user[..idx], Some(&nick_user[idx + 1..])),
+            None => (nick_user, None),
+        };
 
-                Ok(NickName::new(nick, user, host))
-            }
+        if !is_valid_nickname(nick, usize::MAX) {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam, "Invalid nickname"));
+        }
+        if !user.map(is_valid_user).unwrap_or(true) {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam, "Invalid user"));
+        }
+        if !host.map(is_valid_prefix_host).unwrap_or(true) {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam, "Invalid host"));
         }
+
+        Ok(NickName::new(nick, user, host))
     }
 
-    pub fn nick(&'a self) -> &'a str {
-        &self.nick
+    pub fn nick(&self) -> &'a str {
+        self.nick
     }
 
-    pub fn user(&'a self) -> Option<&'a str> {
+    pub fn user(&self) -> Option<&'a str> {
         self.user
     }
 
-    pub fn host(&'a self) -> Option<&'a str> {
+    pub fn host(&self) -> Option<&'a str> {
         self.host
     }
+
+    /// Whether the nickname is `nick` for the casemapping of the server
+    pub fn is_nick(&self, nick: &str, mapping: CaseMapping) -> bool {
+        mapping.eq_names(self.nick, nick)
+    }
 }
 
 impl<'a> fmt::Display for NickName<'a> {
@@ -64,16 +77,234 @@ impl<'a> fmt::Display for NickName<'a> {
 
         if let Some(user) = self.user.as_ref() {
             try!(write!(f, "!{}", user));
+        }
 
-            if let Some(host) = self.host.as_ref() {
-                try!(write!(f, "@{}", host));
-            }
+        if let Some(host) = self.host.as_ref() {
+            try!(write!(f, "@{}", host));
         }
 
         Ok(())
     }
 }
 
+/// Limits of the names accepted by a server
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NameLimits<'a> {
+    /// Maximum length of a nickname, `NICKLEN`
+    pub nicklen: usize,
+
+    /// Maximum length of a channel name, `CHANNELLEN`
+    pub channellen: usize,
+
+    /// Channel prefixes, `CHANTYPES`
+    pub chantypes: &'a str,
+}
+
+impl NameLimits<'static> {
+    /// Limits of RFC 2812, 9 characters nicknames and 50 characters
+    /// channel names starting with `#`, `&`, `+` or `!`
+    pub fn rfc2812() -> NameLimits<'static> {
+        NameLimits {
+            nicklen: 9,
+            channellen: 50,
+            chantypes: RFC2811_CHANTYPES,
+        }
+    }
+}
+
+impl<'a> NameLimits<'a> {
+    /// Limits advertised by the server
+    pub fn from_isupport(isupport: &'a Isupport) -> NameLimits<'a> {
+        NameLimits {
+            nicklen: isupport.nicklen(),
+            channellen: isupport.channellen(),
+            chantypes: isupport.chantypes(),
+        }
+    }
+
+    pub fn is_valid_nickname(&self, nick: &str) -> bool {
+        is_valid_nickname(nick, self.nicklen)
+    }
+
+    pub fn is_valid_channel(&self, channel: &str) -> bool {
+        is_valid_channel(channel, self.chantypes, self.channellen)
+    }
+}
+
+fn is_special(b: u8) -> bool {
+    matches!(b, b'[' | b']' | b'\\' | b'`' | b'_' | b'^' | b'{' | b'|' | b'}')
+}
+
+/// Check a nickname of at most `max_len` characters
+///
+/// ```plain
+/// nickname =  ( letter / special ) *( letter / digit / special / "-" )
+/// special  =  "[" / "]" / "\" / "`" / "_" / "^" / "{" / "|" / "}"
+/// ```
+pub fn is_valid_nickname(nick: &str, max_len: usize) -> bool {
+    let bytes = nick.as_bytes();
+    if bytes.is_empty() || bytes.len() > max_len {
+        return false;
+    }
+
+    let first = bytes[0];
+    (first.is_ascii_alphabetic() || is_special(first))
+        && bytes[1..].iter().all(|&b| b.is_ascii_alphanumeric() || is_special(b) || b == b'-')
+}
+
+/// Check a user name, anything but `NUL`, `CR`, `LF`, space and `@`
+pub fn is_valid_user(user: &str) -> bool {
+    !user.is_empty() && user.bytes().all(|b| !matches!(b, b'\0' | b'\r' | b'\n' | b' ' | b'@'))
+}
+
+/// Check a host name of at most 253 characters, with labels of at most
+/// 63 characters
+///
+/// ```plain
+/// hostname  =  shortname *( "." shortname )
+/// shortname =  ( letter / digit ) *( letter / digit / "-" ) *( letter / digit )
+/// ```
+pub fn is_valid_hostname(host: &str) -> bool {
+    host.len() <= 253 && host.split('.').all(|label| {
+        let bytes = label.as_bytes();
+        !bytes.is_empty() && bytes.len() <= 63
+            && bytes[0].is_ascii_alphanumeric()
+            && bytes[bytes.len() - 1].is_ascii_alphanumeric()
+            && bytes.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'-')
+    })
+}
+
+/// Check a host, a host name or an IPv4 or IPv6 address
+pub fn is_valid_host(host: &str) -> bool {
+    is_valid_hostname(host) || host.parse::<IpAddr>().is_ok()
+}
+
+/// Check the host of a prefix received from a server, anything but `NUL`,
+/// `CR`, `LF` and space
+///
+/// Servers send cloaks such as `user/nick` or `gateway/web/session` which
+/// are not host names, use `is_valid_host` for the names sent.
+pub fn is_valid_prefix_host(host: &str) -> bool {
+    !host.is_empty() && host.bytes().all(|b| !matches!(b, b'\0' | b'\r' | b'\n' | b' '))
+}
+
+/// Check a server name, which is a host name
+pub fn is_valid_server_name(server: &str) -> bool {
+    is_valid_hostname(server)
+}
+
+/// Check a channel name starting with one of `chantypes`, of at most
+/// `max_len` bytes
+///
+/// ```plain
+/// channel    =  ( "#" / "+" / ( "!" channelid ) / "&" ) chanstring [ ":" chanstring ]
+/// channelid  = 5( %x41-5A / digit )
+/// chanstring = any octet except NUL, BELL, CR, LF, " ", "," and ":"
+/// ```
+///
+/// `!!chanstring`, which creates a safe channel, is accepted too.
+pub fn is_valid_channel(channel: &str, chantypes: &str, max_len: usize) -> bool {
+    let bytes = channel.as_bytes();
+    if bytes.len() < 2 || bytes.len() > max_len || !bytes[0].is_ascii() || !chantypes.as_bytes().contains(&bytes[0]) {
+        return false;
+    }
+
+    let mut name = &bytes[1..];
+    if bytes[0] == b'!' && name.first() == Some(&b'!') {
+        // `!!name` creates a safe channel
+        name = &name[1..];
+    } else if bytes[0] == b'!' {
+        if name.len() < 5 || !name[..5].iter().all(|&b| b.is_ascii_uppercase() || b.is_ascii_digit()) {
+            return false;
+        }
+        name = &name[5..];
+    }
+
+    let mut parts = name.splitn(2, |&b| b == b':');
+    let is_chanstring = |s: &[u8]| {
+        !s.is_empty() && s.iter().all(|&b| !matches!(b, b'\0' | b'\x07' | b'\r' | b'\n' | b' ' | b',' | b':'))
+    };
+
+    match (parts.next(), parts.next()) {
+        (Some(name), None) => is_chanstring(name),
+        (Some(name), Some(mask)) => is_chanstring(name) && is_chanstring(mask),
+        _ => false,
+    }
+}
+
+/// Prefix of a message, the origin of the message
+///
+/// ```plain
+/// prefix = servername / ( nickname [ [ "!" user ] "@" host ] )
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Prefix<'a> {
+    /// Message sent by a server
+    Server(&'a str),
+
+    /// Message sent by a user
+    Nick(NickName<'a>),
+}
+
+impl<'a> Prefix<'a> {
+    /// Parse a prefix, without the leading `:`
+    ///
+    /// The grammar is ambiguous for a bare name, a name containing `.` is
+    /// taken as a server name, anything else as a nickname.
+    pub fn parse(s: &'a str) -> Result<Prefix<'a>, ParseMessageError> {
+        if s.is_empty() {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                              "Prefix should not be empty"));
+        }
+
+        if !s.contains(&['!', '@'][..]) && s.contains('.') {
+            return Ok(Prefix::Server(s));
+        }
+
+        NickName::from_str(s).map(Prefix::Nick)
+    }
+
+    pub fn is_server(&self) -> bool {
+        match *self {
+            Prefix::Server(..) => true,
+            Prefix::Nick(..) => false,
+        }
+    }
+
+    /// Get the server name if the message is sent by a server
+    pub fn server(&self) -> Option<&'a str> {
+        match *self {
+            Prefix::Server(s) => Some(s),
+            Prefix::Nick(..) => None,
+        }
+    }
+
+    /// Get the nickname if the message is sent by a user
+    pub fn nick(&self) -> Option<&'a str> {
+        match *self {
+            Prefix::Server(..) => None,
+            Prefix::Nick(ref n) => Some(n.nick),
+        }
+    }
+
+    /// Get the full name if the message is sent by a user
+    pub fn nick_name(&self) -> Option<&NickName<'a>> {
+        match *self {
+            Prefix::Server(..) => None,
+            Prefix::Nick(ref n) => Some(n),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Prefix<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Prefix::Server(s) => write!(f, "{}", s),
+            Prefix::Nick(ref n) => n.fmt(f),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -137,4 +368,135 @@ mod test {
         assert_eq!(name.user(), None);
         assert_eq!(name.host(), Some("www.mozilla.org"));
     }
+
+    #[test]
+    fn test_name_special_chars() {
+        let data = "[zonyitoo]_away!~zony@example.com";
+        let name = NickName::from_str(data).unwrap();
+
+        assert_eq!(name.nick(), "[zonyitoo]_away");
+        assert_eq!(name.user(), Some("~zony"));
+    }
+
+    #[test]
+    fn test_name_long_and_compressed() {
+        let name = NickName::from_str("a_very_long_nick!~user@::1").unwrap();
+        assert_eq!(name.nick(), "a_very_long_nick");
+        assert_eq!(name.host(), Some("::1"));
+
+        assert_eq!(NickName::from_str("foo[away]@2001:db8::42").unwrap().host(), Some("2001:db8::42"));
+        assert!(NickName::from_str("1nick").is_err());
+        assert!(NickName::from_str("nick!us er").is_err());
+        assert!(NickName::from_str("nick@").is_err());
+
+        // Cloaks and long reverse DNS names sent by servers
+        assert_eq!(NickName::from_str("nick!~u@user/nick").unwrap().host(), Some("user/nick"));
+        let host = format!("{}.example.com", "a".repeat(60));
+        assert_eq!(NickName::from_str(&format!("nick!~u@{}", host)).unwrap().host(), Some(&host[..]));
+        assert!(NickName::from_str("nick@-host.example.com").is_ok());
+    }
+
+    #[test]
+    fn test_validate_nickname() {
+        assert!(is_valid_nickname("WiZ", 9));
+        assert!(is_valid_nickname("[away]`_^{|}-9", 30));
+        assert!(is_valid_nickname("foo[away]", 9));
+        assert!(!is_valid_nickname("a_very_long_nick", 9));
+        assert!(!is_valid_nickname("", 9));
+        assert!(!is_valid_nickname("-WiZ", 9));
+        assert!(!is_valid_nickname("9WiZ", 9));
+        assert!(!is_valid_nickname("Wi Z", 9));
+        assert!(!is_valid_nickname("WiZé", 9));
+
+        let limits = NameLimits { nicklen: 16, ..NameLimits::rfc2812() };
+        assert!(limits.is_valid_nickname("a_very_long_nick"));
+    }
+
+    #[test]
+    fn test_validate_user_host() {
+        assert!(is_valid_user("~jto"));
+        assert!(!is_valid_user("jto@home"));
+        assert!(!is_valid_user(""));
+
+        assert!(is_valid_hostname("tolsun.oulu.fi"));
+        assert!(is_valid_hostname("a-b.c"));
+        assert!(!is_valid_hostname("a-.b"));
+        assert!(!is_valid_hostname("a..b"));
+        assert!(!is_valid_hostname(&"a".repeat(64)));
+        assert!(is_valid_hostname(&format!("{}.example.com", "a".repeat(60))));
+        assert!(!is_valid_hostname(&format!("{}.example.com", "a.".repeat(125))));
+        assert!(!is_valid_hostname("user/nick"));
+
+        assert!(is_valid_prefix_host("user/nick"));
+        assert!(!is_valid_prefix_host("user nick"));
+        assert!(!is_valid_prefix_host(""));
+
+        assert!(is_valid_host("192.168.0.1"));
+        assert!(is_valid_host("::1"));
+        assert!(is_valid_host("::ffff:192.168.0.1"));
+        assert!(!is_valid_host("1::2::3"));
+        assert!(is_valid_server_name("irc.example.com"));
+        assert!(!is_valid_server_name("irc example"));
+    }
+
+    #[test]
+    fn test_validate_channel() {
+        let rfc = NameLimits::rfc2812();
+        assert!(rfc.is_valid_channel("#rust"));
+        assert!(rfc.is_valid_channel("&local"));
+        assert!(rfc.is_valid_channel("+modeless"));
+        assert!(rfc.is_valid_channel("!12345rust"));
+        assert!(rfc.is_valid_channel("#rust:*.fi"));
+        assert!(rfc.is_valid_channel("!!rust"));
+        assert!(!rfc.is_valid_channel("!1234"));
+        assert!(!rfc.is_valid_channel("!abcderust"));
+        assert!(!rfc.is_valid_channel("#"));
+        assert!(!rfc.is_valid_channel("rust"));
+        assert!(!rfc.is_valid_channel("#ru st"));
+        assert!(!rfc.is_valid_channel("#ru,st"));
+        assert!(!rfc.is_valid_channel("#ru\x07st"));
+        assert!(!rfc.is_valid_channel("#rust:"));
+        assert!(!rfc.is_valid_channel("#:x"));
+        assert!(!rfc.is_valid_channel("!!"));
+        assert!(!rfc.is_valid_channel("!12345"));
+        assert!(!rfc.is_valid_channel(&format!("#{}", "a".repeat(50))));
+
+        let mut isupport = Isupport::new();
+        isupport.apply_token("CHANTYPES=#");
+        isupport.apply_token("CHANNELLEN=64");
+        let limits = NameLimits::from_isupport(&isupport);
+        assert!(limits.is_valid_channel(&format!("#{}", "a".repeat(50))));
+        assert!(!limits.is_valid_channel("&local"));
+    }
+
+    #[test]
+    fn test_name_is_nick() {
+        let name = NickName::from_str("[zonyitoo]!~zony@example.com").unwrap();
+        assert!(name.is_nick("{ZonyItoo}", CaseMapping::Rfc1459));
+        assert!(!name.is_nick("{ZonyItoo}", CaseMapping::Ascii));
+        assert!(!name.is_nick("zonyitoo", CaseMapping::Rfc1459));
+    }
+
+    #[test]
+    fn test_prefix() {
+        let prefix = Prefix::parse("irc.mozilla.org").unwrap();
+        assert_eq!(prefix, Prefix::Server("irc.mozilla.org"));
+        assert!(prefix.is_server());
+        assert_eq!(prefix.nick(), None);
+
+        let prefix = Prefix::parse("zonyitoo!zonyitoo@www.mozilla.org").unwrap();
+        assert_eq!(prefix.nick(), Some("zonyitoo"));
+        assert_eq!(prefix.nick_name().and_then(|n| n.host()), Some("www.mozilla.org"));
+
+        assert_eq!(Prefix::parse("zonyitoo").unwrap().nick(), Some("zonyitoo"));
+        assert!(Prefix::parse("").is_err());
+    }
+
+    #[test]
+    fn test_prefix_display() {
+        for data in &["irc.mozilla.org", "zonyitoo", "zonyitoo@www.mozilla.org",
+                      "zonyitoo!zonyitoo", "zonyitoo!zonyitoo@www.mozilla.org"] {
+            assert_eq!(&Prefix::parse(data).unwrap().to_string()[..], *data);
+        }
+    }
 }
@@ -0,0 +1,756 @@
+//! Typed parameters of replies
+//!
+//! ```plain
+//! :irc.example.com 311 WiZ jto ~jto tolsun.oulu.fi * :Jarkko Oikarinen
+//! :irc.example.com 353 WiZ = #rust :@WiZ +jto trillian
+//! ```
+//!
+//! `Reply::payload` parses the parameters after the target for the replies
+//! with a known layout, and the payloads format back to the parameters.
+
+use std::fmt;
+use std::str::FromStr;
+
+use protocol::message::{MessageParamIter, ParseMessageError, ParseMessageErrorKind, LastParam};
+use protocol::reply::{Reply, ReplyCode};
+
+/// Get the next parameter, `index` counts the target as parameter 0
+fn next_param<'a>(params: &mut MessageParamIter<'a>, index: &mut usize, desc: &'static str)
+        -> Result<&'a str, ParseMessageError> {
+    *index += 1;
+    params.next().ok_or_else(|| ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams, desc))
+}
+
+fn parse_number<T: FromStr>(s: &str, index: usize, desc: &'static str) -> Result<T, ParseMessageError> {
+    s.parse().map_err(|_| {
+        ParseMessageError::new(ParseMessageErrorKind::InvalidParam, desc).with_param_index(index)
+    })
+}
+
+/// `RPL_WHOISUSER` and `RPL_WHOWASUSER`
+///
+/// `<nick> <user> <host> * :<real name>`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WhoisUser<'a> {
+    pub nick: &'a str,
+    pub user: &'a str,
+    pub host: &'a str,
+    pub realname: &'a str,
+}
+
+impl<'a> WhoisUser<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<WhoisUser<'a>, ParseMessageError> {
+        let desc = "RPL_WHOISUSER requires a nick, a user, a host and a real name";
+        let mut index = 0;
+        let nick = try!(next_param(&mut params, &mut index, desc));
+        let user = try!(next_param(&mut params, &mut index, desc));
+        let host = try!(next_param(&mut params, &mut index, desc));
+        try!(next_param(&mut params, &mut index, desc));
+        let realname = try!(next_param(&mut params, &mut index, desc));
+
+        Ok(WhoisUser {
+            nick: nick,
+            user: user,
+            host: host,
+            realname: realname,
+        })
+    }
+}
+
+impl<'a> fmt::Display for WhoisUser<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} * {}", self.nick, self.user, self.host, LastParam(self.realname))
+    }
+}
+
+/// `RPL_WHOREPLY`
+///
+/// `<channel> <user> <host> <server> <nick> <flags> :<hopcount> <real name>`
+///
+/// The flags are `H` (here) or `G` (gone), then `*` for operators and the
+/// membership prefixes.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WhoReply<'a> {
+    pub channel: &'a str,
+    pub user: &'a str,
+    pub host: &'a str,
+    pub server: &'a str,
+    pub nick: &'a str,
+    pub flags: &'a str,
+    pub hopcount: u32,
+    pub realname: &'a str,
+}
+
+impl<'a> WhoReply<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<WhoReply<'a>, ParseMessageError> {
+        let desc = "RPL_WHOREPLY requires a channel, a user, a host, a server, a nick, flags and a hopcount";
+        let mut index = 0;
+        let channel = try!(next_param(&mut params, &mut index, desc));
+        let user = try!(next_param(&mut params, &mut index, desc));
+        let host = try!(next_param(&mut params, &mut index, desc));
+        let server = try!(next_param(&mut params, &mut index, desc));
+        let nick = try!(next_param(&mut params, &mut index, desc));
+        let flags = try!(next_param(&mut params, &mut index, desc));
+        let last = try!(next_param(&mut params, &mut index, desc));
+
+        let (hopcount, realname) = match last.find(' ') {
+            Some(idx) => (&last[..idx], &last[idx + 1..]),
+            None => (last, ""),
+        };
+
+        Ok(WhoReply {
+            channel: channel,
+            user: user,
+            host: host,
+            server: server,
+            nick: nick,
+            flags: flags,
+            hopcount: try!(parse_number(hopcount, index, "RPL_WHOREPLY hopcount should be a number")),
+            realname: realname,
+        })
+    }
+
+    /// Whether the user is marked as away
+    pub fn is_away(&self) -> bool {
+        self.flags.starts_with('G')
+    }
+
+    /// Whether the user is an IRC operator
+    pub fn is_operator(&self) -> bool {
+        self.flags.contains('*')
+    }
+}
+
+impl<'a> fmt::Display for WhoReply<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{} {} {} {} {} {} :{}", self.channel, self.user, self.host, self.server,
+                    self.nick, self.flags, self.hopcount));
+        if !self.realname.is_empty() {
+            try!(write!(f, " {}", self.realname));
+        }
+        Ok(())
+    }
+}
+
+/// `RPL_NAMREPLY`
+///
+/// `( "=" / "*" / "@" ) <channel> :[ "@" / "+" ] <nick> *( " " [ "@" / "+" ] <nick> )`
+///
+/// The symbol is `@` for secret channels, `*` for private channels and
+/// `=` for the others.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NamReply<'a> {
+    pub symbol: char,
+    pub channel: &'a str,
+    pub members: &'a str,
+}
+
+impl<'a> NamReply<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<NamReply<'a>, ParseMessageError> {
+        let desc = "RPL_NAMREPLY requires a symbol, a channel and members";
+        let mut index = 0;
+        let symbol = try!(next_param(&mut params, &mut index, desc));
+        let symbol = match symbol {
+            "=" | "*" | "@" => symbol.chars().next().unwrap(),
+            _ => {
+                return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                                  "RPL_NAMREPLY symbol should be =, * or @")
+                           .with_param_index(index));
+            },
+        };
+        let channel = try!(next_param(&mut params, &mut index, desc));
+        let members = params.next().unwrap_or("");
+
+        Ok(NamReply {
+            symbol: symbol,
+            channel: channel,
+            members: members,
+        })
+    }
+
+    /// Members split into their prefixes and nickname, `prefix_chars` are
+    /// the prefixes of `PREFIX` such as `@+`
+    pub fn members(&self, prefix_chars: &'a str) -> NamReplyMemberIter<'a> {
+        NamReplyMemberIter {
+            members: self.members.split(' '),
+            prefix_chars: prefix_chars,
+        }
+    }
+}
+
+impl<'a> fmt::Display for NamReply<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} :{}", self.symbol, self.channel, self.members)
+    }
+}
+
+/// Iterator of the prefixes and nicknames of a `NamReply`
+pub struct NamReplyMemberIter<'a> {
+    members: ::std::str::Split<'a, char>,
+    prefix_chars: &'a str,
+}
+
+impl<'a> Iterator for NamReplyMemberIter<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<(&'a str, &'a str)> {
+        loop {
+            match self.members.next() {
+                None => return None,
+                Some("") => {},
+                Some(member) => {
+                    let prefix_chars = self.prefix_chars;
+                    let idx = member.find(|c| !prefix_chars.contains(c)).unwrap_or(member.len());
+                    return Some((&member[..idx], &member[idx..]));
+                },
+            }
+        }
+    }
+}
+
+/// `RPL_TOPIC`
+///
+/// `<channel> :<topic>`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Topic<'a> {
+    pub channel: &'a str,
+    pub topic: &'a str,
+}
+
+impl<'a> Topic<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<Topic<'a>, ParseMessageError> {
+        let desc = "RPL_TOPIC requires a channel and a topic";
+        let mut index = 0;
+
+        Ok(Topic {
+            channel: try!(next_param(&mut params, &mut index, desc)),
+            topic: try!(next_param(&mut params, &mut index, desc)),
+        })
+    }
+}
+
+impl<'a> fmt::Display for Topic<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.channel, LastParam(self.topic))
+    }
+}
+
+/// `RPL_LIST`
+///
+/// `<channel> <# visible> :<topic>`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ListEntry<'a> {
+    pub channel: &'a str,
+    pub visible: u32,
+    pub topic: &'a str,
+}
+
+impl<'a> ListEntry<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<ListEntry<'a>, ParseMessageError> {
+        let desc = "RPL_LIST requires a channel, a number of users and a topic";
+        let mut index = 0;
+        let channel = try!(next_param(&mut params, &mut index, desc));
+        let visible = try!(next_param(&mut params, &mut index, desc));
+        let visible = try!(parse_number(visible, index, "RPL_LIST number of users should be a number"));
+
+        Ok(ListEntry {
+            channel: channel,
+            visible: visible,
+            topic: params.next().unwrap_or(""),
+        })
+    }
+}
+
+impl<'a> fmt::Display for ListEntry<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.channel, self.visible, LastParam(self.topic))
+    }
+}
+
+/// `RPL_BANLIST`, `RPL_EXCEPTLIST` and `RPL_INVITELIST`
+///
+/// `<channel> <mask> [<setter> <time>]`
+///
+/// The setter and the time it was set are sent by most servers but are not
+/// in RFC 2812.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BanListEntry<'a> {
+    pub channel: &'a str,
+    pub mask: &'a str,
+    pub set_by: Option<&'a str>,
+    pub set_at: Option<u64>,
+}
+
+impl<'a> BanListEntry<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<BanListEntry<'a>, ParseMessageError> {
+        let desc = "RPL_BANLIST requires a channel and a mask";
+        let mut index = 0;
+        let channel = try!(next_param(&mut params, &mut index, desc));
+        let mask = try!(next_param(&mut params, &mut index, desc));
+        let set_by = params.next();
+        let set_at = match params.next() {
+            None => None,
+            Some(t) => Some(try!(parse_number(t, index + 2, "Ban list time should be a number"))),
+        };
+
+        Ok(BanListEntry {
+            channel: channel,
+            mask: mask,
+            set_by: set_by,
+            set_at: set_at,
+        })
+    }
+}
+
+impl<'a> fmt::Display for BanListEntry<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{} {}", self.channel, self.mask));
+
+        if let Some(set_by) = self.set_by {
+            try!(write!(f, " {}", set_by));
+            if let Some(set_at) = self.set_at {
+                try!(write!(f, " {}", set_at));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `RPL_TRACELINK`
+///
+/// `Link <version & debug level> <destination> <next server> V<protocol version>
+/// <link uptime in seconds> <backstream sendq> <upstream sendq>`
+///
+/// Older servers stop after the next server.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TraceLink<'a> {
+    pub version: &'a str,
+    pub destination: &'a str,
+    pub next_server: &'a str,
+    pub protocol_version: Option<&'a str>,
+    pub uptime: Option<u64>,
+    pub backstream_sendq: Option<u64>,
+    pub upstream_sendq: Option<u64>,
+}
+
+impl<'a> TraceLink<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<TraceLink<'a>, ParseMessageError> {
+        let desc = "RPL_TRACELINK requires a version, a destination and a next server";
+        let mut index = 0;
+        if try!(next_param(&mut params, &mut index, desc)) != "Link" {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                              "RPL_TRACELINK should start with Link").with_param_index(index));
+        }
+        let version = try!(next_param(&mut params, &mut index, desc));
+        let destination = try!(next_param(&mut params, &mut index, desc));
+        let next_server = try!(next_param(&mut params, &mut index, desc));
+        let protocol_version = params.next().map(|v| v.strip_prefix('V').unwrap_or(v));
+
+        let mut numbers = [None; 3];
+        for (offset, number) in numbers.iter_mut().enumerate() {
+            if let Some(n) = params.next() {
+                *number = Some(try!(parse_number(n, index + 2 + offset, "RPL_TRACELINK values should be numbers")));
+            }
+        }
+
+        Ok(TraceLink {
+            version: version,
+            destination: destination,
+            next_server: next_server,
+            protocol_version: protocol_version,
+            uptime: numbers[0],
+            backstream_sendq: numbers[1],
+            upstream_sendq: numbers[2],
+        })
+    }
+}
+
+impl<'a> fmt::Display for TraceLink<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "Link {} {} {}", self.version, self.destination, self.next_server));
+
+        if let Some(protocol_version) = self.protocol_version {
+            try!(write!(f, " V{}", protocol_version));
+            for number in &[self.uptime, self.backstream_sendq, self.upstream_sendq] {
+                match *number {
+                    Some(n) => try!(write!(f, " {}", n)),
+                    None => break,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `RPL_TRACESERVER`
+///
+/// `Serv <class> <int>S <int>C <server> <nick!user|*!*>@<host|server> V<protocol version>`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TraceServer<'a> {
+    pub class: &'a str,
+    pub servers: u32,
+    pub clients: u32,
+    pub server: &'a str,
+    pub connected_by: &'a str,
+    pub protocol_version: &'a str,
+}
+
+impl<'a> TraceServer<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<TraceServer<'a>, ParseMessageError> {
+        let desc = "RPL_TRACESERVER requires a class, numbers of servers and clients, a server, \
+                    a connection and a protocol version";
+        let mut index = 0;
+        if try!(next_param(&mut params, &mut index, desc)) != "Serv" {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                              "RPL_TRACESERVER should start with Serv").with_param_index(index));
+        }
+        let class = try!(next_param(&mut params, &mut index, desc));
+        let servers = try!(next_param(&mut params, &mut index, desc));
+        let servers = try!(parse_number(servers.strip_suffix('S').unwrap_or(servers), index,
+                                        "RPL_TRACESERVER number of servers should be a number"));
+        let clients = try!(next_param(&mut params, &mut index, desc));
+        let clients = try!(parse_number(clients.strip_suffix('C').unwrap_or(clients), index,
+                                        "RPL_TRACESERVER number of clients should be a number"));
+        let server = try!(next_param(&mut params, &mut index, desc));
+        let connected_by = try!(next_param(&mut params, &mut index, desc));
+        let protocol_version = try!(next_param(&mut params, &mut index, desc));
+
+        Ok(TraceServer {
+            class: class,
+            servers: servers,
+            clients: clients,
+            server: server,
+            connected_by: connected_by,
+            protocol_version: protocol_version.strip_prefix('V').unwrap_or(protocol_version),
+        })
+    }
+}
+
+impl<'a> fmt::Display for TraceServer<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Serv {} {}S {}C {} {} V{}", self.class, self.servers, self.clients, self.server,
+               self.connected_by, self.protocol_version)
+    }
+}
+
+/// `RPL_STATSLINKINFO`
+///
+/// `<linkname> <sendq> <sent messages> <sent Kbytes> <received messages>
+/// <received Kbytes> <time open>`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StatsLinkInfo<'a> {
+    pub link_name: &'a str,
+    pub sendq: u64,
+    pub sent_messages: u64,
+    pub sent_kbytes: u64,
+    pub received_messages: u64,
+    pub received_kbytes: u64,
+    pub time_open: u64,
+}
+
+impl<'a> StatsLinkInfo<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<StatsLinkInfo<'a>, ParseMessageError> {
+        let desc = "RPL_STATSLINKINFO requires a link name and 6 numbers";
+        let mut index = 0;
+        let link_name = try!(next_param(&mut params, &mut index, desc));
+
+        let mut numbers = [0u64; 6];
+        for number in numbers.iter_mut() {
+            let n = try!(next_param(&mut params, &mut index, desc));
+            *number = try!(parse_number(n, index, "RPL_STATSLINKINFO values should be numbers"));
+        }
+
+        Ok(StatsLinkInfo {
+            link_name: link_name,
+            sendq: numbers[0],
+            sent_messages: numbers[1],
+            sent_kbytes: numbers[2],
+            received_messages: numbers[3],
+            received_kbytes: numbers[4],
+            time_open: numbers[5],
+        })
+    }
+}
+
+impl<'a> fmt::Display for StatsLinkInfo<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} {} {} {} {}", self.link_name, self.sendq, self.sent_messages, self.sent_kbytes,
+               self.received_messages, self.received_kbytes, self.time_open)
+    }
+}
+
+/// `RPL_SERVLIST`
+///
+/// `<name> <server> <mask> <type> <hopcount> <info>`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ServList<'a> {
+    pub name: &'a str,
+    pub server: &'a str,
+    pub mask: &'a str,
+    pub service_type: &'a str,
+    pub hopcount: u32,
+    pub info: &'a str,
+}
+
+impl<'a> ServList<'a> {
+    fn parse(mut params: MessageParamIter<'a>) -> Result<ServList<'a>, ParseMessageError> {
+        let desc = "RPL_SERVLIST requires a name, a server, a mask, a type, a hopcount and an info";
+        let mut index = 0;
+        let name = try!(next_param(&mut params, &mut index, desc));
+        let server = try!(next_param(&mut params, &mut index, desc));
+        let mask = try!(next_param(&mut params, &mut index, desc));
+        let service_type = try!(next_param(&mut params, &mut index, desc));
+        let hopcount = try!(next_param(&mut params, &mut index, desc));
+        let hopcount = try!(parse_number(hopcount, index, "RPL_SERVLIST hopcount should be a number"));
+
+        Ok(ServList {
+            name: name,
+            server: server,
+            mask: mask,
+            service_type: service_type,
+            hopcount: hopcount,
+            info: try!(next_param(&mut params, &mut index, desc)),
+        })
+    }
+}
+
+impl<'a> fmt::Display for ServList<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} {} {} {}", self.name, self.server, self.mask, self.service_type, self.hopcount,
+               LastParam(self.info))
+    }
+}
+
+/// Typed parameters of a reply
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ReplyPayload<'a> {
+    WhoisUser(WhoisUser<'a>),
+    WhowasUser(WhoisUser<'a>),
+    WhoReply(WhoReply<'a>),
+    NamReply(NamReply<'a>),
+    Topic(Topic<'a>),
+    ListEntry(ListEntry<'a>),
+    BanList(BanListEntry<'a>),
+    ExceptList(BanListEntry<'a>),
+    InviteList(BanListEntry<'a>),
+    TraceLink(TraceLink<'a>),
+    TraceServer(TraceServer<'a>),
+    StatsLinkInfo(StatsLinkInfo<'a>),
+    ServList(ServList<'a>),
+}
+
+impl<'a> ReplyPayload<'a> {
+    /// Parse the parameters of a reply, `None` if the reply has no typed
+    /// payload
+    pub fn from_reply(reply: &'a Reply) -> Option<Result<ReplyPayload<'a>, ParseMessageError>> {
+        let params = reply.parameters();
+
+        Some(match reply.code() {
+            ReplyCode::RPL_WHOISUSER => WhoisUser::parse(params).map(ReplyPayload::WhoisUser),
+            ReplyCode::RPL_WHOWASUSER => WhoisUser::parse(params).map(ReplyPayload::WhowasUser),
+            ReplyCode::RPL_WHOREPLY => WhoReply::parse(params).map(ReplyPayload::WhoReply),
+            ReplyCode::RPL_NAMREPLY => NamReply::parse(params).map(ReplyPayload::NamReply),
+            ReplyCode::RPL_TOPIC => Topic::parse(params).map(ReplyPayload::Topic),
+            ReplyCode::RPL_LIST => ListEntry::parse(params).map(ReplyPayload::ListEntry),
+            ReplyCode::RPL_BANLIST => BanListEntry::parse(params).map(ReplyPayload::BanList),
+            ReplyCode::RPL_EXCEPTLIST => BanListEntry::parse(params).map(ReplyPayload::ExceptList),
+            ReplyCode::RPL_INVITELIST => BanListEntry::parse(params).map(ReplyPayload::InviteList),
+            ReplyCode::RPL_TRACELINK => TraceLink::parse(params).map(ReplyPayload::TraceLink),
+            ReplyCode::RPL_TRACESERVER => TraceServer::parse(params).map(ReplyPayload::TraceServer),
+            ReplyCode::RPL_STATSLINKINFO => StatsLinkInfo::parse(params).map(ReplyPayload::StatsLinkInfo),
+            ReplyCode::RPL_SERVLIST => ServList::parse(params).map(ReplyPayload::ServList),
+            _ => return None,
+        })
+    }
+
+    pub fn code(&self) -> ReplyCode {
+        match *self {
+            ReplyPayload::WhoisUser(..) => ReplyCode::RPL_WHOISUSER,
+            ReplyPayload::WhowasUser(..) => ReplyCode::RPL_WHOWASUSER,
+            ReplyPayload::WhoReply(..) => ReplyCode::RPL_WHOREPLY,
+            ReplyPayload::NamReply(..) => ReplyCode::RPL_NAMREPLY,
+            ReplyPayload::Topic(..) => ReplyCode::RPL_TOPIC,
+            ReplyPayload::ListEntry(..) => ReplyCode::RPL_LIST,
+            ReplyPayload::BanList(..) => ReplyCode::RPL_BANLIST,
+            ReplyPayload::ExceptList(..) => ReplyCode::RPL_EXCEPTLIST,
+            ReplyPayload::InviteList(..) => ReplyCode::RPL_INVITELIST,
+            ReplyPayload::TraceLink(..) => ReplyCode::RPL_TRACELINK,
+            ReplyPayload::TraceServer(..) => ReplyCode::RPL_TRACESERVER,
+            ReplyPayload::StatsLinkInfo(..) => ReplyCode::RPL_STATSLINKINFO,
+            ReplyPayload::ServList(..) => ReplyCode::RPL_SERVLIST,
+        }
+    }
+
+    /// Build the reply sent to `target`
+    pub fn to_reply(&self, target: &str) -> Reply<'static> {
+        let params = self.to_string();
+        Reply::new(self.code(), target, &params).into_owned()
+    }
+}
+
+impl<'a> fmt::Display for ReplyPayload<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReplyPayload::WhoisUser(ref p) | ReplyPayload::WhowasUser(ref p) => p.fmt(f),
+            ReplyPayload::WhoReply(ref p) => p.fmt(f),
+            ReplyPayload::NamReply(ref p) => p.fmt(f),
+            ReplyPayload::Topic(ref p) => p.fmt(f),
+            ReplyPayload::ListEntry(ref p) => p.fmt(f),
+            ReplyPayload::BanList(ref p) | ReplyPayload::ExceptList(ref p) | ReplyPayload::InviteList(ref p) => {
+                p.fmt(f)
+            },
+            ReplyPayload::TraceLink(ref p) => p.fmt(f),
+            ReplyPayload::TraceServer(ref p) => p.fmt(f),
+            ReplyPayload::StatsLinkInfo(ref p) => p.fmt(f),
+            ReplyPayload::ServList(ref p) => p.fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::IrcMessage;
+    use protocol::reply::Reply;
+
+    fn roundtrip(line: &str) {
+        let reply = Reply::from_str(line).unwrap();
+        let payload = reply.payload().unwrap().unwrap();
+        assert_eq!(&payload.to_reply(reply.target()).to_string()[..], line);
+    }
+
+    #[test]
+    fn test_payload_whois() {
+        let reply = Reply::from_str("311 WiZ jto ~jto tolsun.oulu.fi * :Jarkko Oikarinen").unwrap();
+        match reply.payload().unwrap().unwrap() {
+            ReplyPayload::WhoisUser(whois) => {
+                assert_eq!(whois, WhoisUser {
+                    nick: "jto",
+                    user: "~jto",
+                    host: "tolsun.oulu.fi",
+                    realname: "Jarkko Oikarinen",
+                });
+            },
+            other => panic!("Unexpected payload {:?}", other),
+        }
+
+        let reply = Reply::from_str("311 WiZ jto ~jto").unwrap();
+        assert_eq!(reply.payload().unwrap().unwrap_err().kind(), ParseMessageErrorKind::NeedMoreParams);
+        assert!(Reply::from_str("001 WiZ :Welcome").unwrap().payload().is_none());
+    }
+
+    #[test]
+    fn test_payload_who() {
+        let reply = Reply::from_str("352 WiZ #rust ~jto tolsun.oulu.fi irc.example.com jto G*@ :2 Jarkko O").unwrap();
+        match reply.payload().unwrap().unwrap() {
+            ReplyPayload::WhoReply(who) => {
+                assert_eq!(who.nick, "jto");
+                assert_eq!(who.hopcount, 2);
+                assert_eq!(who.realname, "Jarkko O");
+                assert!(who.is_away());
+                assert!(who.is_operator());
+            },
+            other => panic!("Unexpected payload {:?}", other),
+        }
+
+        let reply = Reply::from_str("352 WiZ #rust ~jto host server jto H :x Jarkko").unwrap();
+        assert_eq!(reply.payload().unwrap().unwrap_err().param_index(), Some(7));
+    }
+
+    #[test]
+    fn test_payload_names() {
+        let reply = Reply::from_str("353 WiZ @ #rust :@WiZ +jto @+trillian").unwrap();
+        match reply.payload().unwrap().unwrap() {
+            ReplyPayload::NamReply(names) => {
+                assert_eq!(names.symbol, '@');
+                assert_eq!(names.channel, "#rust");
+                assert_eq!(names.members("@+").collect::<Vec<_>>(),
+                           vec![("@", "WiZ"), ("+", "jto"), ("@+", "trillian")]);
+            },
+            other => panic!("Unexpected payload {:?}", other),
+        }
+
+        assert!(Reply::from_str("353 WiZ ! #rust :WiZ").unwrap().payload().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_payload_lists() {
+        let reply = Reply::from_str("367 WiZ #rust *!*@*.fi jto 1792234861").unwrap();
+        match reply.payload().unwrap().unwrap() {
+            ReplyPayload::BanList(ban) => {
+                assert_eq!(ban.mask, "*!*@*.fi");
+                assert_eq!(ban.set_by, Some("jto"));
+                assert_eq!(ban.set_at, Some(1792234861));
+            },
+            other => panic!("Unexpected payload {:?}", other),
+        }
+
+        match Reply::from_str("348 WiZ #rust *!*@*.fi").unwrap().payload().unwrap().unwrap() {
+            ReplyPayload::ExceptList(except) => assert_eq!(except.set_by, None),
+            other => panic!("Unexpected payload {:?}", other),
+        }
+
+        match Reply::from_str("322 WiZ #rust 42 :Rust programming").unwrap().payload().unwrap().unwrap() {
+            ReplyPayload::ListEntry(entry) => assert_eq!(entry.visible, 42),
+            other => panic!("Unexpected payload {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_payload_server() {
+        match Reply::from_str("200 WiZ Link 2.10 irc.example.com hub.example.com V0210 3600 12 0")
+                .unwrap().payload().unwrap().unwrap() {
+            ReplyPayload::TraceLink(link) => {
+                assert_eq!(link.next_server, "hub.example.com");
+                assert_eq!(link.protocol_version, Some("0210"));
+                assert_eq!(link.uptime, Some(3600));
+            },
+            other => panic!("Unexpected payload {:?}", other),
+        }
+
+        match Reply::from_str("211 WiZ hub.example.com 0 1024 512 2048 1024 3600").unwrap().payload().unwrap().unwrap() {
+            ReplyPayload::StatsLinkInfo(stats) => {
+                assert_eq!(stats.sent_messages, 1024);
+                assert_eq!(stats.time_open, 3600);
+            },
+            other => panic!("Unexpected payload {:?}", other),
+        }
+
+        match Reply::from_str("206 WiZ Serv 10 2S 42C hub.example.com *!*@irc.example.com V0210")
+                .unwrap().payload().unwrap().unwrap() {
+            ReplyPayload::TraceServer(serv) => {
+                assert_eq!(serv.servers, 2);
+                assert_eq!(serv.clients, 42);
+                assert_eq!(serv.protocol_version, "0210");
+            },
+            other => panic!("Unexpected payload {:?}", other),
+        }
+        let reply = Reply::from_str("206 WiZ Serv 10 xS 42C hub.example.com *!*@irc.example.com V0210").unwrap();
+        assert_eq!(reply.payload().unwrap().unwrap_err().param_index(), Some(3));
+    }
+
+    #[test]
+    fn test_payload_roundtrip() {
+        for line in &[
+            "311 WiZ jto ~jto tolsun.oulu.fi * :Jarkko Oikarinen",
+            "314 WiZ jto ~jto tolsun.oulu.fi * Jarkko",
+            "352 WiZ #rust ~jto tolsun.oulu.fi irc.example.com jto H :0 Jarkko Oikarinen",
+            "352 WiZ #rust ~jto tolsun.oulu.fi irc.example.com jto H :0",
+            "353 WiZ = #rust :@WiZ +jto trillian",
+            "332 WiZ #rust :Rust programming",
+            "322 WiZ #rust 42 :Rust programming",
+            "367 WiZ #rust *!*@*.fi jto 1792234861",
+            "346 WiZ #rust *!*@*.fi",
+            "200 WiZ Link 2.10 irc.example.com hub.example.com V0210 3600 12 0",
+            "200 WiZ Link 2.10 irc.example.com hub.example.com",
+            "200 WiZ Link 2.10 irc.example.com hub.example.com VV0210",
+            "211 WiZ hub.example.com 0 1024 512 2048 1024 3600",
+            "206 WiZ Serv 10 2S 42C hub.example.com *!*@irc.example.com V0210",
+            "206 WiZ Serv 10 2S 42C hub.example.com *!*@irc.example.com VV0210",
+            "234 WiZ NickServ irc.example.com * 0 1 :Nickname services",
+        ] {
+            roundtrip(line);
+        }
+    }
+}
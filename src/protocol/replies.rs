@@ -0,0 +1,1209 @@
+//! Constructors of the replies sent by servers
+//!
+//! Every reply is built with the parameters of RFC 2812 and its human
+//! readable text, which may be replaced for localization.
+//!
+//! ```
+//! use irc::protocol::reply::ReplyCode;
+//! use irc::protocol::replies::Replies;
+//!
+//! let replies = Replies::new();
+//! assert_eq!(&replies.no_such_nick("WiZ", "nobody").to_string()[..],
+//!            "401 WiZ nobody :No such nick/channel");
+//!
+//! let replies = Replies::new().with_text(ReplyCode::ERR_NOSUCHNICK, "Pseudo ou canal inconnu");
+//! assert_eq!(&replies.no_such_nick("WiZ", "nobody").to_string()[..],
+//!            "401 WiZ nobody :Pseudo ou canal inconnu");
+//! ```
+//!
+//! Texts may refer to the arguments of the constructor documented with
+//! them as `{0}`, `{1}` and so on.
+
+use protocol::message::LastParam;
+use protocol::payload::{ServList, StatsLinkInfo, TraceLink, TraceServer, WhoReply};
+use protocol::reply::{Reply, ReplyCode};
+use protocol::usermode::UserModeSet;
+
+/// Text of a reply in RFC 2812, `None` for the replies without text
+pub fn default_text(code: ReplyCode) -> Option<&'static str> {
+    let text = match code {
+        ReplyCode::RPL_WELCOME => "Welcome to the Internet Relay Network {0}",
+        ReplyCode::RPL_YOURHOST => "Your host is {0}, running version {1}",
+        ReplyCode::RPL_CREATED => "This server was created {0}",
+        ReplyCode::RPL_ISUPPORT => "are supported by this server",
+        ReplyCode::RPL_BOUNCE => "Try server {0}, port {1}",
+        ReplyCode::RPL_UNAWAY => "You are no longer marked as being away",
+        ReplyCode::RPL_NOWAWAY => "You have been marked as being away",
+        ReplyCode::RPL_WHOISOPERATOR => "is an IRC operator",
+        ReplyCode::RPL_WHOISIDLE => "seconds idle",
+        ReplyCode::RPL_ENDOFWHOIS => "End of WHOIS list",
+        ReplyCode::RPL_ENDOFWHOWAS => "End of WHOWAS",
+        ReplyCode::RPL_LISTSTART => "Users  Name",
+        ReplyCode::RPL_LISTEND => "End of LIST",
+        ReplyCode::RPL_NOTOPIC => "No topic is set",
+        ReplyCode::RPL_SUMMONING => "Summoning user to IRC",
+        ReplyCode::RPL_ENDOFINVITELIST => "End of channel invite list",
+        ReplyCode::RPL_ENDOFEXCEPTLIST => "End of channel exception list",
+        ReplyCode::RPL_ENDOFWHO => "End of WHO list",
+        ReplyCode::RPL_ENDOFNAMES => "End of NAMES list",
+        ReplyCode::RPL_ENDOFLINKS => "End of LINKS list",
+        ReplyCode::RPL_ENDOFBANLIST => "End of channel ban list",
+        ReplyCode::RPL_ENDOFINFO => "End of INFO list",
+        ReplyCode::RPL_MOTDSTART => "- {0} Message of the day - ",
+        ReplyCode::RPL_ENDOFMOTD => "End of MOTD command",
+        ReplyCode::RPL_YOUREOPER => "You are now an IRC operator",
+        ReplyCode::RPL_REHASHING => "Rehashing",
+        ReplyCode::RPL_YOURESERVICE => "You are service {0}",
+        ReplyCode::RPL_USERSSTART => "UserID   Terminal  Host",
+        ReplyCode::RPL_ENDOFUSERS => "End of users",
+        ReplyCode::RPL_NOUSERS => "Nobody logged in",
+        ReplyCode::RPL_TRACEEND => "End of TRACE",
+        ReplyCode::RPL_ENDOFSTATS => "End of STATS report",
+        ReplyCode::RPL_STATSUPTIME => "Server Up {0} days {1}:{2}:{3}",
+        ReplyCode::RPL_SERVLISTEND => "End of service listing",
+        ReplyCode::RPL_LUSERCLIENT => "There are {0} users and {1} services on {2} servers",
+        ReplyCode::RPL_LUSEROP => "operator(s) online",
+        ReplyCode::RPL_LUSERUNKNOWN => "unknown connection(s)",
+        ReplyCode::RPL_LUSERCHANNELS => "channels formed",
+        ReplyCode::RPL_LUSERME => "I have {0} clients and {1} servers",
+        ReplyCode::RPL_ADMINME => "Administrative info",
+        ReplyCode::RPL_TRYAGAIN => "Please wait a while and try again.",
+        ReplyCode::ERR_NOSUCHNICK => "No such nick/channel",
+        ReplyCode::ERR_NOSUCHSERVER => "No such server",
+        ReplyCode::ERR_NOSUCHCHANNEL => "No such channel",
+        ReplyCode::ERR_CANNOTSENDTOCHAN => "Cannot send to channel",
+        ReplyCode::ERR_TOOMANYCHANNELS => "You have joined too many channels",
+        ReplyCode::ERR_WASNOSUCHNICK => "There was no such nickname",
+        ReplyCode::ERR_TOOMANYTARGETS => "{0} recipients. {1}",
+        ReplyCode::ERR_NOSUCHSERVICE => "No such service",
+        ReplyCode::ERR_NOORIGIN => "No origin specified",
+        ReplyCode::ERR_NORECIPIENT => "No recipient given ({0})",
+        ReplyCode::ERR_NOTEXTTOSEND => "No text to send",
+        ReplyCode::ERR_NOTOPLEVEL => "No toplevel domain specified",
+        ReplyCode::ERR_WILDTOPLEVEL => "Wildcard in toplevel domain",
+        ReplyCode::ERR_BADMASK => "Bad Server/host mask",
+        ReplyCode::ERR_UNKNOWNCOMMAND => "Unknown command",
+        ReplyCode::ERR_NOMOTD => "MOTD File is missing",
+        ReplyCode::ERR_NOADMININFO => "No administrative info available",
+        ReplyCode::ERR_FILEERROR => "File error doing {0} on {1}",
+        ReplyCode::ERR_NONICKNAMEGIVEN => "No nickname given",
+        ReplyCode::ERR_ERRONEUSNICKNAME => "Erroneous nickname",
+        ReplyCode::ERR_NICKNAMEINUSE => "Nickname is already in use",
+        ReplyCode::ERR_NICKCOLLISION => "Nickname collision KILL from {0}@{1}",
+        ReplyCode::ERR_UNAVAILRESOURCE => "Nick/channel is temporarily unavailable",
+        ReplyCode::ERR_USERNOTINCHANNEL => "They aren't on that channel",
+        ReplyCode::ERR_NOTONCHANNEL => "You're not on that channel",
+        ReplyCode::ERR_USERONCHANNEL => "is already on channel",
+        ReplyCode::ERR_NOLOGIN => "User not logged in",
+        ReplyCode::ERR_SUMMONDISABLED => "SUMMON has been disabled",
+        ReplyCode::ERR_USERSDISABLED => "USERS has been disabled",
+        ReplyCode::ERR_NOTREGISTERED => "You have not registered",
+        ReplyCode::ERR_NEEDMOREPARAMS => "Not enough parameters",
+        ReplyCode::ERR_ALREADYREGISTRED => "Unauthorized command (already registered)",
+        ReplyCode::ERR_NOPERMFORHOST => "Your host isn't among the privileged",
+        ReplyCode::ERR_PASSWDMISMATCH => "Password incorrect",
+        ReplyCode::ERR_YOUREBANNEDCREEP => "You are banned from this server",
+        ReplyCode::ERR_YOUWILLBEBANNED => "You will be banned from this server",
+        ReplyCode::ERR_KEYSET => "Channel key already set",
+        ReplyCode::ERR_CHANNELISFULL => "Cannot join channel (+l)",
+        ReplyCode::ERR_UNKNOWNMODE => "is unknown mode char to me for {0}",
+        ReplyCode::ERR_INVITEONLYCHAN => "Cannot join channel (+i)",
+        ReplyCode::ERR_BANNEDFROMCHAN => "Cannot join channel (+b)",
+        ReplyCode::ERR_BADCHANNELKEY => "Cannot join channel (+k)",
+        ReplyCode::ERR_BADCHANMASK => "Bad Channel Mask",
+        ReplyCode::ERR_NOCHANMODES => "Channel doesn't support modes",
+        ReplyCode::ERR_BANLISTFULL => "Channel list is full",
+        ReplyCode::ERR_NOPRIVILEGES => "Permission Denied- You're not an IRC operator",
+        ReplyCode::ERR_CHANOPRIVSNEEDED => "You're not channel operator",
+        ReplyCode::ERR_CANTKILLSERVER => "You can't kill a server!",
+        ReplyCode::ERR_RESTRICTED => "Your connection is restricted!",
+        ReplyCode::ERR_UNIQOPPRIVSNEEDED => "You're not the original channel operator",
+        ReplyCode::ERR_NOOPERHOST => "No O-lines for your host",
+        ReplyCode::ERR_UMODEUNKNOWNFLAG => "Unknown MODE flag",
+        ReplyCode::ERR_USERSDONTMATCH => "Cannot change mode for other users",
+        ReplyCode::RPL_YOURID => "your unique ID",
+        ReplyCode::RPL_LOCALUSERS => "Current local users {0}, max {1}",
+        ReplyCode::RPL_GLOBALUSERS => "Current global users {0}, max {1}",
+        ReplyCode::RPL_WHOISCERTFP => "has client certificate fingerprint {0}",
+        ReplyCode::RPL_WHOISACCOUNT => "is logged in as",
+        ReplyCode::RPL_WHOISACTUALLY => "Actual user@host, Actual IP",
+        ReplyCode::RPL_WHOISHOST => "is connecting from *@{0} {1}",
+        ReplyCode::RPL_HOSTHIDDEN => "is now your displayed host",
+        ReplyCode::RPL_WHOISSECURE => "is using a secure connection",
+        ReplyCode::RPL_ENDOFQUIETLIST => "End of channel quiet list",
+        ReplyCode::RPL_ENDOFMONLIST => "End of MONITOR list",
+        ReplyCode::ERR_MONLISTFULL => "Monitor list is full.",
+        ReplyCode::RPL_LOGGEDIN => "You are now logged in as {0}",
+        ReplyCode::RPL_LOGGEDOUT => "You are now logged out",
+        ReplyCode::ERR_NICKLOCKED => "You must use a nick assigned to you",
+        ReplyCode::RPL_SASLSUCCESS => "SASL authentication successful",
+        ReplyCode::ERR_SASLFAIL => "SASL authentication failed",
+        ReplyCode::ERR_SASLTOOLONG => "SASL message too long",
+        ReplyCode::ERR_SASLABORTED => "SASL authentication aborted",
+        ReplyCode::ERR_SASLALREADY => "You have already authenticated using SASL",
+        ReplyCode::RPL_SASLMECHS => "are available SASL mechanisms",
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// Replace `{0}`, `{1}`... in a text with the arguments
+fn fill(text: &str, args: &[&str]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let arg = rest.find('}')
+            .and_then(|end| rest[1..end].parse::<usize>().ok().map(|idx| (idx, end)))
+            .and_then(|(idx, end)| args.get(idx).map(|arg| (arg, end)));
+        match arg {
+            Some((arg, end)) => {
+                result.push_str(arg);
+                rest = &rest[end + 1..];
+            },
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            },
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Builds the replies of a server, with the RFC 2812 texts unless replaced
+///
+/// `target` is the nickname of the client, or `*` before it has one.
+#[derive(Debug, Clone, Default)]
+pub struct Replies {
+    texts: Vec<(ReplyCode, String)>,
+}
+
+impl Replies {
+    pub fn new() -> Replies {
+        Replies {
+            texts: Vec::new(),
+        }
+    }
+
+    /// Replace the text of a reply
+    pub fn with_text(mut self, code: ReplyCode, text: &str) -> Replies {
+        self.texts.retain(|&(c, _)| c != code);
+        self.texts.push((code, text.to_owned()));
+        self
+    }
+
+    /// The text used for a reply
+    pub fn text(&self, code: ReplyCode) -> Option<&str> {
+        match self.texts.iter().find(|&&(c, _)| c == code) {
+            Some(&(_, ref text)) => Some(text),
+            None => default_text(code),
+        }
+    }
+
+    /// A reply with the parameters followed by the text of the code
+    fn text_reply(&self, code: ReplyCode, target: &str, params: &[&str], args: &[&str]) -> Reply<'static> {
+        let mut line = params.join(" ");
+
+        if let Some(text) = self.text(code) {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push(':');
+            line.push_str(&fill(text, args));
+        }
+        Reply::new(code, target, &line).into_owned()
+    }
+
+    /// A reply without text, the last parameter is data
+    fn data_reply(&self, code: ReplyCode, target: &str, params: &[&str], last: Option<&str>) -> Reply<'static> {
+        let mut line = params.join(" ");
+
+        if let Some(last) = last {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&LastParam(last).to_string());
+        }
+        Reply::new(code, target, &line).into_owned()
+    }
+
+    /// `RPL_WELCOME`, `{0}` is `<nick>!<user>@<host>`
+    pub fn welcome(&self, target: &str, prefix: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_WELCOME, target, &[], &[prefix])
+    }
+
+    /// `RPL_YOURHOST`, `{0}` is the server name and `{1}` the version
+    pub fn your_host(&self, target: &str, server: &str, version: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_YOURHOST, target, &[], &[server, version])
+    }
+
+    /// `RPL_CREATED`, `{0}` is the date
+    pub fn created(&self, target: &str, date: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_CREATED, target, &[], &[date])
+    }
+
+    /// `RPL_MYINFO`, `<servername> <version> <available user modes> <available channel modes>`
+    pub fn my_info(&self, target: &str, server: &str, version: &str, user_modes: &str, channel_modes: &str)
+            -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_MYINFO, target, &[server, version, user_modes], Some(channel_modes))
+    }
+
+    /// `RPL_ISUPPORT`, with tokens such as `NICKLEN=30`
+    pub fn isupport(&self, target: &str, tokens: &[&str]) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ISUPPORT, target, tokens, &[])
+    }
+
+    /// `RPL_BOUNCE`, `{0}` is the server and `{1}` the port
+    pub fn bounce(&self, target: &str, server: &str, port: u16) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_BOUNCE, target, &[], &[server, &port.to_string()])
+    }
+
+    /// `RPL_USERHOST`, replies such as `WiZ*=+jto@tolsun.oulu.fi`
+    pub fn userhost(&self, target: &str, replies: &[&str]) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_USERHOST, target, &[], Some(&replies.join(" ")))
+    }
+
+    /// `RPL_ISON`
+    pub fn ison(&self, target: &str, nicks: &[&str]) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_ISON, target, &[], Some(&nicks.join(" ")))
+    }
+
+    /// `RPL_AWAY`
+    pub fn away(&self, target: &str, nick: &str, message: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_AWAY, target, &[nick], Some(message))
+    }
+
+    /// `RPL_UNAWAY`
+    pub fn unaway(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_UNAWAY, target, &[], &[])
+    }
+
+    /// `RPL_NOWAWAY`
+    pub fn now_away(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_NOWAWAY, target, &[], &[])
+    }
+
+    /// `RPL_WHOISUSER`
+    pub fn whois_user(&self, target: &str, nick: &str, user: &str, host: &str, realname: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_WHOISUSER, target, &[nick, user, host, "*"], Some(realname))
+    }
+
+    /// `RPL_WHOISSERVER`
+    pub fn whois_server(&self, target: &str, nick: &str, server: &str, info: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_WHOISSERVER, target, &[nick, server], Some(info))
+    }
+
+    /// `RPL_WHOISOPERATOR`
+    pub fn whois_operator(&self, target: &str, nick: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_WHOISOPERATOR, target, &[nick], &[])
+    }
+
+    /// `RPL_WHOISIDLE`
+    pub fn whois_idle(&self, target: &str, nick: &str, seconds: u64) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_WHOISIDLE, target, &[nick, &seconds.to_string()], &[])
+    }
+
+    /// `RPL_ENDOFWHOIS`
+    pub fn end_of_whois(&self, target: &str, nick: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFWHOIS, target, &[nick], &[])
+    }
+
+    /// `RPL_WHOISCHANNELS`, channels with their membership prefix such as
+    /// `@#rust`
+    pub fn whois_channels(&self, target: &str, nick: &str, channels: &[&str]) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_WHOISCHANNELS, target, &[nick], Some(&channels.join(" ")))
+    }
+
+    /// `RPL_WHOWASUSER`
+    pub fn whowas_user(&self, target: &str, nick: &str, user: &str, host: &str, realname: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_WHOWASUSER, target, &[nick, user, host, "*"], Some(realname))
+    }
+
+    /// `RPL_ENDOFWHOWAS`
+    pub fn end_of_whowas(&self, target: &str, nick: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFWHOWAS, target, &[nick], &[])
+    }
+
+    /// `RPL_LISTSTART`
+    pub fn list_start(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_LISTSTART, target, &["Channel"], &[])
+    }
+
+    /// `RPL_LIST`
+    pub fn list(&self, target: &str, channel: &str, visible: u32, topic: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_LIST, target, &[channel, &visible.to_string()], Some(topic))
+    }
+
+    /// `RPL_LISTEND`
+    pub fn list_end(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_LISTEND, target, &[], &[])
+    }
+
+    /// `RPL_UNIQOPIS`
+    pub fn uniq_op_is(&self, target: &str, channel: &str, nick: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_UNIQOPIS, target, &[channel], Some(nick))
+    }
+
+    /// `RPL_CHANNELMODEIS`, `<channel> <mode> <mode params>`
+    pub fn channel_mode_is(&self, target: &str, channel: &str, modes: &str, params: &[&str]) -> Reply<'static> {
+        let mut all = vec![channel, modes];
+        all.extend_from_slice(params);
+        let last = all.pop();
+        self.data_reply(ReplyCode::RPL_CHANNELMODEIS, target, &all, last)
+    }
+
+    /// `RPL_NOTOPIC`
+    pub fn no_topic(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_NOTOPIC, target, &[channel], &[])
+    }
+
+    /// `RPL_TOPIC`
+    pub fn topic(&self, target: &str, channel: &str, topic: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TOPIC, target, &[channel], Some(topic))
+    }
+
+    /// `RPL_INVITING`
+    pub fn inviting(&self, target: &str, channel: &str, nick: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_INVITING, target, &[channel], Some(nick))
+    }
+
+    /// `RPL_SUMMONING`
+    pub fn summoning(&self, target: &str, user: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_SUMMONING, target, &[user], &[])
+    }
+
+    /// `RPL_INVITELIST`
+    pub fn invite_list(&self, target: &str, channel: &str, mask: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_INVITELIST, target, &[channel], Some(mask))
+    }
+
+    /// `RPL_ENDOFINVITELIST`
+    pub fn end_of_invite_list(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFINVITELIST, target, &[channel], &[])
+    }
+
+    /// `RPL_EXCEPTLIST`
+    pub fn except_list(&self, target: &str, channel: &str, mask: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_EXCEPTLIST, target, &[channel], Some(mask))
+    }
+
+    /// `RPL_ENDOFEXCEPTLIST`
+    pub fn end_of_except_list(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFEXCEPTLIST, target, &[channel], &[])
+    }
+
+    /// `RPL_VERSION`, `version` includes the debug level such as `2.10.1`
+    pub fn version(&self, target: &str, version: &str, server: &str, comments: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_VERSION, target, &[version, server], Some(comments))
+    }
+
+    /// `RPL_WHOREPLY`
+    pub fn who_reply(&self, target: &str, who: &WhoReply) -> Reply<'static> {
+        Reply::new(ReplyCode::RPL_WHOREPLY, target, &who.to_string()).into_owned()
+    }
+
+    /// `RPL_ENDOFWHO`
+    pub fn end_of_who(&self, target: &str, name: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFWHO, target, &[name], &[])
+    }
+
+    /// `RPL_NAMREPLY`, `symbol` is `=`, `*` or `@` and the members carry their
+    /// membership prefix
+    pub fn nam_reply(&self, target: &str, symbol: char, channel: &str, members: &[&str]) -> Reply<'static> {
+        let line = format!("{} {} :{}", symbol, channel, members.join(" "));
+        Reply::new(ReplyCode::RPL_NAMREPLY, target, &line).into_owned()
+    }
+
+    /// `RPL_ENDOFNAMES`
+    pub fn end_of_names(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFNAMES, target, &[channel], &[])
+    }
+
+    /// `RPL_LINKS`
+    pub fn links(&self, target: &str, mask: &str, server: &str, hopcount: u32, info: &str) -> Reply<'static> {
+        let last = format!("{} {}", hopcount, info);
+        self.data_reply(ReplyCode::RPL_LINKS, target, &[mask, server], Some(&last))
+    }
+
+    /// `RPL_ENDOFLINKS`
+    pub fn end_of_links(&self, target: &str, mask: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFLINKS, target, &[mask], &[])
+    }
+
+    /// `RPL_BANLIST`
+    pub fn ban_list(&self, target: &str, channel: &str, mask: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_BANLIST, target, &[channel], Some(mask))
+    }
+
+    /// `RPL_ENDOFBANLIST`
+    pub fn end_of_ban_list(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFBANLIST, target, &[channel], &[])
+    }
+
+    /// `RPL_INFO`
+    pub fn info(&self, target: &str, line: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_INFO, target, &[], Some(line))
+    }
+
+    /// `RPL_ENDOFINFO`
+    pub fn end_of_info(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFINFO, target, &[], &[])
+    }
+
+    /// `RPL_MOTDSTART`, `{0}` is the server
+    pub fn motd_start(&self, target: &str, server: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_MOTDSTART, target, &[], &[server])
+    }
+
+    /// `RPL_MOTD`, a line of the message of the day
+    pub fn motd(&self, target: &str, line: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_MOTD, target, &[], Some(&format!("- {}", line)))
+    }
+
+    /// `RPL_ENDOFMOTD`
+    pub fn end_of_motd(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFMOTD, target, &[], &[])
+    }
+
+    /// `RPL_YOUREOPER`
+    pub fn youre_oper(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_YOUREOPER, target, &[], &[])
+    }
+
+    /// `RPL_REHASHING`
+    pub fn rehashing(&self, target: &str, config_file: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_REHASHING, target, &[config_file], &[])
+    }
+
+    /// `RPL_YOURESERVICE`, `{0}` is the service name
+    pub fn youre_service(&self, target: &str, service: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_YOURESERVICE, target, &[], &[service])
+    }
+
+    /// `RPL_TIME`
+    pub fn time(&self, target: &str, server: &str, time: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TIME, target, &[server], Some(time))
+    }
+
+    /// `RPL_USERSSTART`
+    pub fn users_start(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_USERSSTART, target, &[], &[])
+    }
+
+    /// `RPL_USERS`
+    pub fn users(&self, target: &str, user: &str, tty: &str, host: &str) -> Reply<'static> {
+        let line = format!("{:<8} {:<9} {:<8}", user, tty, host);
+        self.data_reply(ReplyCode::RPL_USERS, target, &[], Some(&line))
+    }
+
+    /// `RPL_ENDOFUSERS`
+    pub fn end_of_users(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFUSERS, target, &[], &[])
+    }
+
+    /// `RPL_NOUSERS`
+    pub fn no_users(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_NOUSERS, target, &[], &[])
+    }
+
+    /// `RPL_TRACELINK`
+    pub fn trace_link(&self, target: &str, link: &TraceLink) -> Reply<'static> {
+        Reply::new(ReplyCode::RPL_TRACELINK, target, &link.to_string()).into_owned()
+    }
+
+    /// `RPL_TRACECONNECTING`
+    pub fn trace_connecting(&self, target: &str, class: &str, server: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TRACECONNECTING, target, &["Try.", class], Some(server))
+    }
+
+    /// `RPL_TRACEHANDSHAKE`
+    pub fn trace_handshake(&self, target: &str, class: &str, server: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TRACEHANDSHAKE, target, &["H.S.", class], Some(server))
+    }
+
+    /// `RPL_TRACEUNKNOWN`
+    pub fn trace_unknown(&self, target: &str, class: &str, address: Option<&str>) -> Reply<'static> {
+        match address {
+            Some(address) => self.data_reply(ReplyCode::RPL_TRACEUNKNOWN, target, &["????", class], Some(address)),
+            None => self.data_reply(ReplyCode::RPL_TRACEUNKNOWN, target, &["????"], Some(class)),
+        }
+    }
+
+    /// `RPL_TRACEOPERATOR`
+    pub fn trace_operator(&self, target: &str, class: &str, nick: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TRACEOPERATOR, target, &["Oper", class], Some(nick))
+    }
+
+    /// `RPL_TRACEUSER`
+    pub fn trace_user(&self, target: &str, class: &str, nick: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TRACEUSER, target, &["User", class], Some(nick))
+    }
+
+    /// `RPL_TRACESERVER`
+    pub fn trace_server(&self, target: &str, server: &TraceServer) -> Reply<'static> {
+        Reply::new(ReplyCode::RPL_TRACESERVER, target, &server.to_string()).into_owned()
+    }
+
+    /// `RPL_TRACESERVICE`
+    pub fn trace_service(&self, target: &str, class: &str, name: &str, service_type: &str, active_type: &str)
+            -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TRACESERVICE, target, &["Service", class, name, service_type],
+                        Some(active_type))
+    }
+
+    /// `RPL_TRACENEWTYPE`
+    pub fn trace_new_type(&self, target: &str, new_type: &str, client: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TRACENEWTYPE, target, &[new_type, "0"], Some(client))
+    }
+
+    /// `RPL_TRACECLASS`
+    pub fn trace_class(&self, target: &str, class: &str, count: u32) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TRACECLASS, target, &["Class", class], Some(&count.to_string()))
+    }
+
+    /// `RPL_TRACERECONNECT`, unused by RFC 2812
+    pub fn trace_reconnect(&self, target: &str, class: &str, server: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TRACERECONNECT, target, &["Retry.", class], Some(server))
+    }
+
+    /// `RPL_TRACELOG`
+    pub fn trace_log(&self, target: &str, log_file: &str, debug_level: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TRACELOG, target, &["File", log_file], Some(debug_level))
+    }
+
+    /// `RPL_TRACEEND`, `version` includes the debug level
+    pub fn trace_end(&self, target: &str, server: &str, version: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_TRACEEND, target, &[server, version], &[])
+    }
+
+    /// `RPL_STATSLINKINFO`
+    pub fn stats_link_info(&self, target: &str, info: &StatsLinkInfo) -> Reply<'static> {
+        Reply::new(ReplyCode::RPL_STATSLINKINFO, target, &info.to_string()).into_owned()
+    }
+
+    /// `RPL_STATSCOMMANDS`
+    pub fn stats_commands(&self, target: &str, command: &str, count: u64, bytes: u64, remote_count: u64)
+            -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_STATSCOMMANDS, target, &[command, &count.to_string(), &bytes.to_string()],
+                        Some(&remote_count.to_string()))
+    }
+
+    /// `RPL_ENDOFSTATS`
+    pub fn end_of_stats(&self, target: &str, letter: char) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFSTATS, target, &[&letter.to_string()], &[])
+    }
+
+    /// `RPL_STATSUPTIME`, `{0}` to `{3}` are the days, hours, minutes and
+    /// seconds of `uptime`
+    pub fn stats_uptime(&self, target: &str, uptime: u64) -> Reply<'static> {
+        let days = (uptime / 86400).to_string();
+        let hours = (uptime % 86400 / 3600).to_string();
+        let minutes = format!("{:02}", uptime % 3600 / 60);
+        let seconds = format!("{:02}", uptime % 60);
+        self.text_reply(ReplyCode::RPL_STATSUPTIME, target, &[], &[&days, &hours, &minutes, &seconds])
+    }
+
+    /// `RPL_STATSOLINE`
+    pub fn stats_oline(&self, target: &str, hostmask: &str, name: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_STATSOLINE, target, &["O", hostmask, "*"], Some(name))
+    }
+
+    /// `RPL_UMODEIS`
+    pub fn umode_is(&self, target: &str, modes: &UserModeSet) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_UMODEIS, target, &[], Some(&modes.to_string()))
+    }
+
+    /// `RPL_SERVLIST`
+    pub fn serv_list(&self, target: &str, service: &ServList) -> Reply<'static> {
+        Reply::new(ReplyCode::RPL_SERVLIST, target, &service.to_string()).into_owned()
+    }
+
+    /// `RPL_SERVLISTEND`
+    pub fn serv_list_end(&self, target: &str, mask: &str, service_type: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_SERVLISTEND, target, &[mask, service_type], &[])
+    }
+
+    /// `RPL_LUSERCLIENT`, `{0}` to `{2}` are the numbers of users, services
+    /// and servers
+    pub fn luser_client(&self, target: &str, users: u32, services: u32, servers: u32) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_LUSERCLIENT, target, &[],
+                        &[&users.to_string(), &services.to_string(), &servers.to_string()])
+    }
+
+    /// `RPL_LUSEROP`
+    pub fn luser_op(&self, target: &str, count: u32) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_LUSEROP, target, &[&count.to_string()], &[])
+    }
+
+    /// `RPL_LUSERUNKNOWN`
+    pub fn luser_unknown(&self, target: &str, count: u32) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_LUSERUNKNOWN, target, &[&count.to_string()], &[])
+    }
+
+    /// `RPL_LUSERCHANNELS`
+    pub fn luser_channels(&self, target: &str, count: u32) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_LUSERCHANNELS, target, &[&count.to_string()], &[])
+    }
+
+    /// `RPL_LUSERME`, `{0}` and `{1}` are the numbers of clients and servers
+    pub fn luser_me(&self, target: &str, clients: u32, servers: u32) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_LUSERME, target, &[], &[&clients.to_string(), &servers.to_string()])
+    }
+
+    /// `RPL_ADMINME`
+    pub fn admin_me(&self, target: &str, server: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ADMINME, target, &[server], &[])
+    }
+
+    /// `RPL_ADMINLOC1`, the city, state and country of the server
+    pub fn admin_loc1(&self, target: &str, info: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_ADMINLOC1, target, &[], Some(info))
+    }
+
+    /// `RPL_ADMINLOC2`, the institution running the server
+    pub fn admin_loc2(&self, target: &str, info: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_ADMINLOC2, target, &[], Some(info))
+    }
+
+    /// `RPL_ADMINEMAIL`
+    pub fn admin_email(&self, target: &str, email: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_ADMINEMAIL, target, &[], Some(email))
+    }
+
+    /// `RPL_TRYAGAIN`
+    pub fn try_again(&self, target: &str, command: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_TRYAGAIN, target, &[command], &[])
+    }
+
+    /// `ERR_NOSUCHNICK`
+    pub fn no_such_nick(&self, target: &str, nick: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOSUCHNICK, target, &[nick], &[])
+    }
+
+    /// `ERR_NOSUCHSERVER`
+    pub fn no_such_server(&self, target: &str, server: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOSUCHSERVER, target, &[server], &[])
+    }
+
+    /// `ERR_NOSUCHCHANNEL`
+    pub fn no_such_channel(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOSUCHCHANNEL, target, &[channel], &[])
+    }
+
+    /// `ERR_CANNOTSENDTOCHAN`
+    pub fn cannot_send_to_chan(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_CANNOTSENDTOCHAN, target, &[channel], &[])
+    }
+
+    /// `ERR_TOOMANYCHANNELS`
+    pub fn too_many_channels(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_TOOMANYCHANNELS, target, &[channel], &[])
+    }
+
+    /// `ERR_WASNOSUCHNICK`
+    pub fn was_no_such_nick(&self, target: &str, nick: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_WASNOSUCHNICK, target, &[nick], &[])
+    }
+
+    /// `ERR_TOOMANYTARGETS`, `{0}` is the error code and `{1}` the abort
+    /// message
+    pub fn too_many_targets(&self, target: &str, name: &str, error_code: &str, abort_message: &str)
+            -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_TOOMANYTARGETS, target, &[name], &[error_code, abort_message])
+    }
+
+    /// `ERR_NOSUCHSERVICE`
+    pub fn no_such_service(&self, target: &str, service: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOSUCHSERVICE, target, &[service], &[])
+    }
+
+    /// `ERR_NOORIGIN`
+    pub fn no_origin(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOORIGIN, target, &[], &[])
+    }
+
+    /// `ERR_NORECIPIENT`, `{0}` is the command
+    pub fn no_recipient(&self, target: &str, command: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NORECIPIENT, target, &[], &[command])
+    }
+
+    /// `ERR_NOTEXTTOSEND`
+    pub fn no_text_to_send(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOTEXTTOSEND, target, &[], &[])
+    }
+
+    /// `ERR_NOTOPLEVEL`
+    pub fn no_top_level(&self, target: &str, mask: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOTOPLEVEL, target, &[mask], &[])
+    }
+
+    /// `ERR_WILDTOPLEVEL`
+    pub fn wild_top_level(&self, target: &str, mask: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_WILDTOPLEVEL, target, &[mask], &[])
+    }
+
+    /// `ERR_BADMASK`
+    pub fn bad_mask(&self, target: &str, mask: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_BADMASK, target, &[mask], &[])
+    }
+
+    /// `ERR_UNKNOWNCOMMAND`
+    pub fn unknown_command(&self, target: &str, command: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_UNKNOWNCOMMAND, target, &[command], &[])
+    }
+
+    /// `ERR_NOMOTD`
+    pub fn no_motd(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOMOTD, target, &[], &[])
+    }
+
+    /// `ERR_NOADMININFO`
+    pub fn no_admin_info(&self, target: &str, server: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOADMININFO, target, &[server], &[])
+    }
+
+    /// `ERR_FILEERROR`, `{0}` is the operation and `{1}` the file
+    pub fn file_error(&self, target: &str, operation: &str, file: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_FILEERROR, target, &[], &[operation, file])
+    }
+
+    /// `ERR_NONICKNAMEGIVEN`
+    pub fn no_nickname_given(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NONICKNAMEGIVEN, target, &[], &[])
+    }
+
+    /// `ERR_ERRONEUSNICKNAME`
+    pub fn erroneous_nickname(&self, target: &str, nick: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_ERRONEUSNICKNAME, target, &[nick], &[])
+    }
+
+    /// `ERR_NICKNAMEINUSE`
+    pub fn nickname_in_use(&self, target: &str, nick: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NICKNAMEINUSE, target, &[nick], &[])
+    }
+
+    /// `ERR_NICKCOLLISION`, `{0}` is the user and `{1}` the host
+    pub fn nick_collision(&self, target: &str, nick: &str, user: &str, host: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NICKCOLLISION, target, &[nick], &[user, host])
+    }
+
+    /// `ERR_UNAVAILRESOURCE`
+    pub fn unavail_resource(&self, target: &str, name: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_UNAVAILRESOURCE, target, &[name], &[])
+    }
+
+    /// `ERR_USERNOTINCHANNEL`
+    pub fn user_not_in_channel(&self, target: &str, nick: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_USERNOTINCHANNEL, target, &[nick, channel], &[])
+    }
+
+    /// `ERR_NOTONCHANNEL`
+    pub fn not_on_channel(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOTONCHANNEL, target, &[channel], &[])
+    }
+
+    /// `ERR_USERONCHANNEL`
+    pub fn user_on_channel(&self, target: &str, user: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_USERONCHANNEL, target, &[user, channel], &[])
+    }
+
+    /// `ERR_NOLOGIN`
+    pub fn no_login(&self, target: &str, user: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOLOGIN, target, &[user], &[])
+    }
+
+    /// `ERR_SUMMONDISABLED`
+    pub fn summon_disabled(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_SUMMONDISABLED, target, &[], &[])
+    }
+
+    /// `ERR_USERSDISABLED`
+    pub fn users_disabled(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_USERSDISABLED, target, &[], &[])
+    }
+
+    /// `ERR_NOTREGISTERED`
+    pub fn not_registered(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOTREGISTERED, target, &[], &[])
+    }
+
+    /// `ERR_NEEDMOREPARAMS`
+    pub fn need_more_params(&self, target: &str, command: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NEEDMOREPARAMS, target, &[command], &[])
+    }
+
+    /// `ERR_ALREADYREGISTRED`
+    pub fn already_registered(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_ALREADYREGISTRED, target, &[], &[])
+    }
+
+    /// `ERR_NOPERMFORHOST`
+    pub fn no_perm_for_host(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOPERMFORHOST, target, &[], &[])
+    }
+
+    /// `ERR_PASSWDMISMATCH`
+    pub fn passwd_mismatch(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_PASSWDMISMATCH, target, &[], &[])
+    }
+
+    /// `ERR_YOUREBANNEDCREEP`
+    pub fn youre_banned_creep(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_YOUREBANNEDCREEP, target, &[], &[])
+    }
+
+    /// `ERR_YOUWILLBEBANNED`
+    pub fn you_will_be_banned(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_YOUWILLBEBANNED, target, &[], &[])
+    }
+
+    /// `ERR_KEYSET`
+    pub fn key_set(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_KEYSET, target, &[channel], &[])
+    }
+
+    /// `ERR_CHANNELISFULL`
+    pub fn channel_is_full(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_CHANNELISFULL, target, &[channel], &[])
+    }
+
+    /// `ERR_UNKNOWNMODE`, `{0}` is the channel
+    pub fn unknown_mode(&self, target: &str, mode: char, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_UNKNOWNMODE, target, &[&mode.to_string()], &[channel])
+    }
+
+    /// `ERR_INVITEONLYCHAN`
+    pub fn invite_only_chan(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_INVITEONLYCHAN, target, &[channel], &[])
+    }
+
+    /// `ERR_BANNEDFROMCHAN`
+    pub fn banned_from_chan(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_BANNEDFROMCHAN, target, &[channel], &[])
+    }
+
+    /// `ERR_BADCHANNELKEY`
+    pub fn bad_channel_key(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_BADCHANNELKEY, target, &[channel], &[])
+    }
+
+    /// `ERR_BADCHANMASK`
+    pub fn bad_chan_mask(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_BADCHANMASK, target, &[channel], &[])
+    }
+
+    /// `ERR_NOCHANMODES`
+    pub fn no_chan_modes(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOCHANMODES, target, &[channel], &[])
+    }
+
+    /// `ERR_BANLISTFULL`
+    pub fn ban_list_full(&self, target: &str, channel: &str, mode: char) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_BANLISTFULL, target, &[channel, &mode.to_string()], &[])
+    }
+
+    /// `ERR_NOPRIVILEGES`
+    pub fn no_privileges(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOPRIVILEGES, target, &[], &[])
+    }
+
+    /// `ERR_CHANOPRIVSNEEDED`
+    pub fn chanop_privs_needed(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_CHANOPRIVSNEEDED, target, &[channel], &[])
+    }
+
+    /// `ERR_CANTKILLSERVER`
+    pub fn cant_kill_server(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_CANTKILLSERVER, target, &[], &[])
+    }
+
+    /// `ERR_RESTRICTED`
+    pub fn restricted(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_RESTRICTED, target, &[], &[])
+    }
+
+    /// `ERR_UNIQOPPRIVSNEEDED`
+    pub fn uniq_op_privs_needed(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_UNIQOPPRIVSNEEDED, target, &[], &[])
+    }
+
+    /// `ERR_NOOPERHOST`
+    pub fn no_oper_host(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NOOPERHOST, target, &[], &[])
+    }
+
+    /// `ERR_UMODEUNKNOWNFLAG`
+    pub fn umode_unknown_flag(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_UMODEUNKNOWNFLAG, target, &[], &[])
+    }
+
+    /// `ERR_USERSDONTMATCH`
+    pub fn users_dont_match(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_USERSDONTMATCH, target, &[], &[])
+    }
+
+    /// `RPL_YOURID`
+    pub fn your_id(&self, target: &str, id: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_YOURID, target, &[id], &[])
+    }
+
+    /// `RPL_LOCALUSERS`, `{0}` and `{1}` are the current and maximum
+    /// numbers of users
+    pub fn local_users(&self, target: &str, current: u32, max: u32) -> Reply<'static> {
+        let (current, max) = (current.to_string(), max.to_string());
+        self.text_reply(ReplyCode::RPL_LOCALUSERS, target, &[&current, &max], &[&current, &max])
+    }
+
+    /// `RPL_GLOBALUSERS`, `{0}` and `{1}` are the current and maximum
+    /// numbers of users
+    pub fn global_users(&self, target: &str, current: u32, max: u32) -> Reply<'static> {
+        let (current, max) = (current.to_string(), max.to_string());
+        self.text_reply(ReplyCode::RPL_GLOBALUSERS, target, &[&current, &max], &[&current, &max])
+    }
+
+    /// `RPL_WHOISCERTFP`, `{0}` is the fingerprint
+    pub fn whois_cert_fp(&self, target: &str, nick: &str, fingerprint: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_WHOISCERTFP, target, &[nick], &[fingerprint])
+    }
+
+    /// `RPL_WHOISACCOUNT`
+    pub fn whois_account(&self, target: &str, nick: &str, account: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_WHOISACCOUNT, target, &[nick, account], &[])
+    }
+
+    /// `RPL_WHOISACTUALLY`, `host` is `<user>@<host>`
+    pub fn whois_actually(&self, target: &str, nick: &str, host: &str, ip: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_WHOISACTUALLY, target, &[nick, host, ip], &[])
+    }
+
+    /// `RPL_WHOISHOST`, `{0}` is the host and `{1}` the address
+    pub fn whois_host(&self, target: &str, nick: &str, host: &str, ip: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_WHOISHOST, target, &[nick], &[host, ip])
+    }
+
+    /// `RPL_WHOISSECURE`
+    pub fn whois_secure(&self, target: &str, nick: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_WHOISSECURE, target, &[nick], &[])
+    }
+
+    /// `RPL_TOPICWHOTIME`, `set_at` in seconds since the epoch
+    pub fn topic_who_time(&self, target: &str, channel: &str, nick: &str, set_at: u64) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_TOPICWHOTIME, target, &[channel, nick], Some(&set_at.to_string()))
+    }
+
+    /// `RPL_HOSTHIDDEN`
+    pub fn host_hidden(&self, target: &str, host: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_HOSTHIDDEN, target, &[host], &[])
+    }
+
+    /// `RPL_QUIETLIST`
+    pub fn quiet_list(&self, target: &str, channel: &str, mask: &str) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_QUIETLIST, target, &[channel, "q"], Some(mask))
+    }
+
+    /// `RPL_ENDOFQUIETLIST`
+    pub fn end_of_quiet_list(&self, target: &str, channel: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFQUIETLIST, target, &[channel, "q"], &[])
+    }
+
+    /// `RPL_MONONLINE`, targets such as `jto!~jto@tolsun.oulu.fi`
+    pub fn mon_online(&self, target: &str, targets: &[&str]) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_MONONLINE, target, &[], Some(&targets.join(",")))
+    }
+
+    /// `RPL_MONOFFLINE`
+    pub fn mon_offline(&self, target: &str, targets: &[&str]) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_MONOFFLINE, target, &[], Some(&targets.join(",")))
+    }
+
+    /// `RPL_MONLIST`
+    pub fn mon_list(&self, target: &str, targets: &[&str]) -> Reply<'static> {
+        self.data_reply(ReplyCode::RPL_MONLIST, target, &[], Some(&targets.join(",")))
+    }
+
+    /// `RPL_ENDOFMONLIST`
+    pub fn end_of_mon_list(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_ENDOFMONLIST, target, &[], &[])
+    }
+
+    /// `ERR_MONLISTFULL`, `targets` are the ones which were not added
+    pub fn mon_list_full(&self, target: &str, limit: u32, targets: &[&str]) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_MONLISTFULL, target, &[&limit.to_string(), &targets.join(",")], &[])
+    }
+
+    /// `RPL_LOGGEDIN`, `{0}` is the account
+    pub fn logged_in(&self, target: &str, prefix: &str, account: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_LOGGEDIN, target, &[prefix, account], &[account])
+    }
+
+    /// `RPL_LOGGEDOUT`
+    pub fn logged_out(&self, target: &str, prefix: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_LOGGEDOUT, target, &[prefix], &[])
+    }
+
+    /// `ERR_NICKLOCKED`
+    pub fn nick_locked(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_NICKLOCKED, target, &[], &[])
+    }
+
+    /// `RPL_SASLSUCCESS`
+    pub fn sasl_success(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_SASLSUCCESS, target, &[], &[])
+    }
+
+    /// `ERR_SASLFAIL`
+    pub fn sasl_fail(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_SASLFAIL, target, &[], &[])
+    }
+
+    /// `ERR_SASLTOOLONG`
+    pub fn sasl_too_long(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_SASLTOOLONG, target, &[], &[])
+    }
+
+    /// `ERR_SASLABORTED`
+    pub fn sasl_aborted(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_SASLABORTED, target, &[], &[])
+    }
+
+    /// `ERR_SASLALREADY`
+    pub fn sasl_already(&self, target: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::ERR_SASLALREADY, target, &[], &[])
+    }
+
+    /// `RPL_SASLMECHS`, mechanisms such as `PLAIN,EXTERNAL`
+    pub fn sasl_mechs(&self, target: &str, mechanisms: &str) -> Reply<'static> {
+        self.text_reply(ReplyCode::RPL_SASLMECHS, target, &[mechanisms], &[])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::IrcMessage;
+    use protocol::payload::ReplyPayload;
+    use protocol::reply::{Reply, ReplyCode};
+    use protocol::usermode::UserModeSet;
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill("Welcome {0}", &["WiZ"]), "Welcome WiZ");
+        assert_eq!(fill("{1} and {0}", &["a", "b"]), "b and a");
+        assert_eq!(fill("{2} {x} {", &["a"]), "{2} {x} {");
+    }
+
+    #[test]
+    fn test_replies() {
+        let replies = Replies::new();
+        let cases = [
+            (replies.welcome("WiZ", "WiZ!jto@tolsun.oulu.fi"),
+             "001 WiZ :Welcome to the Internet Relay Network WiZ!jto@tolsun.oulu.fi"),
+            (replies.your_host("WiZ", "irc.example.com", "2.10"),
+             "002 WiZ :Your host is irc.example.com, running version 2.10"),
+            (replies.my_info("WiZ", "irc.example.com", "2.10", "aiwroOs", "biklmnopstv"),
+             "004 WiZ irc.example.com 2.10 aiwroOs biklmnopstv"),
+            (replies.isupport("WiZ", &["CHANTYPES=#", "NICKLEN=30"]),
+             "005 WiZ CHANTYPES=# NICKLEN=30 :are supported by this server"),
+            (replies.ison("WiZ", &["jto", "trillian"]), "303 WiZ :jto trillian"),
+            (replies.umode_is("WiZ", &"+iw".parse::<UserModeSet>().unwrap()), "221 WiZ +iw"),
+            (replies.nam_reply("WiZ", '=', "#rust", &["@WiZ", "+jto"]), "353 WiZ = #rust :@WiZ +jto"),
+            (replies.end_of_names("WiZ", "#rust"), "366 WiZ #rust :End of NAMES list"),
+            (replies.channel_mode_is("WiZ", "#rust", "+kl", &["key", "42"]), "324 WiZ #rust +kl key 42"),
+            (replies.motd("WiZ", "Hello"), "372 WiZ :- Hello"),
+            (replies.stats_uptime("WiZ", 90061), "242 WiZ :Server Up 1 days 1:01:01"),
+            (replies.luser_client("WiZ", 42, 1, 3), "251 WiZ :There are 42 users and 1 services on 3 servers"),
+            (replies.admin_me("WiZ", "irc.example.com"), "256 WiZ irc.example.com :Administrative info"),
+            (replies.admin_loc1("WiZ", "Oulu, Finland"), "257 WiZ :Oulu, Finland"),
+            (replies.admin_loc2("WiZ", "University of Oulu"), "258 WiZ :University of Oulu"),
+            (replies.admin_email("WiZ", "jto@tolsun.oulu.fi"), "259 WiZ jto@tolsun.oulu.fi"),
+            (replies.no_recipient("WiZ", "PRIVMSG"), "411 WiZ :No recipient given (PRIVMSG)"),
+            (replies.unknown_mode("WiZ", 'y', "#rust"), "472 WiZ y :is unknown mode char to me for #rust"),
+            (replies.need_more_params("*", "USER"), "461 * USER :Not enough parameters"),
+            (replies.user_not_in_channel("WiZ", "jto", "#rust"), "441 WiZ jto #rust :They aren't on that channel"),
+            (replies.logged_in("WiZ", "WiZ!jto@tolsun.oulu.fi", "jto"),
+             "900 WiZ WiZ!jto@tolsun.oulu.fi jto :You are now logged in as jto"),
+            (replies.sasl_mechs("WiZ", "PLAIN,EXTERNAL"), "908 WiZ PLAIN,EXTERNAL :are available SASL mechanisms"),
+            (replies.your_id("WiZ", "9XAAAAAAA"), "042 WiZ 9XAAAAAAA :your unique ID"),
+            (replies.local_users("WiZ", 42, 64), "265 WiZ 42 64 :Current local users 42, max 64"),
+            (replies.whois_account("WiZ", "jto", "jarkko"), "330 WiZ jto jarkko :is logged in as"),
+            (replies.topic_who_time("WiZ", "#rust", "jto", 1792234861), "333 WiZ #rust jto 1792234861"),
+            (replies.whois_host("WiZ", "jto", "tolsun.oulu.fi", "192.0.2.1"),
+             "378 WiZ jto :is connecting from *@tolsun.oulu.fi 192.0.2.1"),
+            (replies.quiet_list("WiZ", "#rust", "*!*@*.fi"), "728 WiZ #rust q *!*@*.fi"),
+            (replies.mon_online("WiZ", &["jto!~jto@tolsun.oulu.fi", "trillian"]),
+             "730 WiZ jto!~jto@tolsun.oulu.fi,trillian"),
+            (replies.mon_list_full("WiZ", 100, &["jto", "trillian"]), "734 WiZ 100 jto,trillian :Monitor list is full."),
+        ];
+
+        for &(ref reply, line) in &cases {
+            assert_eq!(&reply.to_string()[..], line);
+            assert_eq!(&Reply::from_str(line).unwrap(), reply);
+        }
+    }
+
+    #[test]
+    fn test_replies_payload() {
+        let replies = Replies::new();
+
+        let reply = replies.whois_user("WiZ", "jto", "~jto", "tolsun.oulu.fi", "Jarkko Oikarinen");
+        assert_eq!(&reply.to_string()[..], "311 WiZ jto ~jto tolsun.oulu.fi * :Jarkko Oikarinen");
+
+        let who = WhoReply {
+            channel: "#rust",
+            user: "~jto",
+            host: "tolsun.oulu.fi",
+            server: "irc.example.com",
+            nick: "jto",
+            flags: "H@",
+            hopcount: 0,
+            realname: "Jarkko Oikarinen",
+        };
+        let reply = replies.who_reply("WiZ", &who);
+        assert_eq!(&reply.to_string()[..],
+                   "352 WiZ #rust ~jto tolsun.oulu.fi irc.example.com jto H@ :0 Jarkko Oikarinen");
+        match reply.payload().unwrap().unwrap() {
+            ReplyPayload::WhoReply(parsed) => assert_eq!(parsed, who),
+            other => panic!("Unexpected payload {:?}", other),
+        }
+
+        let server = TraceServer {
+            class: "10",
+            servers: 2,
+            clients: 42,
+            server: "hub.example.com",
+            connected_by: "*!*@irc.example.com",
+            protocol_version: "0210",
+        };
+        assert_eq!(&replies.trace_server("WiZ", &server).to_string()[..],
+                   "206 WiZ Serv 10 2S 42C hub.example.com *!*@irc.example.com V0210");
+
+        let service = ServList {
+            name: "NickServ",
+            server: "irc.example.com",
+            mask: "*",
+            service_type: "0",
+            hopcount: 1,
+            info: "Nickname services",
+        };
+        assert_eq!(&replies.serv_list("WiZ", &service).to_string()[..],
+                   "234 WiZ NickServ irc.example.com * 0 1 :Nickname services");
+    }
+
+    #[test]
+    fn test_replies_texts() {
+        let replies = Replies::new()
+            .with_text(ReplyCode::RPL_WELCOME, "Bienvenue {0}")
+            .with_text(ReplyCode::ERR_YOUWILLBEBANNED, "Bye");
+
+        assert_eq!(&replies.welcome("WiZ", "WiZ!jto@tolsun.oulu.fi").to_string()[..],
+                   "001 WiZ :Bienvenue WiZ!jto@tolsun.oulu.fi");
+        assert_eq!(&replies.you_will_be_banned("WiZ").to_string()[..], "466 WiZ :Bye");
+        assert_eq!(replies.text(ReplyCode::ERR_NOSUCHNICK), Some("No such nick/channel"));
+        assert_eq!(replies.text(ReplyCode::RPL_MYINFO), None);
+    }
+}
@@ -1,26 +1,40 @@
 
 use std::fmt;
+use std::borrow::Cow;
 
 use protocol::message::{RawMessage, IrcMessage, MessageParamIter, ParseMessageError, ParseMessageErrorKind};
+use protocol::payload::ReplyPayload;
+use protocol::usermode::UserModeSet;
 
 /// Numerics in the range from 001 to 099 are used for client-server
 /// connections only and should never travel between servers.  Replies
 /// generated in the response to commands are found in the range from 200
 /// to 399.
-#[repr(u16)]
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ReplyCode {
     /// The server sends Replies 001 to 004 to a user upon
     /// successful registration.
-    RPL_WELCOME         = 1,
-    RPL_YOURHOST        = 2,
-    RPL_CREATED         = 3,
-    RPL_MYINFO          = 4,
+    RPL_WELCOME,
+    RPL_YOURHOST,
+    RPL_CREATED,
+    RPL_MYINFO,
+    /// Lists the features supported by the server, in one or
+    /// more replies after `RPL_MYINFO`.
+    ///
+    /// `<token>[=<value>] ... :are supported by this server`
+    RPL_ISUPPORT,
     /// Sent by the server to a user to suggest an alternative
     /// server.  This is often used when the connection is
-    /// refused because the server is already full.
-    RPL_BOUNCE          = 5,
+    /// refused because the server is already full.  RFC 2812
+    /// gives it 005, which servers use for `RPL_ISUPPORT`.
+    RPL_BOUNCE,
+
+    /// Sent after registration with the unique identifier of the
+    /// client on the network.
+    ///
+    /// `<id> :your unique ID`
+    RPL_YOURID,
 
     /// Reply format used by `USERHOST` to list replies to
     /// the query list.  The reply string is composed as
@@ -32,11 +46,11 @@ pub enum ReplyCode {
     /// as an Operator.  The '-' or '+' characters represent
     /// whether the client has set an `AWAY` message or not
     /// respectively.
-    RPL_USERHOST        = 302,
+    RPL_USERHOST,
 
     /// Reply format used by `ISON` to list replies to the
     /// query list.
-    RPL_ISON            = 303,
+    RPL_ISON,
 
     /// These replies are used with the `AWAY` command (if
     /// allowed).  `RPL_AWAY` is sent to any client sending a
@@ -44,9 +58,9 @@ pub enum ReplyCode {
     /// sent by the server to which the client is connected.
     /// Replies `RPL_UNAWAY` and `RPL_NOWAWAY` are sent when the
     /// client removes and sets an `AWAY `message.
-    RPL_AWAY            = 301,
-    RPL_UNAWAY          = 305,
-    RPL_NOWAWAY         = 306,
+    RPL_AWAY,
+    RPL_UNAWAY,
+    RPL_NOWAWAY,
 
     /// Replies 311 - 313, 317 - 319 are all replies
     /// generated in response to a `WHOIS` message.  Given that
@@ -62,12 +76,42 @@ pub enum ReplyCode {
     /// has been granted permission to speak on a moderated
     /// channel.  The `RPL_ENDOFWHOIS` reply is used to mark
     /// the end of processing a `WHOIS` message.
-    RPL_WHOISUSER       = 311,
-    RPL_WHOISSERVER     = 312,
-    RPL_WHOISOPERATOR   = 313,
-    RPL_WHOISIDLE       = 317,
-    RPL_ENDOFWHOIS      = 318,
-    RPL_WHOISCHANNELS   = 319,
+    RPL_WHOISUSER,
+    RPL_WHOISSERVER,
+    RPL_WHOISOPERATOR,
+    RPL_WHOISIDLE,
+    RPL_ENDOFWHOIS,
+    RPL_WHOISCHANNELS,
+
+    /// Sent in reply to `WHOIS` with the fingerprint of the TLS
+    /// client certificate of the user.
+    ///
+    /// `<nick> :has client certificate fingerprint <fingerprint>`
+    RPL_WHOISCERTFP,
+
+    /// Sent in reply to `WHOIS` when the user is logged into an
+    /// account.
+    ///
+    /// `<nick> <account> :is logged in as`
+    RPL_WHOISACCOUNT,
+
+    /// Sent in reply to `WHOIS` with the real host or address of
+    /// the user, when the visible host is cloaked.
+    ///
+    /// `<nick> <host> <ip> :Actual user@host, Actual IP`
+    RPL_WHOISACTUALLY,
+
+    /// Sent in reply to `WHOIS` with the host the user connects
+    /// from.
+    ///
+    /// `<nick> :is connecting from *@<host> <ip>`
+    RPL_WHOISHOST,
+
+    /// Sent in reply to `WHOIS` when the user is connected with
+    /// TLS.
+    ///
+    /// `<nick> :is using a secure connection`
+    RPL_WHOISSECURE,
 
     /// When replying to a `WHOWAS` message, a server MUST use
     /// the replies `RPL_WHOWASUSER`, `RPL_WHOISSERVER` or
@@ -75,36 +119,42 @@ pub enum ReplyCode {
     /// list.  At the end of all reply batches, there MUST
     /// be `RPL_ENDOFWHOWAS` (even if there was only one reply
     /// and it was an error).
-    RPL_WHOWASUSER      = 314,
-    RPL_ENDOFWHOWAS     = 369,
+    RPL_WHOWASUSER,
+    RPL_ENDOFWHOWAS,
 
     /// Obsolete
-    RPL_LISTSTART       = 321,
+    RPL_LISTSTART,
 
     /// Replies `RPL_LIST`, `RPL_LISTEND` mark the actual replies
     /// with data and end of the server's response to a `LIST`
     /// command.  If there are no channels available to return,
     /// only the end reply MUST be sent.
-    RPL_LIST            = 322,
-    RPL_LISTEND         = 323,
+    RPL_LIST,
+    RPL_LISTEND,
 
     /// When sending a `TOPIC` message to determine the
     /// channel topic, one of two replies is sent.  If
     /// the topic is set, `RPL_TOPIC` is sent back else
     /// `RPL_NOTOPIC`.
-    RPL_UNIQOPIS        = 325,
-    RPL_CHANNELMODEIS   = 324,
-    RPL_NOTOPIC         = 331,
-    RPL_TOPIC           = 332,
+    RPL_UNIQOPIS,
+    RPL_CHANNELMODEIS,
+    RPL_NOTOPIC,
+    RPL_TOPIC,
+
+    /// Sent after `RPL_TOPIC` with the author of the topic and the
+    /// time it was set, in seconds since the epoch.
+    ///
+    /// `<channel> <nick> <setat>`
+    RPL_TOPICWHOTIME,
 
     /// Returned by the server to indicate that the
     /// attempted `INVITE` message was successful and is
     /// being passed onto the end client.
-    RPL_INVITING        = 341,
+    RPL_INVITING,
 
     /// Returned by a server answering a `SUMMON` message to
     /// indicate that it is summoning that user.
-    RPL_SUMMONING       = 342,
+    RPL_SUMMONING,
 
     /// When listing the 'invitations masks' for a given channel,
     /// a server is required to send the list back using the
@@ -112,8 +162,8 @@ pub enum ReplyCode {
     /// separate `RPL_INVITELIST` is sent for each active mask.
     /// After the masks have been listed (or if none present) a
     /// `RPL_ENDOFINVITELIST` MUST be sent.
-    RPL_INVITELIST      = 346,
-    RPL_ENDOFINVITELIST = 347,
+    RPL_INVITELIST,
+    RPL_ENDOFINVITELIST,
 
     /// When listing the 'exception masks' for a given channel,
     /// a server is required to send the list back using the
@@ -121,8 +171,8 @@ pub enum ReplyCode {
     /// separate `RPL_EXCEPTLIST` is sent for each active mask.
     /// After the masks have been listed (or if none present)
     /// a `RPL_ENDOFEXCEPTLIST` MUST be sent.
-    RPL_EXCEPTLIST      = 348,
-    RPL_ENDOFEXCEPTLIST = 349,
+    RPL_EXCEPTLIST,
+    RPL_ENDOFEXCEPTLIST,
 
     /// Reply by the server showing its version details.
     /// The `<version>` is the version of the software being
@@ -132,7 +182,7 @@ pub enum ReplyCode {
     ///
     /// The "comments" field may contain any comments about
     /// the version or further version details.
-    RPL_VERSION         = 351,
+    RPL_VERSION,
 
     /// The `RPL_WHOREPLY` and `RPL_ENDOFWHO` pair are used
     /// to answer a `WHO` message.  The `RPL_WHOREPLY` is only
@@ -141,8 +191,8 @@ pub enum ReplyCode {
     /// with a `WHO` message, a `RPL_ENDOFWHO` MUST be sent
     /// after processing each list item with `<name>` being
     /// the item.
-    RPL_WHOREPLY        = 352,
-    RPL_ENDOFWHO        = 315,
+    RPL_WHOREPLY,
+    RPL_ENDOFWHO,
 
     /// To reply to a `NAMES` message, a reply pair consisting
     /// of `RPL_NAMREPLY` and `RPL_ENDOFNAMES` is sent by the
@@ -153,14 +203,14 @@ pub enum ReplyCode {
     /// channels and contents are sent back in a series of
     /// `RPL_NAMEREPLY` messages with a `RPL_ENDOFNAMES` to mark
     /// the end.
-    RPL_NAMREPLY        = 353,
-    RPL_ENDOFNAMES      = 366,
+    RPL_NAMREPLY,
+    RPL_ENDOFNAMES,
 
     /// In replying to the `LINKS` message, a server MUST send
     /// replies back using the `RPL_LINKS` numeric and mark the
     /// end of the list using an `RPL_ENDOFLINKS` reply.
-    RPL_LINKS           = 364,
-    RPL_ENDOFLINKS      = 365,
+    RPL_LINKS,
+    RPL_ENDOFLINKS,
 
     /// When listing the active 'bans' for a given channel,
     /// a server is required to send the list back using the
@@ -168,15 +218,22 @@ pub enum ReplyCode {
     /// `RPL_BANLIST` is sent for each active banmask.  After the
     /// banmasks have been listed (or if none present) a
     /// `RPL_ENDOFBANLIST` MUST be sent.
-    RPL_BANLIST         = 367,
-    RPL_ENDOFBANLIST    = 368,
+    RPL_BANLIST,
+    RPL_ENDOFBANLIST,
+
+    /// When listing the quiet masks of a channel, with the mode
+    /// `q` on Charybdis based servers.
+    ///
+    /// `<channel> q <mask> [<who> <set-ts>]`
+    RPL_QUIETLIST,
+    RPL_ENDOFQUIETLIST,
 
     /// A server responding to an `INFO` message is required to
     /// send all its 'info' in a series of `RPL_INFO` messages
     /// with a `RPL_ENDOFINFO` reply to indicate the end of the
     /// replies.
-    RPL_INFO            = 371,
-    RPL_ENDOFINFO       = 374,
+    RPL_INFO,
+    RPL_ENDOFINFO,
 
     /// When responding to the `MOTD` message and the `MOTD` file
     /// is found, the file is displayed line by line, with
@@ -184,30 +241,30 @@ pub enum ReplyCode {
     /// `RPL_MOTD` format replies.  These MUST be surrounded
     /// by a `RPL_MOTDSTART` (before the `RPL_MOTD`s) and an
     /// `RPL_ENDOFMOTD` (after).
-    RPL_MOTDSTART       = 375,
-    RPL_MOTD            = 372,
-    RPL_ENDOFMOTD       = 376,
+    RPL_MOTDSTART,
+    RPL_MOTD,
+    RPL_ENDOFMOTD,
 
     /// `RPL_YOUREOPER` is sent back to a client which has
     /// just successfully issued an `OPER` message and gained
     /// operator status.
-    RPL_YOUREOPER       = 381,
+    RPL_YOUREOPER,
 
     /// If the `REHASH` option is used and an operator sends
     /// a `REHASH` message, an `RPL_REHASHING` is sent back to
     /// the operator.
-    RPL_REHASHING       = 382,
+    RPL_REHASHING,
 
     /// Sent by the server to a service upon successful
     /// registration.
-    RPL_YOURESERVICE    = 383,
+    RPL_YOURESERVICE,
 
     /// When replying to the `TIME` message, a server MUST send
     /// the reply using the `RPL_TIME` format above.  The string
     /// showing the time need only contain the correct day and
     /// time there.  There is no further requirement for the
     /// time string.
-    RPL_TIME            = 391,
+    RPL_TIME,
 
     /// If the `USERS` message is handled by a server, the
     /// replies `RPL_USERSTART`, `RPL_USERS`, `RPL_ENDOFUSERS` and
@@ -215,10 +272,16 @@ pub enum ReplyCode {
     /// first, following by either a sequence of `RPL_USERS`
     /// or a single `RPL_NOUSER`.  Following this is
     /// `RPL_ENDOFUSERS`.
-    RPL_USERSSTART      = 392,
-    RPL_USERS           = 393,
-    RPL_ENDOFUSERS      = 394,
-    RPL_NOUSERS         = 395,
+    RPL_USERSSTART,
+    RPL_USERS,
+    RPL_ENDOFUSERS,
+    RPL_NOUSERS,
+
+    /// Sent when the visible host of the user changes, such as
+    /// when a cloak is applied.
+    ///
+    /// `<host> :is now your displayed host`
+    RPL_HOSTHIDDEN,
 
     /// The `RPL_TRACE*` are all returned by the server in
     /// response to the `TRACE` message.  How many are
@@ -242,19 +305,19 @@ pub enum ReplyCode {
     /// being displayed anyway.
     ///
     /// `RPL_TRACEEND` is sent to indicate the end of the list.
-    RPL_TRACELINK       = 200,
-    RPL_TRACECONNECTING = 201,
-    RPL_TRACEHANDSHAKE  = 202,
-    RPL_TRACEUNKNOWN    = 203,
-    RPL_TRACEOPERATOR   = 204,
-    RPL_TRACEUSER       = 205,
-    RPL_TRACESERVER     = 206,
-    RPL_TRACESERVICE    = 207,
-    RPL_TRACENEWTYPE    = 208,
-    RPL_TRACECLASS      = 209,
-    RPL_TRACERECONNECT  = 210,
-    RPL_TRACELOG        = 261,
-    RPL_TRACEEND        = 262,
+    RPL_TRACELINK,
+    RPL_TRACECONNECTING,
+    RPL_TRACEHANDSHAKE,
+    RPL_TRACEUNKNOWN,
+    RPL_TRACEOPERATOR,
+    RPL_TRACEUSER,
+    RPL_TRACESERVER,
+    RPL_TRACESERVICE,
+    RPL_TRACENEWTYPE,
+    RPL_TRACECLASS,
+    RPL_TRACERECONNECT,
+    RPL_TRACELOG,
+    RPL_TRACEEND,
 
     /// reports statistics on a connection.  `<linkname>`
     /// identifies the particular connection, `<sendq>` is
@@ -266,23 +329,23 @@ pub enum ReplyCode {
     /// Kbytes>` for received data, respectively.  `<time
     /// open>` indicates how long ago the connection was
     /// opened, in seconds.
-    RPL_STATSLINKINFO   = 211,
+    RPL_STATSLINKINFO,
 
     /// reports statistics on commands usage.
-    RPL_STATSCOMMANDS   = 212,
+    RPL_STATSCOMMANDS,
 
-    RPL_ENDOFSTATS      = 219,
+    RPL_ENDOFSTATS,
 
     /// reports the server uptime.
-    RPL_STATSUPTIME     = 242,
+    RPL_STATSUPTIME,
 
     /// reports the allowed hosts from where user may become IRC
     /// operators.
-    RPL_STATSOLINE      = 243,
+    RPL_STATSOLINE,
 
     /// To answer a query about a client's own mode,
     /// `RPL_UMODEIS` is sent back.
-    RPL_UMODEIS         = 221,
+    RPL_UMODEIS,
 
     /// When listing services in reply to a `SERVLIST` message,
     /// a server is required to send the list back using the
@@ -290,8 +353,8 @@ pub enum ReplyCode {
     /// `RPL_SERVLIST` is sent for each service.  After the
     /// services have been listed (or if none present) a
     /// `RPL_SERVLISTEND` MUST be sent.
-    RPL_SERVLIST        = 234,
-    RPL_SERVLISTEND     = 235,
+    RPL_SERVLIST,
+    RPL_SERVLISTEND,
 
     /// In processing an `LUSERS` message, the server
     /// sends a set of replies from `RPL_LUSERCLIENT`,
@@ -301,11 +364,11 @@ pub enum ReplyCode {
     /// `RPL_LUSERCLIENT` and `RPL_LUSERME`.  The other
     /// replies are only sent back if a non-zero count
     /// is found for them.
-    RPL_LUSERCLIENT     = 251,
-    RPL_LUSEROP         = 252,
-    RPL_LUSERUNKNOWN    = 253,
-    RPL_LUSERCHANNELS   = 254,
-    RPL_LUSERME         = 255,
+    RPL_LUSERCLIENT,
+    RPL_LUSEROP,
+    RPL_LUSERUNKNOWN,
+    RPL_LUSERCHANNELS,
+    RPL_LUSERME,
 
     /// When replying to an `ADMIN` message, a server
     /// is expected to use replies `RPL_ADMINME`
@@ -317,44 +380,51 @@ pub enum ReplyCode {
     /// and finally the administrative contact for the
     /// server (an email address here is REQUIRED)
     /// in `RPL_ADMINEMAIL.
-    RPL_ADMINME         = 256,
-    RPL_ADMINLOC1       = 257,
-    RPL_ADMINLOC2       = 258,
-    RPL_ADMINEMAIL      = 259,
+    RPL_ADMINME,
+    RPL_ADMINLOC1,
+    RPL_ADMINLOC2,
+    RPL_ADMINEMAIL,
 
     /// When a server drops a command without processing it,
     /// it MUST use the reply `RPL_TRYAGAIN` to inform the
     /// originating client.
-    RPL_TRYAGAIN        = 263,
+    RPL_TRYAGAIN,
+
+    /// Replies to `LUSERS` with the current and maximum numbers of
+    /// users on the server and on the network.
+    ///
+    /// `[<u> <m>] :Current local users <u>, max <m>`
+    RPL_LOCALUSERS,
+    RPL_GLOBALUSERS,
 
     // Errors
 
     /// Used to indicate the nickname parameter supplied to a
     /// command is currently unused.
-    ERR_NOSUCHNICK      = 401,
+    ERR_NOSUCHNICK,
 
     /// Used to indicate the server name given currently
     /// does not exist.
-    ERR_NOSUCHSERVER    = 402,
+    ERR_NOSUCHSERVER,
 
     /// Used to indicate the given channel name is invalid.
-    ERR_NOSUCHCHANNEL   = 403,
+    ERR_NOSUCHCHANNEL,
 
     /// Sent to a user who is either (a) not on a channel
     /// which is mode `+n` or (b) not a chanop (or mode `+v`) on
     /// a channel which has mode `+m` set or where the user is
     /// banned and is trying to send a `PRIVMSG` message to
     /// that channel.
-    ERR_CANNOTSENDTOCHAN    = 404,
+    ERR_CANNOTSENDTOCHAN,
 
     /// Sent to a user when they have joined the maximum
     /// number of allowed channels and they try to join
     /// another channel.
-    ERR_TOOMANYCHANNELS = 405,
+    ERR_TOOMANYCHANNELS,
 
     /// Returned by `WHOWAS` to indicate there is no history
     /// information for that nickname.
-    ERR_WASNOSUCHNICK   = 406,
+    ERR_WASNOSUCHNICK,
 
     /// - Returned to a client which is attempting to send a
     ///   `PRIVMSG`/`NOTICE` using the `user@host` destination format
@@ -366,59 +436,59 @@ pub enum ReplyCode {
     /// - Returned to a client which is attempting to `JOIN` a safe
     ///   channel using the shortname when there are more than one
     ///   such channel.
-    ERR_TOOMANYTARGETS  = 407,
+    ERR_TOOMANYTARGETS,
 
     /// Returned to a client which is attempting to send a `SQUERY`
     /// to a service which does not exist.
-    ERR_NOSUCHSERVICE   = 408,
+    ERR_NOSUCHSERVICE,
 
     /// `PING` or `PONG` message missing the originator parameter.
-    ERR_NOORIGIN        = 409,
+    ERR_NOORIGIN,
 
     /// 412 - 415 are returned by `PRIVMSG` to indicate that
     /// the message wasn't delivered for some reason.
     /// `ERR_NOTOPLEVEL` and `ERR_WILDTOPLEVEL` are errors that
     /// are returned when an invalid use of
     /// `PRIVMSG $<server>` or `PRIVMSG #<host>` is attempted.
-    ERR_NORECIPIENT     = 411,
-    ERR_NOTEXTTOSEND    = 412,
-    ERR_NOTOPLEVEL      = 413,
-    ERR_WILDTOPLEVEL    = 414,
-    ERR_BADMASK         = 415,
+    ERR_NORECIPIENT,
+    ERR_NOTEXTTOSEND,
+    ERR_NOTOPLEVEL,
+    ERR_WILDTOPLEVEL,
+    ERR_BADMASK,
 
     /// Returned to a registered client to indicate that the
     /// command sent is unknown by the server.
-    ERR_UNKNOWNCOMMAND  = 421,
+    ERR_UNKNOWNCOMMAND,
 
     /// Server's `MOTD` file could not be opened by the server.
-    ERR_NOMOTD          = 422,
+    ERR_NOMOTD,
 
     /// Returned by a server in response to an `ADMIN` message
     /// when there is an error in finding the appropriate
     /// information.
-    ERR_NOADMININFO     = 423,
+    ERR_NOADMININFO,
 
     /// Generic error message used to report a failed file
     /// operation during the processing of a message.
-    ERR_FILEERROR       = 424,
+    ERR_FILEERROR,
 
     /// Returned when a nickname parameter expected for a
     /// command and isn't found.
-    ERR_NONICKNAMEGIVEN = 431,
+    ERR_NONICKNAMEGIVEN,
 
     /// Returned after receiving a `NICK` message which contains
     /// characters which do not fall in the defined set.
-    ERR_ERRONEUSNICKNAME    = 432,
+    ERR_ERRONEUSNICKNAME,
 
     /// Returned when a `NICK` message is processed that results
     /// in an attempt to change to a currently existing
     /// nickname.
-    ERR_NICKNAMEINUSE   = 433,
+    ERR_NICKNAMEINUSE,
 
     /// Returned by a server to a client when it detects a
     /// nickname collision (registered of a `NICK` that
     /// already exists by another server).
-    ERR_NICKCOLLISION   = 436,
+    ERR_NICKCOLLISION,
 
     /// - Returned by a server to a user trying to join a channel
     ///   currently blocked by the channel delay mechanism.
@@ -426,152 +496,433 @@ pub enum ReplyCode {
     /// - Returned by a server to a user trying to change nickname
     ///   when the desired nickname is blocked by the nick delay
     ///   mechanism.
-    ERR_UNAVAILRESOURCE = 437,
+    ERR_UNAVAILRESOURCE,
 
     /// Returned by the server to indicate that the target
     /// user of the command is not on the given channel.
-    ERR_USERNOTINCHANNEL    = 441,
+    ERR_USERNOTINCHANNEL,
 
     /// Returned by the server whenever a client tries to
     /// perform a channel affecting command for which the
     /// client isn't a member.
-    ERR_NOTONCHANNEL    = 442,
+    ERR_NOTONCHANNEL,
 
     /// Returned when a client tries to invite a user to a
     /// channel they are already on.
-    ERR_USERONCHANNEL   = 443,
+    ERR_USERONCHANNEL,
 
     /// Returned by the summon after a `SUMMON` command for a
     /// user was unable to be performed since they were not
     /// logged in.
-    ERR_NOLOGIN         = 444,
+    ERR_NOLOGIN,
 
     /// Returned as a response to the `SUMMON` command.  MUST be
     /// returned by any server which doesn't implement it.
-    ERR_SUMMONDISABLED  = 445,
+    ERR_SUMMONDISABLED,
 
     /// Returned as a response to the `USERS` command.  MUST be
     /// returned by any server which does not implement it.
-    ERR_USERSDISABLED   = 446,
+    ERR_USERSDISABLED,
 
     /// Returned by the server to indicate that the client
     /// MUST be registered before the server will allow it
     /// to be parsed in detail.
-    ERR_NOTREGISTERED   = 451,
+    ERR_NOTREGISTERED,
 
     /// Returned by the server by numerous commands to
     /// indicate to the client that it didn't supply enough
     /// parameters.
-    ERR_NEEDMOREPARAMS  = 461,
+    ERR_NEEDMOREPARAMS,
 
     /// Returned by the server to any link which tries to
     /// change part of the registered details (such as
     /// password or user details from second `USER` message).
-    ERR_ALREADYREGISTRED    = 462,
+    ERR_ALREADYREGISTRED,
 
     /// Returned to a client which attempts to register with
     /// a server which does not been setup to allow
     /// connections from the host the attempted connection
     /// is tried.
-    ERR_NOPERMFORHOST   = 463,
+    ERR_NOPERMFORHOST,
 
     /// Returned to indicate a failed attempt at registering
     /// a connection for which a password was required and
     /// was either not given or incorrect.
-    ERR_PASSWDMISMATCH  = 464,
+    ERR_PASSWDMISMATCH,
 
     /// Returned after an attempt to connect and register
     /// yourself with a server which has been setup to
     /// explicitly deny connections to you.
-    ERR_YOUREBANNEDCREEP    = 465,
+    ERR_YOUREBANNEDCREEP,
 
     /// Sent by a server to a user to inform that access to the
     /// server will soon be denied.
-    ERR_YOUWILLBEBANNED = 466,
-
-    ERR_KEYSET          = 467,
-    ERR_CHANNELISFULL   = 471,
-    ERR_UNKNOWNMODE     = 472,
-    ERR_INVITEONLYCHAN  = 473,
-    ERR_BANNEDFROMCHAN  = 474,
-    ERR_BADCHANNELKEY   = 475,
-    ERR_BADCHANMASK     = 476,
-    ERR_NOCHANMODES     = 477,
-    ERR_BANLISTFULL     = 478,
+    ERR_YOUWILLBEBANNED,
+
+    ERR_KEYSET,
+    ERR_CHANNELISFULL,
+    ERR_UNKNOWNMODE,
+    ERR_INVITEONLYCHAN,
+    ERR_BANNEDFROMCHAN,
+    ERR_BADCHANNELKEY,
+    ERR_BADCHANMASK,
+    ERR_NOCHANMODES,
+    ERR_BANLISTFULL,
 
     /// Any command requiring operator privileges to operate
     /// MUST return this error to indicate the attempt was
     /// unsuccessful.
-    ERR_NOPRIVILEGES    = 481,
+    ERR_NOPRIVILEGES,
 
     /// Any command requiring 'chanop' privileges (such as
     /// `MODE` messages) MUST return this error if the client
     /// making the attempt is not a chanop on the specified
     /// channel.
-    ERR_CHANOPRIVSNEEDED    = 482,
+    ERR_CHANOPRIVSNEEDED,
 
     /// Any attempts to use the `KILL` command on a server
     /// are to be refused and this error returned directly
     /// to the client.
-    ERR_CANTKILLSERVER  = 483,
+    ERR_CANTKILLSERVER,
 
     /// Sent by the server to a user upon connection to indicate
     /// the restricted nature of the connection (user mode `+r`).
-    ERR_RESTRICTED      = 484,
+    ERR_RESTRICTED,
 
     /// Any `MODE` requiring "channel creator" privileges MUST
     /// return this error if the client making the attempt is not
     /// a chanop on the specified channel.
-    ERR_UNIQOPPRIVSNEEDED   = 485,
+    ERR_UNIQOPPRIVSNEEDED,
 
     /// If a client sends an `OPER` message and the server has
     /// not been configured to allow connections from the
     /// client's host as an operator, this error MUST be
     /// returned.
-    ERR_NOOPERHOST      = 491,
+    ERR_NOOPERHOST,
 
     /// Returned by the server to indicate that a `MODE`
     /// message was sent with a nickname parameter and that
     /// the a mode flag sent was not recognized.
-    ERR_UMODEUNKNOWNFLAG    = 501,
+    ERR_UMODEUNKNOWNFLAG,
 
     /// Error sent to any user trying to view or change the
     /// user mode for a user other than themselves.
-    ERR_USERSDONTMATCH  = 502,
+    ERR_USERSDONTMATCH,
+
+    /// Replies to `MONITOR` when monitored nicknames come online
+    /// or go offline.
+    ///
+    /// `:<nick>[!<user>@<host>][,<nick>[!<user>@<host>]]*`
+    RPL_MONONLINE,
+    RPL_MONOFFLINE,
+
+    /// Replies to `MONITOR L` with the monitored nicknames.
+    ///
+    /// `:<target>[,<target>]*`
+    RPL_MONLIST,
+    RPL_ENDOFMONLIST,
+
+    /// Sent when `MONITOR +` would exceed the limit of monitored
+    /// nicknames.
+    ///
+    /// `<limit> <targets> :Monitor list is full.`
+    ERR_MONLISTFULL,
+
+    /// Sent when the user logs into an account, with SASL or
+    /// services.
+    ///
+    /// `<nick>!<user>@<host> <account> :You are now logged in as <user>`
+    RPL_LOGGEDIN,
+
+    /// Sent when the user logs out of their account.
+    RPL_LOGGEDOUT,
+
+    /// Sent when SASL authentication fails because the account
+    /// is locked or held.
+    ERR_NICKLOCKED,
+
+    /// Sent when SASL authentication succeeds.
+    RPL_SASLSUCCESS,
+
+    /// Sent when SASL authentication fails because of invalid
+    /// credentials or other errors.
+    ERR_SASLFAIL,
+
+    /// Sent when an `AUTHENTICATE` message is longer than 400
+    /// bytes.
+    ERR_SASLTOOLONG,
+
+    /// Sent when the client aborts SASL authentication with
+    /// `AUTHENTICATE *`.
+    ERR_SASLABORTED,
+
+    /// Sent when the client attempts SASL authentication after
+    /// having completed it.
+    ERR_SASLALREADY,
+
+    /// Lists the mechanisms supported by the server, after an
+    /// unsupported mechanism was requested.
+    ///
+    /// `<mechanisms> :are available SASL mechanisms`
+    RPL_SASLMECHS,
+
+    /// Any other three-digit numeric, from servers with
+    /// extensions this crate does not know about.
+    ///
+    /// Created by `ReplyCode::from_u16` for numerics without a
+    /// variant, so that known numerics are never `Other`.
+    Other(u16),
 }
 
 impl ReplyCode {
     pub fn as_u16(&self) -> u16 {
-        *self as u16
+        match *self {
+            ReplyCode::RPL_WELCOME           => 1,
+            ReplyCode::RPL_YOURHOST          => 2,
+            ReplyCode::RPL_CREATED           => 3,
+            ReplyCode::RPL_MYINFO            => 4,
+            ReplyCode::RPL_ISUPPORT          => 5,
+            ReplyCode::RPL_BOUNCE            => 10,
+            ReplyCode::RPL_YOURID            => 42,
+
+            ReplyCode::RPL_TRACELINK         => 200,
+            ReplyCode::RPL_TRACECONNECTING   => 201,
+            ReplyCode::RPL_TRACEHANDSHAKE    => 202,
+            ReplyCode::RPL_TRACEUNKNOWN      => 203,
+            ReplyCode::RPL_TRACEOPERATOR     => 204,
+            ReplyCode::RPL_TRACEUSER         => 205,
+            ReplyCode::RPL_TRACESERVER       => 206,
+            ReplyCode::RPL_TRACESERVICE      => 207,
+            ReplyCode::RPL_TRACENEWTYPE      => 208,
+            ReplyCode::RPL_TRACECLASS        => 209,
+            ReplyCode::RPL_TRACERECONNECT    => 210,
+            ReplyCode::RPL_STATSLINKINFO     => 211,
+            ReplyCode::RPL_STATSCOMMANDS     => 212,
+            ReplyCode::RPL_ENDOFSTATS        => 219,
+            ReplyCode::RPL_UMODEIS           => 221,
+            ReplyCode::RPL_SERVLIST          => 234,
+            ReplyCode::RPL_SERVLISTEND       => 235,
+            ReplyCode::RPL_STATSUPTIME       => 242,
+            ReplyCode::RPL_STATSOLINE        => 243,
+            ReplyCode::RPL_LUSERCLIENT       => 251,
+            ReplyCode::RPL_LUSEROP           => 252,
+            ReplyCode::RPL_LUSERUNKNOWN      => 253,
+            ReplyCode::RPL_LUSERCHANNELS     => 254,
+            ReplyCode::RPL_LUSERME           => 255,
+            ReplyCode::RPL_ADMINME           => 256,
+            ReplyCode::RPL_ADMINLOC1         => 257,
+            ReplyCode::RPL_ADMINLOC2         => 258,
+            ReplyCode::RPL_ADMINEMAIL        => 259,
+            ReplyCode::RPL_TRACELOG          => 261,
+            ReplyCode::RPL_TRACEEND          => 262,
+            ReplyCode::RPL_TRYAGAIN          => 263,
+            ReplyCode::RPL_LOCALUSERS        => 265,
+            ReplyCode::RPL_GLOBALUSERS       => 266,
+            ReplyCode::RPL_WHOISCERTFP       => 276,
+
+            ReplyCode::RPL_AWAY              => 301,
+            ReplyCode::RPL_USERHOST          => 302,
+            ReplyCode::RPL_ISON              => 303,
+            ReplyCode::RPL_UNAWAY            => 305,
+            ReplyCode::RPL_NOWAWAY           => 306,
+            ReplyCode::RPL_WHOISUSER         => 311,
+            ReplyCode::RPL_WHOISSERVER       => 312,
+            ReplyCode::RPL_WHOISOPERATOR     => 313,
+            ReplyCode::RPL_WHOWASUSER        => 314,
+            ReplyCode::RPL_ENDOFWHO          => 315,
+            ReplyCode::RPL_WHOISIDLE         => 317,
+            ReplyCode::RPL_ENDOFWHOIS        => 318,
+            ReplyCode::RPL_WHOISCHANNELS     => 319,
+            ReplyCode::RPL_LISTSTART         => 321,
+            ReplyCode::RPL_LIST              => 322,
+            ReplyCode::RPL_LISTEND           => 323,
+            ReplyCode::RPL_CHANNELMODEIS     => 324,
+            ReplyCode::RPL_UNIQOPIS          => 325,
+            ReplyCode::RPL_WHOISACCOUNT      => 330,
+            ReplyCode::RPL_NOTOPIC           => 331,
+            ReplyCode::RPL_TOPIC             => 332,
+            ReplyCode::RPL_TOPICWHOTIME      => 333,
+            ReplyCode::RPL_WHOISACTUALLY     => 338,
+            ReplyCode::RPL_INVITING          => 341,
+            ReplyCode::RPL_SUMMONING         => 342,
+            ReplyCode::RPL_INVITELIST        => 346,
+            ReplyCode::RPL_ENDOFINVITELIST   => 347,
+            ReplyCode::RPL_EXCEPTLIST        => 348,
+            ReplyCode::RPL_ENDOFEXCEPTLIST   => 349,
+            ReplyCode::RPL_VERSION           => 351,
+            ReplyCode::RPL_WHOREPLY          => 352,
+            ReplyCode::RPL_NAMREPLY          => 353,
+            ReplyCode::RPL_LINKS             => 364,
+            ReplyCode::RPL_ENDOFLINKS        => 365,
+            ReplyCode::RPL_ENDOFNAMES        => 366,
+            ReplyCode::RPL_BANLIST           => 367,
+            ReplyCode::RPL_ENDOFBANLIST      => 368,
+            ReplyCode::RPL_ENDOFWHOWAS       => 369,
+            ReplyCode::RPL_INFO              => 371,
+            ReplyCode::RPL_MOTD              => 372,
+            ReplyCode::RPL_ENDOFINFO         => 374,
+            ReplyCode::RPL_MOTDSTART         => 375,
+            ReplyCode::RPL_ENDOFMOTD         => 376,
+            ReplyCode::RPL_WHOISHOST         => 378,
+            ReplyCode::RPL_YOUREOPER         => 381,
+            ReplyCode::RPL_REHASHING         => 382,
+            ReplyCode::RPL_YOURESERVICE      => 383,
+            ReplyCode::RPL_TIME              => 391,
+            ReplyCode::RPL_USERSSTART        => 392,
+            ReplyCode::RPL_USERS             => 393,
+            ReplyCode::RPL_ENDOFUSERS        => 394,
+            ReplyCode::RPL_NOUSERS           => 395,
+            ReplyCode::RPL_HOSTHIDDEN        => 396,
+
+            ReplyCode::ERR_NOSUCHNICK        => 401,
+            ReplyCode::ERR_NOSUCHSERVER      => 402,
+            ReplyCode::ERR_NOSUCHCHANNEL     => 403,
+            ReplyCode::ERR_CANNOTSENDTOCHAN  => 404,
+            ReplyCode::ERR_TOOMANYCHANNELS   => 405,
+            ReplyCode::ERR_WASNOSUCHNICK     => 406,
+            ReplyCode::ERR_TOOMANYTARGETS    => 407,
+            ReplyCode::ERR_NOSUCHSERVICE     => 408,
+            ReplyCode::ERR_NOORIGIN          => 409,
+            ReplyCode::ERR_NORECIPIENT       => 411,
+            ReplyCode::ERR_NOTEXTTOSEND      => 412,
+            ReplyCode::ERR_NOTOPLEVEL        => 413,
+            ReplyCode::ERR_WILDTOPLEVEL      => 414,
+            ReplyCode::ERR_BADMASK           => 415,
+            ReplyCode::ERR_UNKNOWNCOMMAND    => 421,
+            ReplyCode::ERR_NOMOTD            => 422,
+            ReplyCode::ERR_NOADMININFO       => 423,
+            ReplyCode::ERR_FILEERROR         => 424,
+            ReplyCode::ERR_NONICKNAMEGIVEN   => 431,
+            ReplyCode::ERR_ERRONEUSNICKNAME  => 432,
+            ReplyCode::ERR_NICKNAMEINUSE     => 433,
+            ReplyCode::ERR_NICKCOLLISION     => 436,
+            ReplyCode::ERR_UNAVAILRESOURCE   => 437,
+            ReplyCode::ERR_USERNOTINCHANNEL  => 441,
+            ReplyCode::ERR_NOTONCHANNEL      => 442,
+            ReplyCode::ERR_USERONCHANNEL     => 443,
+            ReplyCode::ERR_NOLOGIN           => 444,
+            ReplyCode::ERR_SUMMONDISABLED    => 445,
+            ReplyCode::ERR_USERSDISABLED     => 446,
+            ReplyCode::ERR_NOTREGISTERED     => 451,
+            ReplyCode::ERR_NEEDMOREPARAMS    => 461,
+            ReplyCode::ERR_ALREADYREGISTRED  => 462,
+            ReplyCode::ERR_NOPERMFORHOST     => 463,
+            ReplyCode::ERR_PASSWDMISMATCH    => 464,
+            ReplyCode::ERR_YOUREBANNEDCREEP  => 465,
+            ReplyCode::ERR_YOUWILLBEBANNED   => 466,
+            ReplyCode::ERR_KEYSET            => 467,
+            ReplyCode::ERR_CHANNELISFULL     => 471,
+            ReplyCode::ERR_UNKNOWNMODE       => 472,
+            ReplyCode::ERR_INVITEONLYCHAN    => 473,
+            ReplyCode::ERR_BANNEDFROMCHAN    => 474,
+            ReplyCode::ERR_BADCHANNELKEY     => 475,
+            ReplyCode::ERR_BADCHANMASK       => 476,
+            ReplyCode::ERR_NOCHANMODES       => 477,
+            ReplyCode::ERR_BANLISTFULL       => 478,
+            ReplyCode::ERR_NOPRIVILEGES      => 481,
+            ReplyCode::ERR_CHANOPRIVSNEEDED  => 482,
+            ReplyCode::ERR_CANTKILLSERVER    => 483,
+            ReplyCode::ERR_RESTRICTED        => 484,
+            ReplyCode::ERR_UNIQOPPRIVSNEEDED => 485,
+            ReplyCode::ERR_NOOPERHOST        => 491,
+
+            ReplyCode::ERR_UMODEUNKNOWNFLAG  => 501,
+            ReplyCode::ERR_USERSDONTMATCH    => 502,
+
+            ReplyCode::RPL_WHOISSECURE       => 671,
+
+            ReplyCode::RPL_QUIETLIST         => 728,
+            ReplyCode::RPL_ENDOFQUIETLIST    => 729,
+            ReplyCode::RPL_MONONLINE         => 730,
+            ReplyCode::RPL_MONOFFLINE        => 731,
+            ReplyCode::RPL_MONLIST           => 732,
+            ReplyCode::RPL_ENDOFMONLIST      => 733,
+            ReplyCode::ERR_MONLISTFULL       => 734,
+
+            ReplyCode::RPL_LOGGEDIN          => 900,
+            ReplyCode::RPL_LOGGEDOUT         => 901,
+            ReplyCode::ERR_NICKLOCKED        => 902,
+            ReplyCode::RPL_SASLSUCCESS       => 903,
+            ReplyCode::ERR_SASLFAIL          => 904,
+            ReplyCode::ERR_SASLTOOLONG       => 905,
+            ReplyCode::ERR_SASLABORTED       => 906,
+            ReplyCode::ERR_SASLALREADY       => 907,
+            ReplyCode::RPL_SASLMECHS         => 908,
+
+            ReplyCode::Other(c)              => c,
+        }
     }
 
+    /// Get the code of a numeric, `None` if it has more than three
+    /// digits
     pub fn from_u16(c: u16) -> Option<ReplyCode> {
         match c {
             1   => Some(ReplyCode::RPL_WELCOME),
             2   => Some(ReplyCode::RPL_YOURHOST),
             3   => Some(ReplyCode::RPL_CREATED),
             4   => Some(ReplyCode::RPL_MYINFO),
-            5   => Some(ReplyCode::RPL_BOUNCE),
+            5   => Some(ReplyCode::RPL_ISUPPORT),
+            10  => Some(ReplyCode::RPL_BOUNCE),
+            42  => Some(ReplyCode::RPL_YOURID),
+
+            200 => Some(ReplyCode::RPL_TRACELINK),
+            201 => Some(ReplyCode::RPL_TRACECONNECTING),
+            202 => Some(ReplyCode::RPL_TRACEHANDSHAKE),
+            203 => Some(ReplyCode::RPL_TRACEUNKNOWN),
+            204 => Some(ReplyCode::RPL_TRACEOPERATOR),
+            205 => Some(ReplyCode::RPL_TRACEUSER),
+            206 => Some(ReplyCode::RPL_TRACESERVER),
+            207 => Some(ReplyCode::RPL_TRACESERVICE),
+            208 => Some(ReplyCode::RPL_TRACENEWTYPE),
+            209 => Some(ReplyCode::RPL_TRACECLASS),
+            210 => Some(ReplyCode::RPL_TRACERECONNECT),
+            211 => Some(ReplyCode::RPL_STATSLINKINFO),
+            212 => Some(ReplyCode::RPL_STATSCOMMANDS),
+            219 => Some(ReplyCode::RPL_ENDOFSTATS),
+            221 => Some(ReplyCode::RPL_UMODEIS),
+            234 => Some(ReplyCode::RPL_SERVLIST),
+            235 => Some(ReplyCode::RPL_SERVLISTEND),
+            242 => Some(ReplyCode::RPL_STATSUPTIME),
+            243 => Some(ReplyCode::RPL_STATSOLINE),
+            251 => Some(ReplyCode::RPL_LUSERCLIENT),
+            252 => Some(ReplyCode::RPL_LUSEROP),
+            253 => Some(ReplyCode::RPL_LUSERUNKNOWN),
+            254 => Some(ReplyCode::RPL_LUSERCHANNELS),
+            255 => Some(ReplyCode::RPL_LUSERME),
+            256 => Some(ReplyCode::RPL_ADMINME),
+            257 => Some(ReplyCode::RPL_ADMINLOC1),
+            258 => Some(ReplyCode::RPL_ADMINLOC2),
+            259 => Some(ReplyCode::RPL_ADMINEMAIL),
+            261 => Some(ReplyCode::RPL_TRACELOG),
+            262 => Some(ReplyCode::RPL_TRACEEND),
+            263 => Some(ReplyCode::RPL_TRYAGAIN),
+            265 => Some(ReplyCode::RPL_LOCALUSERS),
+            266 => Some(ReplyCode::RPL_GLOBALUSERS),
+            276 => Some(ReplyCode::RPL_WHOISCERTFP),
+
+            301 => Some(ReplyCode::RPL_AWAY),
             302 => Some(ReplyCode::RPL_USERHOST),
             303 => Some(ReplyCode::RPL_ISON),
-            301 => Some(ReplyCode::RPL_AWAY),
             305 => Some(ReplyCode::RPL_UNAWAY),
             306 => Some(ReplyCode::RPL_NOWAWAY),
             311 => Some(ReplyCode::RPL_WHOISUSER),
             312 => Some(ReplyCode::RPL_WHOISSERVER),
             313 => Some(ReplyCode::RPL_WHOISOPERATOR),
+            314 => Some(ReplyCode::RPL_WHOWASUSER),
+            315 => Some(ReplyCode::RPL_ENDOFWHO),
             317 => Some(ReplyCode::RPL_WHOISIDLE),
             318 => Some(ReplyCode::RPL_ENDOFWHOIS),
             319 => Some(ReplyCode::RPL_WHOISCHANNELS),
-            314 => Some(ReplyCode::RPL_WHOWASUSER),
-            369 => Some(ReplyCode::RPL_ENDOFWHOWAS),
             321 => Some(ReplyCode::RPL_LISTSTART),
             322 => Some(ReplyCode::RPL_LIST),
             323 => Some(ReplyCode::RPL_LISTEND),
-            325 => Some(ReplyCode::RPL_UNIQOPIS),
             324 => Some(ReplyCode::RPL_CHANNELMODEIS),
+            325 => Some(ReplyCode::RPL_UNIQOPIS),
+            330 => Some(ReplyCode::RPL_WHOISACCOUNT),
             331 => Some(ReplyCode::RPL_NOTOPIC),
             332 => Some(ReplyCode::RPL_TOPIC),
+            333 => Some(ReplyCode::RPL_TOPICWHOTIME),
+            338 => Some(ReplyCode::RPL_WHOISACTUALLY),
             341 => Some(ReplyCode::RPL_INVITING),
             342 => Some(ReplyCode::RPL_SUMMONING),
             346 => Some(ReplyCode::RPL_INVITELIST),
@@ -580,18 +931,19 @@ impl ReplyCode {
             349 => Some(ReplyCode::RPL_ENDOFEXCEPTLIST),
             351 => Some(ReplyCode::RPL_VERSION),
             352 => Some(ReplyCode::RPL_WHOREPLY),
-            315 => Some(ReplyCode::RPL_ENDOFWHO),
             353 => Some(ReplyCode::RPL_NAMREPLY),
-            366 => Some(ReplyCode::RPL_ENDOFNAMES),
             364 => Some(ReplyCode::RPL_LINKS),
             365 => Some(ReplyCode::RPL_ENDOFLINKS),
+            366 => Some(ReplyCode::RPL_ENDOFNAMES),
             367 => Some(ReplyCode::RPL_BANLIST),
             368 => Some(ReplyCode::RPL_ENDOFBANLIST),
+            369 => Some(ReplyCode::RPL_ENDOFWHOWAS),
             371 => Some(ReplyCode::RPL_INFO),
+            372 => Some(ReplyCode::RPL_MOTD),
             374 => Some(ReplyCode::RPL_ENDOFINFO),
             375 => Some(ReplyCode::RPL_MOTDSTART),
-            372 => Some(ReplyCode::RPL_MOTD),
             376 => Some(ReplyCode::RPL_ENDOFMOTD),
+            378 => Some(ReplyCode::RPL_WHOISHOST),
             381 => Some(ReplyCode::RPL_YOUREOPER),
             382 => Some(ReplyCode::RPL_REHASHING),
             383 => Some(ReplyCode::RPL_YOURESERVICE),
@@ -600,37 +952,7 @@ impl ReplyCode {
             393 => Some(ReplyCode::RPL_USERS),
             394 => Some(ReplyCode::RPL_ENDOFUSERS),
             395 => Some(ReplyCode::RPL_NOUSERS),
-            200 => Some(ReplyCode::RPL_TRACELINK),
-            201 => Some(ReplyCode::RPL_TRACECONNECTING),
-            202 => Some(ReplyCode::RPL_TRACEHANDSHAKE),
-            203 => Some(ReplyCode::RPL_TRACEUNKNOWN),
-            204 => Some(ReplyCode::RPL_TRACEOPERATOR),
-            205 => Some(ReplyCode::RPL_TRACEUSER),
-            206 => Some(ReplyCode::RPL_TRACESERVER),
-            207 => Some(ReplyCode::RPL_TRACESERVICE),
-            208 => Some(ReplyCode::RPL_TRACENEWTYPE),
-            209 => Some(ReplyCode::RPL_TRACECLASS),
-            210 => Some(ReplyCode::RPL_TRACERECONNECT),
-            261 => Some(ReplyCode::RPL_TRACELOG),
-            262 => Some(ReplyCode::RPL_TRACEEND),
-            211 => Some(ReplyCode::RPL_STATSLINKINFO),
-            212 => Some(ReplyCode::RPL_STATSCOMMANDS),
-            219 => Some(ReplyCode::RPL_ENDOFSTATS),
-            242 => Some(ReplyCode::RPL_STATSUPTIME),
-            243 => Some(ReplyCode::RPL_STATSOLINE),
-            221 => Some(ReplyCode::RPL_UMODEIS),
-            234 => Some(ReplyCode::RPL_SERVLIST),
-            235 => Some(ReplyCode::RPL_SERVLISTEND),
-            251 => Some(ReplyCode::RPL_LUSERCLIENT),
-            252 => Some(ReplyCode::RPL_LUSEROP),
-            253 => Some(ReplyCode::RPL_LUSERUNKNOWN),
-            254 => Some(ReplyCode::RPL_LUSERCHANNELS),
-            255 => Some(ReplyCode::RPL_LUSERME),
-            256 => Some(ReplyCode::RPL_ADMINME),
-            257 => Some(ReplyCode::RPL_ADMINLOC1),
-            258 => Some(ReplyCode::RPL_ADMINLOC2),
-            259 => Some(ReplyCode::RPL_ADMINEMAIL),
-            263 => Some(ReplyCode::RPL_TRYAGAIN),
+            396 => Some(ReplyCode::RPL_HOSTHIDDEN),
 
             401 => Some(ReplyCode::ERR_NOSUCHNICK),
             402 => Some(ReplyCode::ERR_NOSUCHSERVER),
@@ -683,12 +1005,39 @@ impl ReplyCode {
             484 => Some(ReplyCode::ERR_RESTRICTED),
             485 => Some(ReplyCode::ERR_UNIQOPPRIVSNEEDED),
             491 => Some(ReplyCode::ERR_NOOPERHOST),
+
             501 => Some(ReplyCode::ERR_UMODEUNKNOWNFLAG),
             502 => Some(ReplyCode::ERR_USERSDONTMATCH),
 
+            671 => Some(ReplyCode::RPL_WHOISSECURE),
+
+            728 => Some(ReplyCode::RPL_QUIETLIST),
+            729 => Some(ReplyCode::RPL_ENDOFQUIETLIST),
+            730 => Some(ReplyCode::RPL_MONONLINE),
+            731 => Some(ReplyCode::RPL_MONOFFLINE),
+            732 => Some(ReplyCode::RPL_MONLIST),
+            733 => Some(ReplyCode::RPL_ENDOFMONLIST),
+            734 => Some(ReplyCode::ERR_MONLISTFULL),
+
+            900 => Some(ReplyCode::RPL_LOGGEDIN),
+            901 => Some(ReplyCode::RPL_LOGGEDOUT),
+            902 => Some(ReplyCode::ERR_NICKLOCKED),
+            903 => Some(ReplyCode::RPL_SASLSUCCESS),
+            904 => Some(ReplyCode::ERR_SASLFAIL),
+            905 => Some(ReplyCode::ERR_SASLTOOLONG),
+            906 => Some(ReplyCode::ERR_SASLABORTED),
+            907 => Some(ReplyCode::ERR_SASLALREADY),
+            908 => Some(ReplyCode::RPL_SASLMECHS),
+
+            0..=999 => Some(ReplyCode::Other(c)),
             _   => None,
         }
     }
+
+    /// Whether the numeric has a named variant
+    pub fn is_known(&self) -> bool {
+        !matches!(*self, ReplyCode::Other(..))
+    }
 }
 
 impl fmt::Display for ReplyCode {
@@ -697,19 +1046,19 @@ impl fmt::Display for ReplyCode {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Reply<'a> {
     reply_code: ReplyCode,
-    target: &'a str,
-    params: &'a str,
+    target: Cow<'a, str>,
+    params: Cow<'a, str>,
 }
 
 impl<'a> Reply<'a> {
     pub fn new(code: ReplyCode, target: &'a str, params: &'a str) -> Reply<'a> {
         Reply {
             reply_code: code,
-            target: target,
-            params: params,
+            target: Cow::Borrowed(target),
+            params: Cow::Borrowed(params),
         }
     }
 
@@ -717,12 +1066,47 @@ impl<'a> Reply<'a> {
         self.reply_code
     }
 
-    pub fn target(&self) -> &'a str {
-        self.target
+    pub fn target(&self) -> &str {
+        &self.target
     }
 
-    pub fn parameters(&self) -> MessageParamIter<'a> {
-        MessageParamIter::wrap(self.params)
+    pub fn parameters(&self) -> MessageParamIter<'_> {
+        MessageParamIter::wrap(&self.params)
+    }
+
+    /// Get the user modes of a `RPL_UMODEIS` reply
+    pub fn user_modes(&self) -> Option<Result<UserModeSet, ParseMessageError>> {
+        if self.reply_code != ReplyCode::RPL_UMODEIS {
+            return None;
+        }
+
+        Some(match self.parameters().next() {
+            None => Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
+                                               "RPL_UMODEIS reply needs a mode string")),
+            Some(modes) => modes.parse(),
+        })
+    }
+
+    /// Parse the parameters of the replies with a known layout, such as
+    /// `RPL_WHOISUSER` or `RPL_NAMREPLY`
+    pub fn payload(&self) -> Option<Result<ReplyPayload<'_>, ParseMessageError>> {
+        ReplyPayload::from_reply(self)
+    }
+
+    pub fn into_owned(self) -> Reply<'static> {
+        Reply {
+            reply_code: self.reply_code,
+            target: Cow::Owned(self.target.into_owned()),
+            params: Cow::Owned(self.params.into_owned()),
+        }
+    }
+
+    pub fn as_borrowed<'b>(&'b self) -> Reply<'b> {
+        Reply {
+            reply_code: self.reply_code,
+            target: Cow::Borrowed(&self.target[..]),
+            params: Cow::Borrowed(&self.params[..]),
+        }
     }
 }
 
@@ -740,31 +1124,118 @@ impl<'a> fmt::Display for Reply<'a> {
 
 impl<'a> IrcMessage<'a> for Reply<'a> {
     fn from_raw(raw: &RawMessage<'a>) -> Result<Reply<'a>, ParseMessageError> {
-        let cmd = match raw.command().parse::<u16>() {
-            Err(..) => {
+        // Every three-digit numeric is a reply, the unknown ones with
+        // `ReplyCode::Other`
+        let command = raw.command();
+        let code = match command.parse::<u16>().ok().and_then(ReplyCode::from_u16) {
+            Some(code) if command.len() == 3 && command.bytes().all(|b| b.is_ascii_digit()) => code,
+            _ => {
                 return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidReplyCode,
-                                                  "Reply should be a numeric number"))
+                                                  "Reply should be a three-digit number"));
             },
-            Ok(cmd) => cmd,
         };
 
-        let code = match ReplyCode::from_u16(cmd) {
-            None => {
-                return Err(ParseMessageError::new(ParseMessageErrorKind::UnrecognizedReply,
-                                                  "Unrecognized reply"));
-            },
-            Some(code) => code,
-        };
-
-        let mut params = raw.parameters();
+        let mut params = raw.cow_parameters();
         let target = match params.next() {
             None => {
                 return Err(ParseMessageError::new(ParseMessageErrorKind::NeedMoreParams,
-                                                  "Require a target"));
+                                                  "Require a target").with_raw(raw));
             },
             Some(t) => t,
         };
 
-        Ok(Reply::new(code, target, params.get()))
+        Ok(Reply {
+            reply_code: code,
+            target: target,
+            params: params.get(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::{IrcMessage, Message, Body};
+
+    #[test]
+    fn test_reply_roundtrip() {
+        let corpus = [
+            "001 WiZ :Welcome to the Internet Relay Network WiZ!jto@tolsun.oulu.fi",
+            "005 WiZ CHANTYPES=# PREFIX=(ov)@+ :are supported by this server",
+            "221 WiZ +iw",
+            "353 WiZ = #rust :@WiZ +jto trillian",
+            "366 WiZ #rust :End of NAMES list",
+            "401 WiZ nobody :No such nick/channel",
+            "433 * WiZ :Nickname is already in use",
+            "900 WiZ WiZ!jto@tolsun.oulu.fi jto :You are now logged in as jto",
+            "903 WiZ :SASL authentication successful",
+            "908 WiZ PLAIN,EXTERNAL :are available SASL mechanisms",
+            "042 WiZ 9XAAAAAAA :your unique ID",
+            "266 WiZ 1024 2048 :Current global users 1024, max 2048",
+            "333 WiZ #rust jto 1792234861",
+            "671 WiZ jto :is using a secure connection",
+            "730 WiZ :jto!~jto@tolsun.oulu.fi,trillian",
+            "999 WiZ unknown :Numeric unknown to RFC 2812",
+            "000 WiZ",
+        ];
+
+        for data in &corpus {
+            for prefix in &[":irc.example.com ", "@time=now :irc.example.com "] {
+                let line = format!("{}{}", prefix, data);
+
+                let reply = Message::from_str(&line).unwrap();
+                match reply.body() {
+                    &Body::Reply(..) => {},
+                    other => panic!("{:?} parsed to {:?}", line, other),
+                }
+                assert_eq!(Message::from_str(&reply.to_string()).unwrap(), reply);
+                assert_eq!(&reply.to_string()[..], &line[..]);
+            }
+
+            let reply = Reply::from_str(data).unwrap();
+            assert_eq!(&reply.to_string()[..], *data);
+        }
+    }
+
+    #[test]
+    fn test_reply_code() {
+        for c in 0..1000 {
+            let code = ReplyCode::from_u16(c).unwrap();
+            assert_eq!(code.as_u16(), c);
+        }
+        assert_eq!(ReplyCode::from_u16(5), Some(ReplyCode::RPL_ISUPPORT));
+        assert_eq!(ReplyCode::from_u16(730), Some(ReplyCode::RPL_MONONLINE));
+        assert_eq!(ReplyCode::from_u16(999), Some(ReplyCode::Other(999)));
+        assert_eq!(ReplyCode::from_u16(1000), None);
+
+        assert!(ReplyCode::RPL_WHOISACCOUNT.is_known());
+        assert!(!ReplyCode::Other(999).is_known());
+        assert_eq!(&ReplyCode::Other(42).to_string()[..], "042");
+    }
+
+    #[test]
+    fn test_reply_unknown_numeric() {
+        let reply = Reply::from_str("799 WiZ #rust jto :Some extension").unwrap();
+        assert_eq!(reply.code(), ReplyCode::Other(799));
+        assert_eq!(reply.target(), "WiZ");
+        assert_eq!(reply.parameters().collect::<Vec<&str>>(), vec!["#rust", "jto", "Some extension"]);
+
+        // Only three-digit numerics are replies
+        for line in &[":irc.example.com 42 WiZ :Hello", ":irc.example.com 1000 WiZ :Hello"] {
+            match Message::from_str(line).unwrap().body() {
+                &Body::Unrecognized(..) => {},
+                other => panic!("{:?} parsed to {:?}", line, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_reply_user_modes() {
+        let modes = Reply::from_str("221 WiZ +iw").unwrap().user_modes().unwrap().unwrap();
+        assert_eq!(&modes.to_string()[..], "+iw");
+
+        assert!(Reply::from_str("221 WiZ").unwrap().user_modes().unwrap().is_err());
+        assert!(Reply::from_str("401 WiZ nobody :No such nick/channel").unwrap().user_modes().is_none());
     }
 }
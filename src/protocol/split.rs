@@ -0,0 +1,268 @@
+//! Splitting long text into several `PRIVMSG` or `NOTICE` messages
+//!
+//! Servers relay a message with the prefix of the sender prepended and cut
+//! everything beyond 512 bytes, so the room left for the text depends on our
+//! own `nick!user@host`, the command and the target.
+
+use protocol::codec::MAX_MESSAGE_LENGTH;
+use protocol::format::sequence_len;
+use protocol::command::{CMD_PRIVMSG, CMD_NOTICE, PrivmsgCommand, NoticeCommand};
+use protocol::message::{Message, Body};
+
+/// Room reserved for the prefix added by the server when our own
+/// `nick!user@host` is not known
+pub const DEFAULT_SOURCE_RESERVE: usize = 100;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum SplitCommand {
+    Privmsg,
+    Notice,
+}
+
+/// Splits text into messages which won't be truncated when relayed
+///
+/// The text is split on line breaks first, then long lines are split on the
+/// last space which fits. A line without spaces is split anywhere, but never
+/// inside a UTF-8 character or a formatting sequence such as a color code.
+/// Empty lines are skipped.
+///
+/// Tags are attached to every message. They have a separate length limit
+/// and don't take room from the text.
+///
+/// ```
+/// use irc::protocol::split::MessageSplitter;
+///
+/// let text = "Lorem ipsum dolor sit amet";
+/// let splitter = MessageSplitter::new().source("abc!abc@example.com").max_length(60);
+/// let lines: Vec<String> = splitter.privmsg("#rust", text).map(|m| m.to_string()).collect();
+/// assert_eq!(lines, vec!["PRIVMSG #rust :Lorem ipsum dolor sit", "PRIVMSG #rust :amet"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageSplitter<'a> {
+    tags: Option<&'a str>,
+    source: Option<&'a str>,
+    max_length: usize,
+}
+
+impl<'a> MessageSplitter<'a> {
+    pub fn new() -> MessageSplitter<'a> {
+        MessageSplitter {
+            tags: None,
+            source: None,
+            max_length: MAX_MESSAGE_LENGTH,
+        }
+    }
+
+    /// Our own prefix as the server will relay it, `nick!user@host`
+    pub fn source(mut self, source: &'a str) -> MessageSplitter<'a> {
+        self.source = Some(source);
+        self
+    }
+
+    /// Escaped tags to attach to every message, without the leading `@`
+    pub fn tags(mut self, tags: &'a str) -> MessageSplitter<'a> {
+        self.tags = Some(tags);
+        self
+    }
+
+    /// Maximum length of a relayed message, including `CR-LF`. Defaults to
+    /// `MAX_MESSAGE_LENGTH`
+    pub fn max_length(mut self, len: usize) -> MessageSplitter<'a> {
+        self.max_length = len;
+        self
+    }
+
+    /// Number of bytes of text which fit in a single message
+    pub fn text_limit(&self, command: &str, target: &str) -> usize {
+        let source_len = self.source.map(|s| s.len()).unwrap_or(DEFAULT_SOURCE_RESERVE);
+
+        // ":source COMMAND target :text\r\n"
+        let overhead = 1 + source_len + 1 + command.len() + 1 + target.len() + 2 + 2;
+        self.max_length.saturating_sub(overhead)
+    }
+
+    /// Split text into `PRIVMSG` messages
+    pub fn privmsg(&self, target: &'a str, text: &'a str) -> SplitMessages<'a> {
+        self.split(SplitCommand::Privmsg, target, text)
+    }
+
+    /// Split text into `NOTICE` messages
+    pub fn notice(&self, target: &'a str, text: &'a str) -> SplitMessages<'a> {
+        self.split(SplitCommand::Notice, target, text)
+    }
+
+    fn split(&self, command: SplitCommand, target: &'a str, text: &'a str) -> SplitMessages<'a> {
+        let cmd = match command {
+            SplitCommand::Privmsg => CMD_PRIVMSG,
+            SplitCommand::Notice => CMD_NOTICE,
+        };
+
+        SplitMessages {
+            command: command,
+            tags: self.tags,
+            target: target,
+            rest: text,
+            limit: self.text_limit(cmd, target),
+        }
+    }
+}
+
+impl<'a> Default for MessageSplitter<'a> {
+    fn default() -> MessageSplitter<'a> {
+        MessageSplitter::new()
+    }
+}
+
+/// Iterator of messages created by `MessageSplitter`
+pub struct SplitMessages<'a> {
+    command: SplitCommand,
+    tags: Option<&'a str>,
+    target: &'a str,
+    rest: &'a str,
+    limit: usize,
+}
+
+impl<'a> SplitMessages<'a> {
+    fn next_chunk(&mut self) -> Option<&'a str> {
+        loop {
+            let rest = self.rest;
+            if rest.is_empty() {
+                return None;
+            }
+
+            let (line, after_line) = match rest.find('\n') {
+                None => (rest, rest.len()),
+                Some(idx) => (&rest[..idx], idx + 1),
+            };
+            let line = line.strip_suffix('\r').unwrap_or(line);
+
+            if line.is_empty() {
+                self.rest = &rest[after_line..];
+                continue;
+            }
+
+            if line.len() <= self.limit {
+                self.rest = &rest[after_line..];
+                return Some(line);
+            }
+
+            let (end, next) = find_split(line, self.limit);
+            self.rest = &rest[next..];
+            return Some(&line[..end]);
+        }
+    }
+}
+
+impl<'a> Iterator for SplitMessages<'a> {
+    type Item = Message<'a>;
+
+    fn next(&mut self) -> Option<Message<'a>> {
+        let chunk = match self.next_chunk() {
+            None => return None,
+            Some(c) => c,
+        };
+
+        let body = match self.command {
+            SplitCommand::Privmsg => Body::command(PrivmsgCommand::new(self.target, chunk)),
+            SplitCommand::Notice => Body::command(NoticeCommand::new(self.target, chunk)),
+        };
+
+        Some(Message::with_tags(self.tags, None, body))
+    }
+}
+
+/// Find where to split a line longer than `limit`
+///
+/// Returns the end of the chunk and the start of the rest.
+fn find_split(line: &str, limit: usize) -> (usize, usize) {
+    let mut pos = 0;
+    let mut last_space = None;
+
+    while pos < line.len() {
+        let unit = match sequence_len(&line[pos..]) {
+            0 => line[pos..].chars().next().map(|c| c.len_utf8()).unwrap_or(1),
+            n => n,
+        };
+
+        if pos + unit > limit {
+            break;
+        }
+
+        if pos > 0 && line.as_bytes()[pos] == b' ' {
+            last_space = Some(pos);
+        }
+        pos += unit;
+    }
+
+    match last_space {
+        Some(space) => (space, space + 1),
+        None if pos == 0 => {
+            // Not even one character fits, send it anyway to make progress
+            let len = line.chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+            (len, len)
+        },
+        None => (pos, pos),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::Body;
+    use protocol::command::Command;
+
+    fn texts<'a>(msgs: &'a [Message<'a>]) -> Vec<&'a str> {
+        msgs.iter().map(|m| match m.body() {
+            &Body::Command(Command::Privmsg(ref p)) => p.message(),
+            &Body::Command(Command::Notice(ref n)) => n.message(),
+            other => panic!("Unexpected body {:?}", other),
+        }).collect()
+    }
+
+    #[test]
+    fn test_split_length() {
+        let source = "abc!abc@example.com";
+        let text = ["lorem ipsum dolor"; 100].join(" ");
+
+        let splitter = MessageSplitter::new().source(source);
+        let msgs: Vec<Message> = splitter.privmsg("#rust", &text).collect();
+        assert!(msgs.len() > 1);
+
+        for msg in &msgs {
+            let relayed = format!(":{} {}\r\n", source, msg);
+            assert!(relayed.len() <= MAX_MESSAGE_LENGTH, "{:?} is too long", relayed);
+        }
+
+        assert_eq!(texts(&msgs).join(" "), text);
+    }
+
+    #[test]
+    fn test_split_lines() {
+        let msgs: Vec<Message> = MessageSplitter::new().notice("Wiz", "first\r\n\nsecond\n").collect();
+        assert_eq!(texts(&msgs), vec!["first", "second"]);
+        assert_eq!(MessageSplitter::new().notice("Wiz", "").count(), 0);
+    }
+
+    #[test]
+    fn test_split_utf8() {
+        let text = "\u{732b}".repeat(100);
+        let splitter = MessageSplitter::new().source("a").max_length(50);
+
+        let msgs: Vec<Message> = splitter.privmsg("#rust", &text).collect();
+        for t in texts(&msgs) {
+            assert!(t.len() <= splitter.text_limit("PRIVMSG", "#rust"));
+        }
+        assert_eq!(texts(&msgs).concat(), text);
+    }
+
+    #[test]
+    fn test_split_format() {
+        let text = "aaaaaaaa\x0312,04bbbb\x04FF00FFcc";
+        let splitter = MessageSplitter::new().source("a").max_length(1 + 1 + 1 + 7 + 1 + 1 + 2 + 2 + 10);
+        assert_eq!(splitter.text_limit("PRIVMSG", "#"), 10);
+
+        let msgs: Vec<Message> = splitter.privmsg("#", text).collect();
+        assert_eq!(texts(&msgs), vec!["aaaaaaaa", "\x0312,04bbbb", "\x04FF00FFcc"]);
+    }
+}
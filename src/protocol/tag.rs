@@ -0,0 +1,344 @@
+use std::fmt;
+use std::borrow::Cow;
+
+/// A single IRCv3 message tag
+///
+/// ```plain
+/// tag          = key [ "=" escaped_value ]
+/// key          = [ client_prefix ] [ vendor "/" ] key_name
+/// client_prefix = "+"
+/// ```
+///
+/// The value is stored unescaped. It only allocates if the value on the wire
+/// contained escape sequences.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MessageTag<'a> {
+    key: &'a str,
+    value: Option<Cow<'a, str>>,
+}
+
+impl<'a> MessageTag<'a> {
+    /// Create a tag with an unescaped value
+    pub fn new(key: &'a str, value: Option<&'a str>) -> MessageTag<'a> {
+        MessageTag {
+            key: key,
+            value: value.map(Cow::Borrowed),
+        }
+    }
+
+    /// Parse a tag in its wire form, `key[=escaped value]`
+    pub fn parse(s: &'a str) -> MessageTag<'a> {
+        match s.find('=') {
+            None => MessageTag {
+                key: s,
+                value: None,
+            },
+            Some(idx) => {
+                let value = &s[idx+1..];
+
+                MessageTag {
+                    key: &s[..idx],
+                    // An empty value is equivalent to a missing value
+                    value: if value.is_empty() { None } else { Some(unescape_tag_value(value)) },
+                }
+            }
+        }
+    }
+
+    /// The full key, including the client-only prefix and the vendor
+    pub fn key(&self) -> &'a str {
+        self.key
+    }
+
+    /// The key without the client-only prefix and the vendor
+    pub fn name(&self) -> &'a str {
+        let key = self.key_without_client_prefix();
+        match key.rfind('/') {
+            None => key,
+            Some(idx) => &key[idx+1..],
+        }
+    }
+
+    /// The vendor of the tag, for example `example.com` in `example.com/foo`
+    pub fn vendor(&self) -> Option<&'a str> {
+        let key = self.key_without_client_prefix();
+        key.rfind('/').map(|idx| &key[..idx])
+    }
+
+    /// Client-only tags are prefixed with `+`. Servers relay them untouched
+    /// to other clients
+    pub fn is_client_only(&self) -> bool {
+        self.key.starts_with('+')
+    }
+
+    /// The unescaped value
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_ref().map(|v| &v[..])
+    }
+
+    fn key_without_client_prefix(&self) -> &'a str {
+        if self.is_client_only() {
+            &self.key[1..]
+        } else {
+            self.key
+        }
+    }
+}
+
+impl<'a> fmt::Display for MessageTag<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{}", self.key));
+
+        match self.value {
+            Some(ref v) if !v.is_empty() => write!(f, "={}", escape_tag_value(v)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Tags section of a message, without the leading `@`
+///
+/// This is a zero-copy view of the escaped data on the wire, tags are parsed
+/// on access.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MessageTags<'a> {
+    data: &'a str,
+}
+
+impl<'a> MessageTags<'a> {
+    pub fn wrap(s: &'a str) -> MessageTags<'a> {
+        MessageTags {
+            data: s,
+        }
+    }
+
+    /// Get the raw escaped data
+    pub fn get(&self) -> &'a str {
+        self.data
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Iterate all tags
+    pub fn iter(&self) -> MessageTagIter<'a> {
+        MessageTagIter {
+            data: self.data,
+        }
+    }
+
+    /// Find a tag by its full key. If the key appears more than once the
+    /// last one wins, as the specification requires
+    pub fn find(&self, key: &str) -> Option<MessageTag<'a>> {
+        self.iter().filter(|t| t.key() == key).last()
+    }
+
+    /// Get the unescaped value of a tag by its full key
+    ///
+    /// Returns `Some("")` if the tag is present without a value.
+    pub fn value(&self, key: &str) -> Option<Cow<'a, str>> {
+        self.find(key).map(|t| t.value.unwrap_or(Cow::Borrowed("")))
+    }
+
+    /// Check whether the tag exists
+    pub fn contains(&self, key: &str) -> bool {
+        self.iter().any(|t| t.key() == key)
+    }
+
+    /// Iterate tags sent by the server
+    pub fn server_tags(&self) -> ServerTagIter<'a> {
+        ServerTagIter {
+            inner: self.iter(),
+        }
+    }
+
+    /// Iterate client-only tags (`+` prefixed)
+    pub fn client_tags(&self) -> ClientTagIter<'a> {
+        ClientTagIter {
+            inner: self.iter(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for MessageTags<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.data)
+    }
+}
+
+pub struct MessageTagIter<'a> {
+    data: &'a str,
+}
+
+impl<'a> Iterator for MessageTagIter<'a> {
+    type Item = MessageTag<'a>;
+
+    fn next(&mut self) -> Option<MessageTag<'a>> {
+        loop {
+            if self.data.is_empty() {
+                return None;
+            }
+
+            let cur = match self.data.find(';') {
+                None => {
+                    let cur = self.data;
+                    self.data = &self.data[self.data.len()..];
+                    cur
+                },
+                Some(idx) => {
+                    let cur = &self.data[..idx];
+                    self.data = &self.data[idx+1..];
+                    cur
+                }
+            };
+
+            // Skip empty tags in sequences like `a;;b`
+            if !cur.is_empty() {
+                return Some(MessageTag::parse(cur));
+            }
+        }
+    }
+}
+
+pub struct ServerTagIter<'a> {
+    inner: MessageTagIter<'a>,
+}
+
+impl<'a> Iterator for ServerTagIter<'a> {
+    type Item = MessageTag<'a>;
+
+    fn next(&mut self) -> Option<MessageTag<'a>> {
+        self.inner.by_ref().find(|t| !t.is_client_only())
+    }
+}
+
+pub struct ClientTagIter<'a> {
+    inner: MessageTagIter<'a>,
+}
+
+impl<'a> Iterator for ClientTagIter<'a> {
+    type Item = MessageTag<'a>;
+
+    fn next(&mut self) -> Option<MessageTag<'a>> {
+        self.inner.by_ref().find(|t| t.is_client_only())
+    }
+}
+
+/// Escape a tag value for the wire
+///
+/// | Character       | Escaped |
+/// |-----------------|---------|
+/// | `;`             | `\:`    |
+/// | `SPACE`         | `\s`    |
+/// | `\`             | `\\`    |
+/// | `CR`            | `\r`    |
+/// | `LF`            | `\n`    |
+pub fn escape_tag_value<'a>(s: &'a str) -> Cow<'a, str> {
+    if !s.contains(&[';', ' ', '\\', '\r', '\n'][..]) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut escaped = String::with_capacity(s.len() + 8);
+    for c in s.chars() {
+        match c {
+            ';' => escaped.push_str("\\:"),
+            ' ' => escaped.push_str("\\s"),
+            '\\' => escaped.push_str("\\\\"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+/// Unescape a tag value from the wire
+///
+/// Invalid escapes drop the `\`, and a trailing lone `\` is removed.
+pub fn unescape_tag_value<'a>(s: &'a str) -> Cow<'a, str> {
+    if !s.contains('\\') {
+        return Cow::Borrowed(s);
+    }
+
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(':') => unescaped.push(';'),
+            Some('s') => unescaped.push(' '),
+            Some('r') => unescaped.push('\r'),
+            Some('n') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+
+    Cow::Owned(unescaped)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tag_escape_roundtrip() {
+        let value = "semi;colon space\\backslash\r\nend";
+        let escaped = escape_tag_value(value);
+        assert_eq!(&escaped[..], "semi\\:colon\\sspace\\\\backslash\\r\\nend");
+        assert_eq!(&unescape_tag_value(&escaped)[..], value);
+    }
+
+    #[test]
+    fn test_tag_unescape_invalid() {
+        assert_eq!(&unescape_tag_value("\\b\\")[..], "b");
+        assert_eq!(&unescape_tag_value("plain")[..], "plain");
+    }
+
+    #[test]
+    fn test_tags_iter() {
+        let tags = MessageTags::wrap("aaa=bbb;ccc;example.com/ddd=eee;+example.com/fff=g\\sh");
+
+        let tag_list: Vec<MessageTag> = tags.iter().collect();
+        assert_eq!(tag_list.len(), 4);
+
+        assert_eq!(tag_list[0].key(), "aaa");
+        assert_eq!(tag_list[0].value(), Some("bbb"));
+        assert_eq!(tag_list[1].key(), "ccc");
+        assert_eq!(tag_list[1].value(), None);
+        assert_eq!(tag_list[2].vendor(), Some("example.com"));
+        assert_eq!(tag_list[2].name(), "ddd");
+        assert!(tag_list[3].is_client_only());
+        assert_eq!(tag_list[3].name(), "fff");
+        assert_eq!(tag_list[3].value(), Some("g h"));
+
+        assert_eq!(tags.server_tags().count(), 3);
+        assert_eq!(tags.client_tags().map(|t| t.key()).collect::<Vec<&str>>(),
+                   vec!["+example.com/fff"]);
+    }
+
+    #[test]
+    fn test_tags_find() {
+        let tags = MessageTags::wrap("time=2011-10-19T16:40:51.620Z;msgid=a;msgid=b;empty=");
+
+        assert_eq!(tags.value("time").as_ref().map(|v| &v[..]), Some("2011-10-19T16:40:51.620Z"));
+        assert_eq!(tags.value("msgid").as_ref().map(|v| &v[..]), Some("b"));
+        assert_eq!(tags.value("empty").as_ref().map(|v| &v[..]), Some(""));
+        assert!(tags.value("missing").is_none());
+    }
+
+    #[test]
+    fn test_tag_display() {
+        let tag = MessageTag::new("+draft/reply", Some("a b;c"));
+        assert_eq!(&tag.to_string()[..], "+draft/reply=a\\sb\\:c");
+
+        let tag = MessageTag::new("flag", None);
+        assert_eq!(&tag.to_string()[..], "flag");
+    }
+}
@@ -1,11 +1,293 @@
-use std::str::FromStr;
+//! User modes, as defined in RFC 2812
+//!
+//! ```plain
+//! MODE WiZ +iw-o
+//! ```
 
+use std::fmt;
+use std::str::{FromStr, Chars};
+
+use protocol::chanmode::ModeSign;
+use protocol::message::{ParseMessageError, ParseMessageErrorKind};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum UserMode {
+    /// `a`, set with `AWAY` only
     Away,
+
+    /// `i`
     Invisible,
+
+    /// `w`
     ReceiveWallops,
+
+    /// `r`
     RestrictedUserConnection,
+
+    /// `o`, set with `OPER` only
     Operator,
+
+    /// `O`
     LocalOperator,
+
+    /// `s`
     ReceiveServerNotice,
+
+    /// A mode letter not defined by RFC 2812
+    Other(char),
+}
+
+impl UserMode {
+    pub fn from_char(c: char) -> UserMode {
+        match c {
+            'a' => UserMode::Away,
+            'i' => UserMode::Invisible,
+            'w' => UserMode::ReceiveWallops,
+            'r' => UserMode::RestrictedUserConnection,
+            'o' => UserMode::Operator,
+            'O' => UserMode::LocalOperator,
+            's' => UserMode::ReceiveServerNotice,
+            c => UserMode::Other(c),
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match *self {
+            UserMode::Away => 'a',
+            UserMode::Invisible => 'i',
+            UserMode::ReceiveWallops => 'w',
+            UserMode::RestrictedUserConnection => 'r',
+            UserMode::Operator => 'o',
+            UserMode::LocalOperator => 'O',
+            UserMode::ReceiveServerNotice => 's',
+            UserMode::Other(c) => c,
+        }
+    }
+}
+
+impl fmt::Display for UserMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
+impl FromStr for UserMode {
+    type Err = ParseMessageError;
+
+    fn from_str(s: &str) -> Result<UserMode, ParseMessageError> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c != '+' && c != '-' => Ok(UserMode::from_char(c)),
+            _ => Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                            "User mode should be a single letter")),
+        }
+    }
+}
+
+/// A change of a user mode, such as `+i`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct UserModeChange {
+    sign: ModeSign,
+    mode: UserMode,
+}
+
+impl UserModeChange {
+    pub fn new(sign: ModeSign, mode: UserMode) -> UserModeChange {
+        UserModeChange {
+            sign: sign,
+            mode: mode,
+        }
+    }
+
+    pub fn sign(&self) -> ModeSign {
+        self.sign
+    }
+
+    pub fn mode(&self) -> UserMode {
+        self.mode
+    }
+}
+
+impl fmt::Display for UserModeChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.sign, self.mode)
+    }
+}
+
+/// Iterator of the changes in a user mode string such as `+iw-o`
+///
+/// Letters before any sign are taken as added.
+pub struct UserModeChangeIter<'a> {
+    chars: Chars<'a>,
+    sign: ModeSign,
+}
+
+impl<'a> UserModeChangeIter<'a> {
+    pub fn new(s: &'a str) -> UserModeChangeIter<'a> {
+        UserModeChangeIter {
+            chars: s.chars(),
+            sign: ModeSign::Add,
+        }
+    }
+}
+
+impl<'a> Iterator for UserModeChangeIter<'a> {
+    type Item = UserModeChange;
+
+    fn next(&mut self) -> Option<UserModeChange> {
+        loop {
+            match self.chars.next() {
+                None => return None,
+                Some('+') => self.sign = ModeSign::Add,
+                Some('-') => self.sign = ModeSign::Remove,
+                Some(c) => return Some(UserModeChange::new(self.sign, UserMode::from_char(c))),
+            }
+        }
+    }
+}
+
+/// Format changes as a mode string, such as `+iw-o`
+pub fn format_user_mode_changes(changes: &[UserModeChange]) -> String {
+    let mut s = String::with_capacity(changes.len() * 2);
+    let mut sign = None;
+    for change in changes {
+        if sign != Some(change.sign) {
+            s.push(change.sign.as_char());
+            sign = Some(change.sign);
+        }
+        s.push(change.mode.as_char());
+    }
+    s
+}
+
+/// The set of modes currently enabled for a user
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct UserModeSet {
+    modes: Vec<UserMode>,
+}
+
+impl UserModeSet {
+    pub fn new() -> UserModeSet {
+        UserModeSet {
+            modes: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, mode: UserMode) -> bool {
+        self.modes.contains(&mode)
+    }
+
+    /// Enable a mode, returns `false` if it was already enabled
+    pub fn insert(&mut self, mode: UserMode) -> bool {
+        if self.contains(mode) {
+            false
+        } else {
+            self.modes.push(mode);
+            true
+        }
+    }
+
+    /// Disable a mode, returns `false` if it was not enabled
+    pub fn remove(&mut self, mode: UserMode) -> bool {
+        match self.modes.iter().position(|&m| m == mode) {
+            None => false,
+            Some(idx) => {
+                self.modes.remove(idx);
+                true
+            }
+        }
+    }
+
+    /// Apply a change, returns `false` if it changed nothing
+    pub fn apply(&mut self, change: &UserModeChange) -> bool {
+        match change.sign {
+            ModeSign::Add => self.insert(change.mode),
+            ModeSign::Remove => self.remove(change.mode),
+        }
+    }
+
+    /// Apply all the changes of a mode string such as `+iw-o`
+    pub fn apply_str(&mut self, s: &str) {
+        for change in UserModeChangeIter::new(s) {
+            self.apply(&change);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modes.is_empty()
+    }
+
+    pub fn iter(&self) -> ::std::slice::Iter<'_, UserMode> {
+        self.modes.iter()
+    }
+}
+
+impl fmt::Display for UserModeSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "+"));
+        for mode in &self.modes {
+            try!(write!(f, "{}", mode));
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for UserModeSet {
+    type Err = ParseMessageError;
+
+    /// Parse the modes of `RPL_UMODEIS`, such as `+iw`
+    fn from_str(s: &str) -> Result<UserModeSet, ParseMessageError> {
+        if s.contains('-') {
+            return Err(ParseMessageError::new(ParseMessageErrorKind::InvalidParam,
+                                              "User mode set should not remove modes"));
+        }
+
+        let mut set = UserModeSet::new();
+        set.apply_str(s);
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::chanmode::ModeSign;
+
+    #[test]
+    fn test_user_mode_letters() {
+        for c in "aiwroOsx".chars() {
+            let mode = UserMode::from_char(c);
+            assert_eq!(mode.as_char(), c);
+            assert_eq!(c.to_string().parse::<UserMode>().unwrap(), mode);
+        }
+        assert_eq!(UserMode::from_char('x'), UserMode::Other('x'));
+        assert!("iw".parse::<UserMode>().is_err());
+        assert!("+".parse::<UserMode>().is_err());
+    }
+
+    #[test]
+    fn test_user_mode_changes() {
+        let changes: Vec<UserModeChange> = UserModeChangeIter::new("+iw-o").collect();
+        assert_eq!(changes, vec![
+            UserModeChange::new(ModeSign::Add, UserMode::Invisible),
+            UserModeChange::new(ModeSign::Add, UserMode::ReceiveWallops),
+            UserModeChange::new(ModeSign::Remove, UserMode::Operator),
+        ]);
+        assert_eq!(&format_user_mode_changes(&changes)[..], "+iw-o");
+    }
+
+    #[test]
+    fn test_user_mode_set() {
+        let mut set: UserModeSet = "+iwo".parse().unwrap();
+        assert!(set.contains(UserMode::Operator));
+
+        set.apply_str("-o+s");
+        assert!(!set.contains(UserMode::Operator));
+        assert!(set.contains(UserMode::ReceiveServerNotice));
+        assert_eq!(&set.to_string()[..], "+iws");
+
+        assert!("+i-w".parse::<UserModeSet>().is_err());
+        assert_eq!(&UserModeSet::new().to_string()[..], "+");
+    }
 }
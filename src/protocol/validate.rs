@@ -0,0 +1,422 @@
+//! Validation of outgoing messages against the RFC 2812 grammar
+//!
+//! ```plain
+//! message    =  [ ":" prefix SPACE ] command [ params ] crlf
+//! command    =  1*letter / 3digit
+//! params     =  *14( SPACE middle ) [ SPACE ":" trailing ]
+//! middle     =  nospcrlfcl *( ":" / nospcrlfcl )
+//! trailing   =  *( ":" / " " / nospcrlfcl )
+//! nospcrlfcl =  %x01-09 / %x0B-0C / %x0E-1F / %x21-39 / %x3B-FF
+//! ```
+//!
+//! The constructors of the commands accept any string, use `MessageBuilder`
+//! or `validate_message` before sending data which comes from users.
+
+use std::fmt;
+use std::fmt::Write;
+use std::error;
+
+use protocol::codec::{MAX_MESSAGE_LENGTH, MAX_TAGS_LENGTH};
+use protocol::message::{IrcMessage, Message, RawMessage, LastParam};
+use protocol::tag::escape_tag_value;
+
+/// Maximum number of parameters in a message
+pub const MAX_PARAMS: usize = 15;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ValidationError {
+    /// The command is neither letters nor a 3 digit numeric
+    InvalidCommand,
+
+    /// The prefix is empty or contains a space, `NUL`, `CR` or `LF`
+    InvalidPrefix,
+
+    /// The tag key is empty or contains characters not allowed in keys
+    InvalidTagKey,
+
+    /// The parameter at the index contains `NUL`, `CR` or `LF`
+    IllegalCharacter(usize, char),
+
+    /// The parameter at the index is empty, contains a space or starts with
+    /// `:` but is not the last one
+    InvalidMiddleParam(usize),
+
+    /// The target at the index is empty, or contains a space or a leading `:`
+    InvalidTarget(usize),
+
+    /// More than `MAX_PARAMS` parameters
+    TooManyParams(usize),
+
+    /// The serialized message exceeds the length limits, carries its length
+    TooLong(usize),
+
+    /// The message would not parse back to the same message
+    Ambiguous,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValidationError::InvalidCommand => write!(f, "Invalid command"),
+            ValidationError::InvalidPrefix => write!(f, "Invalid prefix"),
+            ValidationError::InvalidTagKey => write!(f, "Invalid tag key"),
+            ValidationError::IllegalCharacter(idx, c) =>
+                write!(f, "Parameter {} contains illegal character {:?}", idx, c),
+            ValidationError::InvalidMiddleParam(idx) => write!(f, "Parameter {} is not a valid middle parameter", idx),
+            ValidationError::InvalidTarget(idx) => write!(f, "Parameter {} is not a valid target", idx),
+            ValidationError::TooManyParams(n) => write!(f, "Too many parameters: {}", n),
+            ValidationError::TooLong(len) => write!(f, "Message too long: {} bytes", len),
+            ValidationError::Ambiguous => write!(f, "Message does not parse back to itself"),
+        }
+    }
+}
+
+impl error::Error for ValidationError {}
+
+fn is_illegal_char(c: char) -> bool {
+    c == '\0' || c == '\r' || c == '\n'
+}
+
+/// Check a command name, `1*letter / 3digit`
+pub fn validate_command(cmd: &str) -> Result<(), ValidationError> {
+    let valid = if cmd.starts_with(|c: char| c.is_ascii_digit()) {
+        cmd.len() == 3 && cmd.bytes().all(|b| b.is_ascii_digit())
+    } else {
+        !cmd.is_empty() && cmd.bytes().all(|b| b.is_ascii_alphabetic())
+    };
+
+    if valid { Ok(()) } else { Err(ValidationError::InvalidCommand) }
+}
+
+/// Check a prefix, without the leading `:`
+pub fn validate_prefix(prefix: &str) -> Result<(), ValidationError> {
+    if prefix.is_empty() || prefix.contains(|c| c == ' ' || is_illegal_char(c)) {
+        Err(ValidationError::InvalidPrefix)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a tag key, `[ "+" ] [ vendor "/" ] 1*( ALPHA / DIGIT / "-" )`
+pub fn validate_tag_key(key: &str) -> Result<(), ValidationError> {
+    let key = key.strip_prefix('+').unwrap_or(key);
+    let name = match key.rfind('/') {
+        Some(idx) => {
+            let vendor = &key[..idx];
+            if vendor.is_empty() || !vendor.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'.') {
+                return Err(ValidationError::InvalidTagKey);
+            }
+            &key[idx+1..]
+        },
+        None => key,
+    };
+
+    if name.is_empty() || !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-') {
+        Err(ValidationError::InvalidTagKey)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a middle parameter at `index`
+pub fn validate_middle(param: &str, index: usize) -> Result<(), ValidationError> {
+    try!(validate_trailing(param, index));
+
+    if param.is_empty() || param.starts_with(':') || param.contains(' ') {
+        Err(ValidationError::InvalidMiddleParam(index))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a trailing parameter at `index`, which may contain spaces
+pub fn validate_trailing(param: &str, index: usize) -> Result<(), ValidationError> {
+    match param.chars().find(|&c| is_illegal_char(c)) {
+        Some(c) => Err(ValidationError::IllegalCharacter(index, c)),
+        None => Ok(()),
+    }
+}
+
+/// Check a message target at `index`, such as the target of `PRIVMSG`
+///
+/// A target is a comma separated list of channels, nicknames or masks, none
+/// of which may be empty.
+pub fn validate_target(target: &str, index: usize) -> Result<(), ValidationError> {
+    try!(validate_trailing(target, index));
+
+    if target.is_empty() || target.starts_with(':') || target.contains(' ')
+        || target.split(',').any(|t| t.is_empty())
+    {
+        Err(ValidationError::InvalidTarget(index))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check every part of a raw message
+///
+/// All parameters but the last one must be valid middle parameters.
+pub fn validate_raw_message(raw: &RawMessage) -> Result<(), ValidationError> {
+    try!(validate_command(raw.command()));
+
+    if let Some(prefix) = raw.prefix() {
+        try!(validate_prefix(prefix));
+    }
+
+    for tag in raw.tags().iter() {
+        try!(validate_tag_key(tag.key()));
+    }
+
+    let params: Vec<&str> = raw.parameters().collect();
+    if params.len() > MAX_PARAMS {
+        return Err(ValidationError::TooManyParams(params.len()));
+    }
+
+    for (idx, param) in params.iter().enumerate() {
+        if idx + 1 == params.len() {
+            try!(validate_trailing(param, idx));
+        } else {
+            try!(validate_middle(param, idx));
+        }
+    }
+
+    check_length(&raw.to_string())
+}
+
+/// Check that a message can be sent as is
+///
+/// This catches `CR-LF` injection and parameters which were passed to a
+/// constructor but would be read back differently, such as a nickname
+/// containing a space.
+pub fn validate_message(msg: &Message) -> Result<(), ValidationError> {
+    if let Some(prefix) = msg.prefix() {
+        try!(validate_prefix(prefix));
+    }
+
+    let serialized = msg.to_string();
+
+    let raw = match RawMessage::from_str(&serialized) {
+        Ok(raw) => raw,
+        Err(..) => return Err(ValidationError::Ambiguous),
+    };
+    try!(validate_raw_message(&raw));
+
+    match Message::from_raw(&raw) {
+        Ok(ref parsed) if parsed == msg => Ok(()),
+        _ => Err(ValidationError::Ambiguous),
+    }
+}
+
+fn check_length(line: &str) -> Result<(), ValidationError> {
+    let body = if line.starts_with('@') {
+        let tags_len = line.find(' ').map(|idx| idx + 1).unwrap_or(line.len());
+        if tags_len > MAX_TAGS_LENGTH {
+            return Err(ValidationError::TooLong(tags_len));
+        }
+        &line[tags_len..]
+    } else {
+        line
+    };
+
+    if body.len() + 2 > MAX_MESSAGE_LENGTH {
+        Err(ValidationError::TooLong(body.len() + 2))
+    } else {
+        Ok(())
+    }
+}
+
+/// Builds a `RawMessage` from parts, validating each of them
+///
+/// The last parameter is written with a leading `:` only if it needs one,
+/// unless `trailing` is used.
+///
+/// ```
+/// use irc::protocol::validate::MessageBuilder;
+///
+/// let msg = MessageBuilder::new("PRIVMSG").param("#rust").param("Hello world").build().unwrap();
+/// assert_eq!(&msg.to_string()[..], "PRIVMSG #rust :Hello world");
+///
+/// assert!(MessageBuilder::new("PRIVMSG").param("#rust").param("Hi\r\nQUIT").build().is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageBuilder<'a> {
+    tags: Vec<(&'a str, Option<&'a str>)>,
+    prefix: Option<&'a str>,
+    command: &'a str,
+    params: Vec<&'a str>,
+    force_trailing: bool,
+}
+
+impl<'a> MessageBuilder<'a> {
+    pub fn new(command: &'a str) -> MessageBuilder<'a> {
+        MessageBuilder {
+            tags: Vec::new(),
+            prefix: None,
+            command: command,
+            params: Vec::new(),
+            force_trailing: false,
+        }
+    }
+
+    /// Add a tag with an unescaped value
+    pub fn tag(mut self, key: &'a str, value: Option<&'a str>) -> MessageBuilder<'a> {
+        self.tags.push((key, value));
+        self
+    }
+
+    pub fn prefix(mut self, prefix: &'a str) -> MessageBuilder<'a> {
+        self.prefix = Some(prefix);
+        self
+    }
+
+    /// Append a parameter
+    pub fn param(mut self, param: &'a str) -> MessageBuilder<'a> {
+        self.params.push(param);
+        self
+    }
+
+    /// Append the last parameter, always written with a leading `:`
+    pub fn trailing(mut self, param: &'a str) -> MessageBuilder<'a> {
+        self.params.push(param);
+        self.force_trailing = true;
+        self
+    }
+
+    /// Validate the parts and build the message
+    pub fn build(&self) -> Result<RawMessage<'static>, ValidationError> {
+        try!(validate_command(self.command));
+
+        if let Some(prefix) = self.prefix {
+            try!(validate_prefix(prefix));
+        }
+
+        if self.params.len() > MAX_PARAMS {
+            return Err(ValidationError::TooManyParams(self.params.len()));
+        }
+
+        let mut line = String::new();
+
+        if !self.tags.is_empty() {
+            line.push('@');
+            for (idx, &(key, value)) in self.tags.iter().enumerate() {
+                try!(validate_tag_key(key));
+
+                if idx > 0 {
+                    line.push(';');
+                }
+                line.push_str(key);
+                if let Some(value) = value {
+                    write!(line, "={}", escape_tag_value(value)).unwrap();
+                }
+            }
+            line.push(' ');
+        }
+
+        if let Some(prefix) = self.prefix {
+            write!(line, ":{} ", prefix).unwrap();
+        }
+
+        line.push_str(self.command);
+
+        for (idx, param) in self.params.iter().enumerate() {
+            if idx + 1 < self.params.len() {
+                try!(validate_middle(param, idx));
+                write!(line, " {}", param).unwrap();
+            } else {
+                try!(validate_trailing(param, idx));
+                if self.force_trailing {
+                    write!(line, " :{}", param).unwrap();
+                } else {
+                    write!(line, " {}", LastParam(param)).unwrap();
+                }
+            }
+        }
+
+        try!(check_length(&line));
+
+        let raw = RawMessage::from_str(&line).expect("Validated message should be parsed");
+        Ok(raw.into_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use protocol::message::{Message, Body};
+    use protocol::command::{PrivmsgCommand, NickCommand};
+
+    #[test]
+    fn test_builder() {
+        let raw = MessageBuilder::new("PRIVMSG")
+                    .tag("+draft/reply", Some("a b"))
+                    .prefix("abc!abc@example.com")
+                    .param("#rust")
+                    .param("Hello")
+                    .build().unwrap();
+        assert_eq!(&raw.to_string()[..], "@+draft/reply=a\\sb :abc!abc@example.com PRIVMSG #rust Hello");
+
+        let raw = MessageBuilder::new("PRIVMSG").param("#rust").trailing("Hello").build().unwrap();
+        assert_eq!(&raw.to_string()[..], "PRIVMSG #rust :Hello");
+
+        for last in &["", ":)", "a b"] {
+            let raw = MessageBuilder::new("PRIVMSG").param("#rust").param(last).build().unwrap();
+            assert_eq!(raw.parameters().last(), Some(*last));
+        }
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        assert_eq!(MessageBuilder::new("PRIV MSG").build().unwrap_err(), ValidationError::InvalidCommand);
+        assert_eq!(MessageBuilder::new("01").build().unwrap_err(), ValidationError::InvalidCommand);
+        assert_eq!(MessageBuilder::new("PING").prefix("a b").build().unwrap_err(), ValidationError::InvalidPrefix);
+        assert_eq!(MessageBuilder::new("PING").tag("a;b", None).build().unwrap_err(), ValidationError::InvalidTagKey);
+        assert_eq!(MessageBuilder::new("PRIVMSG").param("#rust").param("Hi\r\nQUIT").build().unwrap_err(),
+                   ValidationError::IllegalCharacter(1, '\r'));
+        assert_eq!(MessageBuilder::new("PRIVMSG").param("#rust #c").param("Hi").build().unwrap_err(),
+                   ValidationError::InvalidMiddleParam(0));
+
+        let mut builder = MessageBuilder::new("PRIVMSG");
+        for _ in 0..16 {
+            builder = builder.param("a");
+        }
+        assert_eq!(builder.build().unwrap_err(), ValidationError::TooManyParams(16));
+
+        let long_text = "a".repeat(600);
+        match MessageBuilder::new("PRIVMSG").param("#rust").param(&long_text).build() {
+            Err(ValidationError::TooLong(..)) => {},
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_validate_target() {
+        assert!(validate_target("#rust,Wiz", 0).is_ok());
+        assert_eq!(validate_target("#rust,", 0), Err(ValidationError::InvalidTarget(0)));
+        assert_eq!(validate_target(":Wiz", 0), Err(ValidationError::InvalidTarget(0)));
+        assert_eq!(validate_target("Wiz\n", 0), Err(ValidationError::IllegalCharacter(0, '\n')));
+    }
+
+    #[test]
+    fn test_validate_message() {
+        let msg = Message::new(None, Body::command(PrivmsgCommand::new("#rust", "Hello :)")));
+        assert!(validate_message(&msg).is_ok());
+
+        let msg = Message::new(None, Body::command(PrivmsgCommand::new("#rust", "Hello\r\nQUIT :bye")));
+        assert_eq!(validate_message(&msg), Err(ValidationError::IllegalCharacter(1, '\r')));
+
+        let msg = Message::new(None, Body::command(PrivmsgCommand::new("#rust Wiz", "Hello")));
+        assert_eq!(validate_message(&msg), Err(ValidationError::Ambiguous));
+
+        let msg = Message::new(Some("a b"), Body::command(NickCommand::new("Wiz")));
+        assert_eq!(validate_message(&msg), Err(ValidationError::InvalidPrefix));
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(PrivmsgCommand::try_new("#rust", "Hello").is_ok());
+        assert_eq!(PrivmsgCommand::try_new("#rust", "Hi\r\nQUIT").unwrap_err(),
+                   ValidationError::IllegalCharacter(1, '\r'));
+        assert_eq!(PrivmsgCommand::try_new("#rust Wiz", "Hi").unwrap_err(), ValidationError::InvalidTarget(0));
+    }
+}